@@ -0,0 +1,16 @@
+//! Helpers shared by the benchmarks.
+
+/// A fixed permutation of `0 .. len`, so runs are comparable.
+pub fn shuffled(len: usize) -> Vec<usize> {
+    let mut result: Vec<usize> = (0 .. len).collect();
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+
+    for i in (1 .. len).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        result.swap(i, state as usize % (i + 1));
+    }
+
+    result
+}
@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, SamplingMode};
+use ownership::list_set::Set;
+use std::iter::FromIterator;
+use std::time::Duration;
+
+mod common;
+use common::shuffled;
+
+const LEN: usize = 100_000;
+
+// What `from_iter` used to do: one `insert`, and so one scan, per element.
+fn insert_each(elems: Vec<usize>) -> Set<usize> {
+    let mut result = Set::new();
+
+    for elem in elems {
+        result.insert(elem);
+    }
+
+    result
+}
+
+fn bench_from_iter(c: &mut Criterion) {
+    let sorted: Vec<usize> = (0 .. LEN).collect();
+    let shuffled = shuffled(LEN);
+
+    // The `insert_each` runs take seconds apiece, so take as few as
+    // Criterion allows.
+    let mut group = c.benchmark_group("from_iter_100k");
+    group.sample_size(10);
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(1));
+
+    group.bench_function("sorted/insert_each", |b| {
+        b.iter_batched(|| sorted.clone(), insert_each, BatchSize::LargeInput)
+    });
+    group.bench_function("sorted/from_iter", |b| {
+        b.iter_batched(|| sorted.clone(), Set::from_iter, BatchSize::LargeInput)
+    });
+    group.bench_function("sorted/from_sorted_unchecked", |b| {
+        b.iter_batched(|| sorted.clone(), Set::from_sorted_unchecked, BatchSize::LargeInput)
+    });
+    group.bench_function("shuffled/insert_each", |b| {
+        b.iter_batched(|| shuffled.clone(), insert_each, BatchSize::LargeInput)
+    });
+    group.bench_function("shuffled/from_iter", |b| {
+        b.iter_batched(|| shuffled.clone(), Set::from_iter, BatchSize::LargeInput)
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_from_iter);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+// Times churn with and without a node pool. How many allocations each
+// round makes is asserted in `tests/node_pool.rs`, which counts them with
+// the same rounds.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ownership::list_set::Set;
+
+// The number of short-lived elements inserted and removed per round.
+const BATCHES: &[usize] = &[16, 64, 256];
+
+// Inserts a batch of elements behind the long-lived ones, then removes it.
+fn churn(set: &mut Set<usize>, batch: usize) {
+    for elem in 0 .. batch {
+        set.insert(1_000 + elem);
+    }
+
+    for elem in 0 .. batch {
+        set.remove(&(1_000 + elem));
+    }
+}
+
+fn bench_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("churn");
+
+    for &batch in BATCHES {
+        let mut plain = Set::from_sorted_unchecked(0 .. 100);
+        let mut pooled = Set::with_node_pool(batch);
+        pooled.extend(0 .. 100);
+
+        group.bench_function(BenchmarkId::new("unpooled", batch), |b| {
+            b.iter(|| churn(&mut plain, batch))
+        });
+        group.bench_function(BenchmarkId::new("pooled", batch), |b| {
+            b.iter(|| churn(&mut pooled, batch))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_churn);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ownership::list_set::Set;
+use ownership::skip_set::SkipSet;
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+
+mod common;
+use common::shuffled;
+
+// `Set` is linear per lookup, so keep the sizes small enough for it.
+const SIZES: &[usize] = &[100, 1_000, 10_000];
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+
+    for &len in SIZES {
+        let elems = shuffled(len);
+
+        group.bench_with_input(BenchmarkId::new("Set", len), &elems, |b, elems| {
+            b.iter(|| {
+                let mut set = Set::new();
+                for &elem in elems { set.insert(elem); }
+                set
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("SkipSet", len), &elems, |b, elems| {
+            b.iter(|| {
+                let mut set = SkipSet::new();
+                for &elem in elems { set.insert(elem); }
+                set
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("BTreeSet", len), &elems, |b, elems| {
+            b.iter(|| {
+                let mut set = BTreeSet::new();
+                for &elem in elems { set.insert(elem); }
+                set
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_contains(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contains");
+
+    for &len in SIZES {
+        // Half of the probes miss.
+        let probes = shuffled(2 * len);
+        let list = Set::from_iter(0 .. len);
+        let skip = SkipSet::from_iter(0 .. len);
+        let btree = BTreeSet::from_iter(0 .. len);
+
+        group.bench_with_input(BenchmarkId::new("Set", len), &probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|elem| list.contains(elem)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("SkipSet", len), &probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|elem| skip.contains(elem)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("BTreeSet", len), &probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|elem| btree.contains(elem)).count())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert, bench_contains);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ownership::list_set::Set;
+use ownership::unrolled_set::UnrolledSet;
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+
+mod common;
+use common::shuffled;
+
+// `Set` is linear per lookup, so keep the sizes small enough for it.
+const SIZES: &[usize] = &[100, 1_000, 10_000];
+
+fn bench_iter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iter");
+
+    for &len in SIZES {
+        let list = Set::from_iter(0 .. len);
+        let unrolled = UnrolledSet::from_iter(0 .. len);
+        let btree = BTreeSet::from_iter(0 .. len);
+
+        group.bench_function(BenchmarkId::new("Set", len), |b| {
+            b.iter(|| list.iter().sum::<usize>())
+        });
+        group.bench_function(BenchmarkId::new("UnrolledSet", len), |b| {
+            b.iter(|| unrolled.iter().sum::<usize>())
+        });
+        group.bench_function(BenchmarkId::new("BTreeSet", len), |b| {
+            b.iter(|| btree.iter().sum::<usize>())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_contains(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contains");
+
+    for &len in SIZES {
+        // Half of the probes miss.
+        let probes = shuffled(2 * len);
+        let list = Set::from_iter(0 .. len);
+        let unrolled = UnrolledSet::from_iter(0 .. len);
+        let btree = BTreeSet::from_iter(0 .. len);
+
+        group.bench_with_input(BenchmarkId::new("Set", len), &probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|elem| list.contains(elem)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("UnrolledSet", len), &probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|elem| unrolled.contains(elem)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("BTreeSet", len), &probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|elem| btree.contains(elem)).count())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+
+    for &len in SIZES {
+        let elems = shuffled(len);
+
+        group.bench_with_input(BenchmarkId::new("Set", len), &elems, |b, elems| {
+            b.iter(|| {
+                let mut set = Set::new();
+                for &elem in elems { set.insert(elem); }
+                set
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("UnrolledSet", len), &elems, |b, elems| {
+            b.iter(|| {
+                let mut set = UnrolledSet::new();
+                for &elem in elems { set.insert(elem); }
+                set
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_iter, bench_contains, bench_insert);
+criterion_main!(benches);
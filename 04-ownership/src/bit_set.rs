@@ -0,0 +1,911 @@
+//! Sets of small integers, represented as growable vectors of bits.
+//!
+//! A `BitSet` offers the operations of a `list_set::Set<usize>`, storing
+//! each element as one bit, so set algebra runs a word of 64 elements at a
+//! time. Its size is proportional to the greatest element, so it suits
+//! dense sets of small IDs.
+
+use crate::list_set::Set;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::{Extend, FromIterator, FusedIterator};
+use core::mem;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+#[cfg(feature = "std")]
+use std::error;
+
+const BITS: usize = 64;
+
+/// A set of `usize`s.
+///
+/// # Example
+///
+/// ```
+/// use ownership::bit_set::BitSet;
+///
+/// let mut set = BitSet::new();
+///
+/// set.insert(3);
+/// set.insert(500);
+///
+/// if set.contains(&3) {
+///     set.insert(4);
+/// }
+///
+/// assert_eq!( 3, set.len() );
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+// Invariant: the last word, if any, is not zero, so equal sets have equal
+// words.
+
+// Returns the word index and the mask of an element's bit.
+fn locate(elem: usize) -> (usize, u64) {
+    (elem / BITS, 1 << (elem % BITS))
+}
+
+impl BitSet {
+    /// The greatest element a set can hold. A set holding it takes 512 MiB.
+    pub const MAX: usize = u32::MAX as usize;
+
+    /// Creates a new, empty set.
+    pub fn new() -> Self {
+        BitSet { words: Vec::new() }
+    }
+
+    /// Returns whether a set is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// let mut set = BitSet::new();
+    /// assert!(set.is_empty());
+    ///
+    /// set.insert(5);
+    /// assert!(!set.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the number of elements in the set, counting the bits of each
+    /// word in O(max / 64) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// let mut set = BitSet::new();
+    /// assert_eq!(0, set.len());
+    ///
+    /// set.insert(5);
+    /// set.insert(600);
+    /// set.insert(5);
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Returns an iterator over the elements of the set, in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = BitSet::from_iter(vec![70, 1, 3]);
+    /// let result: Vec<_> = set.iter().collect();
+    ///
+    /// assert_eq!( result, &[1, 3, 70] );
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        self.into_iter()
+    }
+
+    /// Returns the least element of the set, if any.
+    pub fn first(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    /// Returns the greatest element of the set, if any, in O(1) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = BitSet::from_iter(vec![3, 130, 7]);
+    /// assert_eq!( Some(130), set.last() );
+    /// ```
+    pub fn last(&self) -> Option<usize> {
+        let word = *self.words.last()?;
+        Some(self.words.len() * BITS - 1 - word.leading_zeros() as usize)
+    }
+
+    /// Checks whether the set contains the given element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = BitSet::from_iter(vec![3, 5, 4]);
+    ///
+    /// assert!(!set.contains(&2));
+    /// assert!( set.contains(&3));
+    /// assert!(!set.contains(&1000));
+    /// ```
+    pub fn contains(&self, element: &usize) -> bool {
+        let (index, mask) = locate(*element);
+        self.words.get(index).is_some_and(|word| word & mask != 0)
+    }
+
+    /// Adds the element to the set, growing it as needed.
+    ///
+    /// Returns `true` if the set did not previously contain the
+    /// element, and `false` if it did.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `element` is greater than `BitSet::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// let mut set = BitSet::new();
+    ///
+    /// assert!( set.insert(3));
+    /// assert!( set.insert(300));
+    /// assert!(!set.insert(3));
+    /// ```
+    pub fn insert(&mut self, element: usize) -> bool {
+        assert!(element <= BitSet::MAX, "BitSet::insert: {} exceeds BitSet::MAX", element);
+        let (index, mask) = locate(element);
+
+        if index >= self.words.len() {
+            self.words.resize(index + 1, 0);
+        }
+
+        let fresh = self.words[index] & mask == 0;
+        self.words[index] |= mask;
+        fresh
+    }
+
+    /// Adds the element to the set if absent, or replaces it if present.
+    ///
+    /// Returns `Some` of the old element if it was present.
+    pub fn replace(&mut self, element: usize) -> Option<usize> {
+        if self.insert(element) { None } else { Some(element) }
+    }
+
+    /// Removes the given element from the set.
+    ///
+    /// Returns `Some(element)` if it was removed, or `None` if it wasn't
+    /// there.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// let mut set = BitSet::new();
+    ///
+    /// assert_eq!(true,    set.insert(5));
+    /// assert_eq!(Some(5), set.remove(&5));
+    /// assert_eq!(None,    set.remove(&5));
+    /// ```
+    pub fn remove(&mut self, element: &usize) -> Option<usize> {
+        let (index, mask) = locate(*element);
+        let word = self.words.get_mut(index)?;
+
+        if *word & mask == 0 {
+            return None;
+        }
+
+        *word &= !mask;
+        self.trim();
+        Some(*element)
+    }
+
+    /// Keeps only the elements that satisfy a predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = BitSet::from_iter(1 .. 10);
+    ///
+    /// set.retain(|elem| elem % 3 == 0);
+    /// assert_eq!(set, BitSet::from_iter(vec![3, 6, 9]));
+    /// ```
+    pub fn retain<P: FnMut(&usize) -> bool>(&mut self, mut pred: P) {
+        for (index, word) in self.words.iter_mut().enumerate() {
+            let mut bits = *word;
+
+            while bits != 0 {
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+
+                if !pred(&(index * BITS + bit)) {
+                    *word &= !(1 << bit);
+                }
+            }
+        }
+
+        self.trim();
+    }
+
+    /// Shrinks the storage to fit the greatest element.
+    pub fn shrink_to_fit(&mut self) {
+        self.words.shrink_to_fit();
+    }
+
+    // Restores the invariant by dropping zero words from the end.
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    /// Returns whether two sets are disjoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = BitSet::from_iter(vec![1, 2]);
+    /// let set2 = BitSet::from_iter(vec![3, 4]);
+    /// let set3 = BitSet::from_iter(vec![1, 3]);
+    ///
+    /// assert!( set1.is_disjoint(&set2));
+    /// assert!(!set1.is_disjoint(&set3));
+    /// ```
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    /// Returns whether `self` is a subset of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = BitSet::from_iter(vec![2]);
+    /// let set2 = BitSet::from_iter(vec![1, 2, 3]);
+    ///
+    /// assert!( set1.is_subset(&set2));
+    /// assert!(!set2.is_subset(&set1));
+    /// ```
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.words.len() <= other.words.len()
+            && self.words.iter().zip(&other.words).all(|(a, b)| a & !b == 0)
+    }
+
+    /// Returns whether `self` is a superset of `other`.
+    pub fn is_superset(&self, other: &BitSet) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = BitSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = BitSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(BitSet::from_iter(vec![1, 3]), set1.intersection(&set2));
+    /// ```
+    pub fn intersection(&self, other: &BitSet) -> Self {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    /// Returns the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = BitSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = BitSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(BitSet::from_iter(vec![1, 2, 3, 4, 5, 7]), set1.union(&set2));
+    /// ```
+    pub fn union(&self, other: &BitSet) -> Self {
+        let mut result = self.clone();
+        result.combine(other, |a, b| a | b);
+        result
+    }
+
+    /// Returns the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = BitSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = BitSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(BitSet::from_iter(vec![5, 7]), set1.difference(&set2));
+    /// ```
+    pub fn difference(&self, other: &BitSet) -> Self {
+        let mut result = self.clone();
+        result.subtract(other);
+        result
+    }
+
+    /// Returns the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = BitSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = BitSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(BitSet::from_iter(vec![2, 4, 5, 7]), set1.symmetric_difference(&set2));
+    /// ```
+    pub fn symmetric_difference(&self, other: &BitSet) -> Self {
+        let mut result = self.clone();
+        result.combine(other, |a, b| a ^ b);
+        result
+    }
+
+    /// Adds every element of `other` to `self`.
+    pub fn union_with(&mut self, other: BitSet) {
+        self.combine(&other, |a, b| a | b);
+    }
+
+    /// Moves all elements of `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut BitSet) {
+        self.union_with(mem::take(other));
+    }
+
+    /// Modifies `self` to contain the symmetric difference of `self` and
+    /// `other`.
+    pub fn symmetric_difference_with(&mut self, other: BitSet) {
+        self.combine(&other, |a, b| a ^ b);
+    }
+
+    /// Modifies `self` to contain the intersection of `self` and `other`.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        self.words.truncate(other.words.len());
+        self.combine(other, |a, b| a & b);
+    }
+
+    /// Removes from `self` every element of `other`.
+    pub fn subtract(&mut self, other: &BitSet) {
+        self.combine(other, |a, b| a & !b);
+    }
+
+    /// Splits the set in two at `element`, returning everything greater
+    /// than or equal to it and leaving the rest in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::bit_set::BitSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = BitSet::from_iter(vec![1, 64, 65, 200]);
+    /// let high = set.split_off(&65);
+    ///
+    /// assert_eq!( set, BitSet::from_iter(vec![1, 64]) );
+    /// assert_eq!( high, BitSet::from_iter(vec![65, 200]) );
+    /// ```
+    pub fn split_off(&mut self, element: &usize) -> BitSet {
+        let (index, mask) = locate(*element);
+
+        if index >= self.words.len() {
+            return BitSet::new();
+        }
+
+        let mut high = BitSet { words: vec![0; index] };
+        high.words.extend_from_slice(&self.words[index ..]);
+        high.words[index] &= !(mask - 1);
+
+        self.words.truncate(index + 1);
+        self.words[index] &= mask - 1;
+        self.trim();
+        high.trim();
+
+        high
+    }
+
+    // Replaces each word of `self` with `f` of it and the same word of
+    // `other`, treating missing words as zero, which `f` must map to zero.
+    fn combine<F: Fn(u64, u64) -> u64>(&mut self, other: &BitSet, f: F) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+
+        for (index, word) in self.words.iter_mut().enumerate() {
+            *word = f(*word, other.words.get(index).cloned().unwrap_or(0));
+        }
+
+        self.trim();
+    }
+}
+
+impl BitOr<&BitSet> for &BitSet {
+    type Output = BitSet;
+
+    fn bitor(self, other: &BitSet) -> BitSet {
+        self.union(other)
+    }
+}
+
+impl BitAnd<&BitSet> for &BitSet {
+    type Output = BitSet;
+
+    fn bitand(self, other: &BitSet) -> BitSet {
+        self.intersection(other)
+    }
+}
+
+impl Sub<&BitSet> for &BitSet {
+    type Output = BitSet;
+
+    fn sub(self, other: &BitSet) -> BitSet {
+        self.difference(other)
+    }
+}
+
+impl BitXor<&BitSet> for &BitSet {
+    type Output = BitSet;
+
+    fn bitxor(self, other: &BitSet) -> BitSet {
+        self.symmetric_difference(other)
+    }
+}
+
+impl BitOrAssign<BitSet> for BitSet {
+    fn bitor_assign(&mut self, other: BitSet) {
+        self.union_with(other);
+    }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+    fn bitor_assign(&mut self, other: &BitSet) {
+        self.combine(other, |a, b| a | b);
+    }
+}
+
+impl BitAndAssign<&BitSet> for BitSet {
+    fn bitand_assign(&mut self, other: &BitSet) {
+        self.intersect_with(other);
+    }
+}
+
+impl SubAssign<&BitSet> for BitSet {
+    fn sub_assign(&mut self, other: &BitSet) {
+        self.subtract(other);
+    }
+}
+
+impl BitXorAssign<BitSet> for BitSet {
+    fn bitxor_assign(&mut self, other: BitSet) {
+        self.symmetric_difference_with(other);
+    }
+}
+
+impl BitXorAssign<&BitSet> for BitSet {
+    fn bitxor_assign(&mut self, other: &BitSet) {
+        self.combine(other, |a, b| a ^ b);
+    }
+}
+
+/// Compares the elements in order, as `Set` does.
+impl Ord for BitSet {
+    fn cmp(&self, other: &BitSet) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl PartialOrd for BitSet {
+    fn partial_cmp(&self, other: &BitSet) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the set as `{a, b, c}`.
+impl fmt::Display for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{")?;
+
+        for (i, elem) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            fmt::Display::fmt(&elem, f)?;
+        }
+
+        f.write_str("}")
+    }
+}
+
+/// The error from converting a `Set<usize>` that holds an element greater
+/// than `BitSet::MAX` into a `BitSet`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromSetError {
+    element: usize,
+}
+
+impl TryFromSetError {
+    /// Returns the greatest element of the set, which is too large.
+    pub fn element(&self) -> usize {
+        self.element
+    }
+}
+
+impl fmt::Display for TryFromSetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "element {} exceeds BitSet::MAX", self.element)
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for TryFromSetError {}
+
+/// Converts in O(n + max / 64), or fails without allocating if the set
+/// holds an element greater than `BitSet::MAX`.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::bit_set::BitSet;
+/// use ownership::list_set::Set;
+/// use std::convert::TryFrom;
+/// use std::iter::FromIterator;
+///
+/// let set = Set::from_iter(vec![3, 100, 2]);
+/// let bits = BitSet::try_from(set.clone()).unwrap();
+///
+/// assert!( bits.contains(&100) );
+/// assert_eq!( Set::from(bits), set );
+/// ```
+impl TryFrom<Set<usize>> for BitSet {
+    type Error = TryFromSetError;
+
+    fn try_from(set: Set<usize>) -> Result<Self, TryFromSetError> {
+        if let Some(&element) = set.last().filter(|&&max| max > BitSet::MAX) {
+            return Err(TryFromSetError { element });
+        }
+
+        let mut result = BitSet::new();
+        result.extend(set);
+        Ok(result)
+    }
+}
+
+/// Converts in O(n + max / 64).
+impl From<BitSet> for Set<usize> {
+    fn from(set: BitSet) -> Self {
+        Set::from_sorted_unchecked(set)
+    }
+}
+
+// The position of `Iter` and `IntoIter` in the words they walk.
+#[derive(Debug, Clone)]
+struct Position {
+    // The bits of `words[index]` not yet yielded.
+    bits:      u64,
+    index:     usize,
+    remaining: usize,
+}
+
+impl Position {
+    // Counts the elements once, so that size hints take O(1) time.
+    fn start(words: &[u64]) -> Self {
+        Position {
+            bits:      words.first().cloned().unwrap_or(0),
+            index:     0,
+            remaining: words.iter().map(|word| word.count_ones() as usize).sum(),
+        }
+    }
+
+    fn next(&mut self, words: &[u64]) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while self.bits == 0 {
+            self.index += 1;
+            self.bits = words[self.index];
+        }
+
+        let bit = self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+        self.remaining -= 1;
+        Some(self.index * BITS + bit)
+    }
+}
+
+/// An iterator over the elements of a `BitSet`, in ascending order.
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    words: &'a [u64],
+    pos:   Position,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.pos.next(self.words)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.pos.remaining, Some(self.pos.remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+impl<'a> FusedIterator for Iter<'a> {}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        Iter {
+            words: &self.words,
+            pos:   Position::start(&self.words),
+        }
+    }
+}
+
+/// An iterator that consumes a `BitSet`, in ascending order.
+#[derive(Debug)]
+pub struct IntoIter {
+    words: Vec<u64>,
+    pos:   Position,
+}
+
+impl Iterator for IntoIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.pos.next(&self.words)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.pos.remaining, Some(self.pos.remaining))
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl FusedIterator for IntoIter {}
+
+impl IntoIterator for BitSet {
+    type Item = usize;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            pos:   Position::start(&self.words),
+            words: self.words,
+        }
+    }
+}
+
+/// # Panics
+///
+/// Panics if an element is greater than `BitSet::MAX`, as `insert` does.
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item=usize>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+
+/// # Panics
+///
+/// Panics if an element is greater than `BitSet::MAX`, as `insert` does.
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item=usize>>(iter: I) -> Self {
+        let mut result = BitSet::new();
+        result.extend(iter);
+        result
+    }
+}
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_bit_set {
+    use super::BitSet;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl Arbitrary for BitSet {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            FromIterator::from_iter(Vec::<usize>::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            Box::new(Vec::from_iter(self)
+                .shrink()
+                .map(FromIterator::from_iter))
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::BitSet;
+    use crate::list_set::Set;
+    use quickcheck::quickcheck;
+    use std::collections::BTreeSet;
+    use std::convert::TryFrom;
+    use std::iter::FromIterator;
+
+    #[test]
+    #[should_panic(expected = "exceeds BitSet::MAX")]
+    fn insert_past_max() {
+        BitSet::new().insert(usize::MAX);
+    }
+
+    #[test]
+    fn try_from_past_max() {
+        // Where `usize` is 32 bits wide, every element fits.
+        let too_large = match BitSet::MAX.checked_add(1) {
+            Some(element) => element,
+            None => return,
+        };
+        let set = Set::from_iter(vec![1, BitSet::MAX, too_large]);
+
+        assert_eq!( Err(too_large), BitSet::try_from(set).map_err(|e| e.element()) );
+    }
+
+    fn model(set: &BitSet) -> BTreeSet<usize> {
+        set.iter().collect()
+    }
+
+    fn trimmed(set: &BitSet) -> bool {
+        set.words.last() != Some(&0)
+    }
+
+    quickcheck! {
+        fn prop_member(vec: Vec<usize>, elems: Vec<usize>) -> bool {
+            let set: BitSet = vec.iter().cloned().collect();
+            let expected: BTreeSet<usize> = vec.into_iter().collect();
+
+            elems.iter().all(|elem| set.contains(elem) == expected.contains(elem))
+                && set.len() == expected.len()
+                && set.iter().len() == expected.len()
+                && set.iter().eq(expected.iter().cloned())
+                && set.first() == expected.iter().next().cloned()
+                && set.last() == expected.iter().next_back().cloned()
+        }
+
+        fn prop_insert_remove(s1: BitSet, ops: Vec<(bool, u16)>) -> bool {
+            let mut set = s1;
+            let mut expected = model(&set);
+
+            ops.into_iter().all(|(insert, elem)| {
+                let elem = usize::from(elem % 512);
+                let agrees = if insert {
+                    set.insert(elem) == expected.insert(elem)
+                } else {
+                    set.remove(&elem) == expected.take(&elem)
+                };
+
+                agrees && trimmed(&set) && model(&set) == expected
+            })
+        }
+
+        fn prop_ops(s1: BitSet, s2: BitSet) -> bool {
+            let (b1, b2) = (model(&s1), model(&s2));
+            let results = [
+                (s1.intersection(&s2), &b1 & &b2),
+                (s1.union(&s2), &b1 | &b2),
+                (s1.difference(&s2), &b1 - &b2),
+                (s1.symmetric_difference(&s2), &b1 ^ &b2),
+            ];
+
+            results.iter().all(|(actual, expected)| trimmed(actual) && model(actual) == *expected)
+                && s1.is_disjoint(&s2) == b1.is_disjoint(&b2)
+                && s1.is_subset(&s2) == b1.is_subset(&b2)
+                && s1.is_superset(&s2) == b1.is_superset(&b2)
+                && s1.cmp(&s2) == b1.cmp(&b2)
+                && (s1 == s2) == (b1 == b2)
+        }
+
+        fn prop_in_place(s1: BitSet, s2: BitSet) -> bool {
+            let mut union = s1.clone();
+            union.union_with(s2.clone());
+            let mut intersection = s1.clone();
+            intersection.intersect_with(&s2);
+            let mut difference = s1.clone();
+            difference.subtract(&s2);
+            let mut symmetric = s1.clone();
+            symmetric.symmetric_difference_with(s2.clone());
+            let mut union_ref = s1.clone();
+            union_ref |= &s2;
+            let mut symmetric_ref = s1.clone();
+            symmetric_ref ^= &s2;
+
+            union == &s1 | &s2
+                && intersection == &s1 & &s2
+                && difference == &s1 - &s2
+                && symmetric == &s1 ^ &s2
+                && union_ref == union && trimmed(&union_ref)
+                && symmetric_ref == symmetric && trimmed(&symmetric_ref)
+        }
+
+        fn prop_split_off(s1: BitSet, elem: usize) -> bool {
+            let mut low = s1.clone();
+            let high = low.split_off(&elem);
+
+            trimmed(&low) && trimmed(&high)
+                && low.iter().all(|e| e < elem)
+                && high.iter().all(|e| e >= elem)
+                && low.union(&high) == s1
+        }
+
+        fn prop_retain(s1: BitSet, modulus: usize) -> bool {
+            let modulus = modulus % 5 + 2;
+            let mut set = s1.clone();
+            set.retain(|elem| elem % modulus != 0);
+
+            trimmed(&set) && set.iter().eq(s1.iter().filter(|elem| elem % modulus != 0))
+        }
+
+        fn prop_convert(s1: Set<usize>) -> bool {
+            let bits = BitSet::try_from(s1.clone()).unwrap();
+            bits.iter().eq(s1.iter().cloned()) && Set::from(bits) == s1
+        }
+
+        fn prop_into_iter(s1: BitSet) -> bool {
+            let expected: Vec<usize> = s1.iter().collect();
+            let mut iter = s1.into_iter();
+            let mut lens_agree = true;
+            let mut actual = Vec::new();
+
+            while let Some(elem) = iter.next() {
+                actual.push(elem);
+                lens_agree &= iter.len() == expected.len() - actual.len();
+            }
+
+            lens_agree && actual == expected
+        }
+    }
+}
@@ -1,10 +1,19 @@
-use std::cmp::Ordering::*;
-use std::mem;
+#[cfg(feature = "std")]
+use crate::codec::{self, Decode, Encode};
+use alloc::boxed::Box;
+use alloc::vec;
+#[cfg(any(feature = "std", feature = "serde", feature = "quickcheck", test))]
+use alloc::vec::Vec;
+use core::cmp::Ordering::*;
+use core::iter::{Extend, FromIterator};
+use core::mem;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BST<K, V>(Link<K, V>);
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Node<K, V> {
     key:   K,
     value: V,
@@ -24,7 +33,40 @@ impl<K, V> BST<K, V> {
     }
 
     pub fn len(&self) -> usize {
-        Node::len_iter(&self.0)
+        if cfg!(feature = "recursive") {
+            Node::len_rec(&self.0)
+        } else {
+            Node::len_iter(&self.0)
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "serde", feature = "quickcheck", test))]
+impl<K, V> BST<K, V> {
+    // Inserting the entries in this order into an empty tree rebuilds a
+    // tree of the same shape.
+    fn preorder(&self) -> Preorder<'_, K, V> {
+        Preorder(vec![&self.0])
+    }
+}
+
+#[cfg(any(feature = "std", feature = "serde", feature = "quickcheck", test))]
+struct Preorder<'a, K, V>(Vec<&'a Link<K, V>>);
+
+#[cfg(any(feature = "std", feature = "serde", feature = "quickcheck", test))]
+impl<'a, K, V> Iterator for Preorder<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        while let Some(each) = self.0.pop() {
+            if let Some(ref node_ptr) = *each {
+                self.0.push(&node_ptr.right);
+                self.0.push(&node_ptr.left);
+                return Some((&node_ptr.key, &node_ptr.value));
+            }
+        }
+
+        None
     }
 }
 
@@ -36,14 +78,91 @@ impl<K, V> Default for BST<K, V> {
 
 impl<K: Ord, V> BST<K, V> {
     pub fn find(&self, key: &K) -> Option<&V> {
-        Node::find_iter(&self.0, key) }
+        if cfg!(feature = "recursive") {
+            Node::find_rec(&self.0, key)
+        } else {
+            Node::find_iter(&self.0, key)
+        }
+    }
 
     pub fn find_mut(&mut self, key: &K) -> Option<&mut V> {
-        Node::find_mut_iter(&mut self.0, key)
+        if cfg!(feature = "recursive") {
+            Node::find_mut_rec(&mut self.0, key)
+        } else {
+            Node::find_mut_iter(&mut self.0, key)
+        }
     }
 
     pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
-        Node::insert_iter(&mut self.0, key, value)
+        if cfg!(feature = "recursive") {
+            Node::insert_rec(&mut self.0, key, value)
+        } else {
+            Node::insert_iter(&mut self.0, key, value)
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<(K, V)> {
+        if cfg!(feature = "recursive") {
+            Node::remove_rec(&mut self.0, key)
+        } else {
+            Node::remove_iter(&mut self.0, key)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Encode, V: Encode> BST<K, V> {
+    /// Writes the tree to `writer`.
+    ///
+    /// The entries are written in preorder, so `read_from` rebuilds a tree
+    /// of exactly the same shape.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        codec::encode_len(self.len(), &mut writer)?;
+
+        for (key, value) in self.preorder() {
+            key.encode(&mut writer)?;
+            value.encode(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Ord + Decode, V: Decode> BST<K, V> {
+    /// Reads a tree written by `write_to`.
+    ///
+    /// Fails with `codec::Error::Unsorted` if a key is repeated.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, codec::Error> {
+        let len = codec::decode_len(&mut reader)?;
+        let mut result = BST::new();
+
+        for _ in 0 .. len {
+            let key   = K::decode(&mut reader)?;
+            let value = V::decode(&mut reader)?;
+
+            if result.insert(key, value).is_some() {
+                return Err(codec::Error::Unsorted);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for BST<K, V> {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for BST<K, V> {
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let mut result = BST::new();
+        result.extend(iter);
+        result
     }
 }
 
@@ -57,7 +176,6 @@ impl<K, V> Node<K, V> {
         })
     }
 
-    #[allow(dead_code)]
     fn len_rec(ptr: &Link<K, V>) -> usize {
         if let Some(ref node_ptr) = *ptr {
             1 + Node::len_rec(&node_ptr.left) + Node::len_rec(&node_ptr.right)
@@ -78,11 +196,40 @@ impl<K, V> Node<K, V> {
 
         result
     }
+
+    fn remove_root(ptr: &mut Link<K, V>) -> Option<(K, V)> {
+        let mut node = ptr.take()?;
+
+        *ptr = match (node.left.take(), node.right.take()) {
+            (None, right) => right,
+            (left, None)  => left,
+            (left, right) => {
+                let mut right = right;
+                let mut min = Node::remove_min(&mut right)
+                    .expect("Node::remove_root: empty right subtree");
+                min.left  = left;
+                min.right = right;
+                Some(min)
+            }
+        };
+
+        let Node { key, value, .. } = *node;
+        Some((key, value))
+    }
+
+    fn remove_min(mut ptr: &mut Link<K, V>) -> Link<K, V> {
+        while ptr.as_ref()?.left.is_some() {
+            ptr = &mut {ptr}.as_mut().unwrap().left;
+        }
+
+        let mut node = ptr.take()?;
+        *ptr = node.right.take();
+        Some(node)
+    }
 }
 
 impl<K: Ord, V> Node<K, V> {
-    #[allow(dead_code)]
-    fn find_rec<'a, 'b>(ptr: &'a Link<K, V>, key: &'b K) -> Option<&'a V> {
+    fn find_rec<'a>(ptr: &'a Link<K, V>, key: &K) -> Option<&'a V> {
         if let Some(ref n) = *ptr {
             match key.cmp(&n.key) {
                 Less    => Node::find_rec(&n.left, key),
@@ -92,7 +239,7 @@ impl<K: Ord, V> Node<K, V> {
         } else {None}
     }
 
-    fn find_iter<'a, 'b>(mut ptr: &'a Link<K, V>, key: &'b K)
+    fn find_iter<'a>(mut ptr: &'a Link<K, V>, key: &K)
         -> Option<&'a V>
     {
         while let Some(ref n) = *ptr {
@@ -106,8 +253,7 @@ impl<K: Ord, V> Node<K, V> {
         None
     }
 
-    #[allow(dead_code)]
-    fn find_mut_rec<'a, 'b>(ptr: &'a mut Link<K, V>, key: &'b K)
+    fn find_mut_rec<'a>(ptr: &'a mut Link<K, V>, key: &K)
         -> Option<&'a mut V>
     {
         if let Some(ref mut n) = *ptr {
@@ -120,7 +266,7 @@ impl<K: Ord, V> Node<K, V> {
     }
 
 
-    fn find_mut_iter<'a, 'b>(ptr: &'a mut Link<K, V>, key: &'b K)
+    fn find_mut_iter<'a>(ptr: &'a mut Link<K, V>, key: &K)
         -> Option<&'a mut V>
     {
         let mut cur = ptr.as_mut();
@@ -138,7 +284,6 @@ impl<K: Ord, V> Node<K, V> {
         }
     }
 
-    #[allow(dead_code)]
     fn insert_rec(ptr: &mut Link<K, V>, key: K, value: V) -> Option<(K, V)> {
         match *ptr {
             None => {
@@ -171,6 +316,26 @@ impl<K: Ord, V> Node<K, V> {
         *ptr = Some(Node::new(key, value));
         None
     }
+
+    fn remove_rec(ptr: &mut Link<K, V>, key: &K) -> Option<(K, V)> {
+        let node_ptr = ptr.as_mut()?;
+
+        match key.cmp(&node_ptr.key) {
+            Less    => Node::remove_rec(&mut node_ptr.left, key),
+            Greater => Node::remove_rec(&mut node_ptr.right, key),
+            Equal   => Node::remove_root(ptr),
+        }
+    }
+
+    fn remove_iter(mut ptr: &mut Link<K, V>, key: &K) -> Option<(K, V)> {
+        loop {
+            match key.cmp(&ptr.as_ref()?.key) {
+                Less    => ptr = &mut {ptr}.as_mut().unwrap().left,
+                Greater => ptr = &mut {ptr}.as_mut().unwrap().right,
+                Equal   => return Node::remove_root(ptr),
+            }
+        }
+    }
 }
 
 #[test]
@@ -192,3 +357,291 @@ fn bst_test() {
     assert_eq!( bst.insert("four", 7), None );
     assert_eq!( bst.insert("four", 8), Some(("four", 7)) );
 }
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_bst {
+    use super::BST;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl<K: Arbitrary + Ord, V: Arbitrary> Arbitrary for BST<K, V> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            FromIterator::from_iter(Vec::<(K, V)>::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            let entries: Vec<(K, V)> = self.preorder()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+
+            Box::new(entries.shrink().map(FromIterator::from_iter))
+        }
+    }
+}
+
+#[cfg(any(test, feature = "serde"))]
+mod impl_serde_for_bst {
+    use super::BST;
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+    use core::fmt;
+    use core::marker::PhantomData;
+    use serde::ser::{Serialize, SerializeMap, Serializer};
+
+    // Entries are serialized in preorder, so deserializing rebuilds the
+    // same shape.
+    impl<K: Serialize, V: Serialize> Serialize for BST<K, V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+
+            for (key, value) in self.preorder() {
+                map.serialize_entry(key, value)?;
+            }
+
+            map.end()
+        }
+    }
+
+    impl<'de, K, V> Deserialize<'de> for BST<K, V>
+        where K: Deserialize<'de> + Ord,
+              V: Deserialize<'de>
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_map(BSTVisitor(PhantomData))
+        }
+    }
+
+    struct BSTVisitor<K, V>(PhantomData<BST<K, V>>);
+
+    impl<'de, K, V> Visitor<'de> for BSTVisitor<K, V>
+        where K: Deserialize<'de> + Ord,
+              V: Deserialize<'de>
+    {
+        type Value = BST<K, V>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map")
+        }
+
+        // Like `read_from`, rejects repeated keys rather than keeping one.
+        fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<BST<K, V>, A::Error> {
+            let mut result = BST::new();
+
+            while let Some((key, value)) = access.next_entry()? {
+                if result.insert(key, value).is_some() {
+                    return Err(de::Error::custom("duplicate key"));
+                }
+            }
+
+            Ok(result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::{BST, Link, Node};
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(u8, u32),
+        Find(u8),
+        FindMut(u8, u32),
+        Remove(u8),
+    }
+
+    use self::Op::*;
+
+    // Keys are drawn from a small range so that operations collide.
+    const KEYS: u8 = 32;
+
+    impl Arbitrary for Op {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let key = u8::arbitrary(g) % KEYS;
+
+            match u8::arbitrary(g) % 4 {
+                0 => Insert(key, u32::arbitrary(g)),
+                1 => Find(key),
+                2 => FindMut(key, u32::arbitrary(g)),
+                _ => Remove(key),
+            }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            match *self {
+                Insert(k, v)  => Box::new((k, v).shrink().map(|(k, v)| Insert(k, v))),
+                Find(k)       => Box::new(k.shrink().map(Find)),
+                FindMut(k, v) => Box::new((k, v).shrink().map(|(k, v)| FindMut(k, v))),
+                Remove(k)     => Box::new(k.shrink().map(Remove)),
+            }
+        }
+    }
+
+    fn agrees_with_model(ops: &[Op]) -> bool {
+        let mut bst   = BST::new();
+        let mut model = BTreeMap::new();
+
+        for op in ops {
+            let agrees = match *op {
+                Insert(k, v) =>
+                    bst.insert(k, v) == model.insert(k, v).map(|old| (k, old)),
+                Find(k) =>
+                    bst.find(&k) == model.get(&k),
+                FindMut(k, v) =>
+                    update_both(bst.find_mut(&k), model.get_mut(&k), v),
+                Remove(k) =>
+                    bst.remove(&k) == model.remove_entry(&k),
+            };
+
+            if !agrees || bst.len() != model.len() || bst.is_empty() != model.is_empty() {
+                return false;
+            }
+        }
+
+        model.iter().all(|(k, v)| bst.find(k) == Some(v))
+    }
+
+    // Replays `ops` through both the recursive and the iterative routines,
+    // then checks that they built exactly the same tree.
+    fn rec_agrees_with_iter(ops: &[Op]) -> bool {
+        let mut rec:  Link<u8, u32> = None;
+        let mut iter: Link<u8, u32> = None;
+
+        for op in ops {
+            let agrees = match *op {
+                Insert(k, v) =>
+                    Node::insert_rec(&mut rec, k, v) == Node::insert_iter(&mut iter, k, v),
+                Find(k) =>
+                    Node::find_rec(&rec, &k) == Node::find_iter(&iter, &k),
+                FindMut(k, v) =>
+                    update_both(Node::find_mut_rec(&mut rec, &k),
+                                Node::find_mut_iter(&mut iter, &k),
+                                v),
+                Remove(k) =>
+                    Node::remove_rec(&mut rec, &k) == Node::remove_iter(&mut iter, &k),
+            };
+
+            if !agrees || Node::len_rec(&rec) != Node::len_iter(&iter) {
+                return false;
+            }
+        }
+
+        same_shape(&rec, &iter)
+    }
+
+    fn update_both(a: Option<&mut u32>, b: Option<&mut u32>, value: u32) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => {
+                let agrees = a == b;
+                *a = value;
+                *b = value;
+                agrees
+            }
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn same_shape<K: Eq, V: Eq>(a: &Link<K, V>, b: &Link<K, V>) -> bool {
+        let mut stack = vec![(a, b)];
+
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (Some(m), Some(n)) => {
+                    if m.key != n.key || m.value != n.value {
+                        return false;
+                    }
+
+                    stack.push((&m.left, &n.left));
+                    stack.push((&m.right, &n.right));
+                }
+                (None, None) => (),
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_read_round_trip() {
+        let bst: BST<u8, u32> = vec![(4, 40), (2, 20), (6, 60), (1, 10)].into_iter().collect();
+        let mut buf = Vec::new();
+        bst.write_to(&mut buf).unwrap();
+
+        let copy = BST::read_from(buf.as_slice()).unwrap();
+        assert!( same_shape(&bst.0, &copy.0) );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let bst: BST<String, u32> = vec![("b".to_owned(), 2), ("a".to_owned(), 1), ("c".to_owned(), 3)]
+            .into_iter()
+            .collect();
+
+        let json = serde_json::to_string(&bst).unwrap();
+        assert_eq!( json, r#"{"b":2,"a":1,"c":3}"# );
+
+        let copy: BST<String, u32> = serde_json::from_str(&json).unwrap();
+        assert!( same_shape(&bst.0, &copy.0) );
+
+        let duplicate = serde_json::from_str::<BST<String, u32>>(r#"{"a":1,"a":2}"#);
+        assert!( duplicate.unwrap_err().to_string().contains("duplicate key") );
+    }
+
+    #[test]
+    fn remove_interior() {
+        let mut bst: BST<u8, ()> = vec![4, 2, 6, 1, 3, 5, 7].into_iter()
+            .map(|k| (k, ()))
+            .collect();
+
+        assert_eq!( bst.remove(&4), Some((4, ())) );
+        assert_eq!( bst.remove(&2), Some((2, ())) );
+        assert_eq!( bst.remove(&2), None );
+        assert_eq!( bst.len(), 5 );
+
+        for k in &[1, 3, 5, 6, 7] {
+            assert_eq!( bst.find(k), Some(&()) );
+        }
+    }
+
+    quickcheck! {
+
+        fn prop_model(ops: Vec<Op>) -> bool {
+            agrees_with_model(&ops)
+        }
+
+        #[cfg(feature = "std")]
+        fn prop_write_read(bst: BST<u8, String>) -> bool {
+            let mut buf = Vec::new();
+            bst.write_to(&mut buf).unwrap();
+
+            same_shape(&bst.0, &BST::read_from(buf.as_slice()).unwrap().0)
+        }
+
+        fn prop_rec_agrees_with_iter(ops: Vec<Op>) -> bool {
+            rec_agrees_with_iter(&ops)
+        }
+
+        fn prop_remove(bst: BST<u8, u32>, keys: Vec<u8>) -> bool {
+            let mut bst = bst;
+
+            keys.iter().all(|k| {
+                let len = bst.len();
+
+                match bst.find(k).cloned() {
+                    Some(v) => bst.remove(k) == Some((*k, v))
+                        && bst.find(k).is_none()
+                        && bst.len() == len - 1,
+                    None    => bst.remove(k).is_none() && bst.len() == len,
+                }
+            })
+        }
+
+    }
+
+}
@@ -0,0 +1,328 @@
+//! A compact, dependency-free binary encoding.
+//!
+//! Integers are written little-endian at their full width (`usize` and
+//! `isize` as 64 bits), strings as a length followed by their UTF-8 bytes,
+//! and tuples as their components in order.
+
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Types that can be written in the binary format.
+pub trait Encode {
+    /// Writes `self` to `writer`.
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+/// Types that can be read back from the binary format.
+pub trait Decode: Sized {
+    /// Reads a value from `reader`.
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, Error>;
+}
+
+/// The ways that decoding can fail.
+#[derive(Debug)]
+pub enum Error {
+    /// The input ended in the middle of a value.
+    Truncated,
+    /// The elements or keys of a collection are out of order or repeated.
+    Unsorted,
+    /// A length does not fit in a `usize`.
+    InvalidLength(u64),
+    /// An integer does not fit in the type being decoded, such as an `i64`
+    /// read as a 32-bit `isize`.
+    OutOfRange(i128),
+    /// A string is not valid UTF-8.
+    InvalidUtf8,
+    /// The underlying reader failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Truncated          => write!(f, "unexpected end of input"),
+            Error::Unsorted           => write!(f, "input is not sorted"),
+            Error::InvalidLength(len) => write!(f, "invalid length: {}", len),
+            Error::OutOfRange(n)      => write!(f, "integer out of range: {}", n),
+            Error::InvalidUtf8        => write!(f, "string is not valid UTF-8"),
+            Error::Io(ref e)          => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            Error::Truncated
+        } else {
+            Error::Io(e)
+        }
+    }
+}
+
+/// Writes a collection length.
+pub(crate) fn encode_len<W: Write>(len: usize, writer: &mut W) -> io::Result<()> {
+    (len as u64).encode(writer)
+}
+
+/// Reads a collection length.
+pub(crate) fn decode_len<R: Read>(reader: &mut R) -> Result<usize, Error> {
+    let len = u64::decode(reader)?;
+    usize::try_from(len).map_err(|_| Error::InvalidLength(len))
+}
+
+macro_rules! impl_codec_for_int {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl Encode for $ty {
+                fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode<R: Read>(reader: &mut R) -> Result<Self, Error> {
+                    let mut buf = [0; std::mem::size_of::<$ty>()];
+                    reader.read_exact(&mut buf)?;
+                    Ok(<$ty>::from_le_bytes(buf))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Encode for usize {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u64).encode(writer)
+    }
+}
+
+impl Decode for usize {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        decode_narrowed::<u64, usize, R>(reader)
+    }
+}
+
+impl Encode for isize {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as i64).encode(writer)
+    }
+}
+
+impl Decode for isize {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        decode_narrowed::<i64, isize, R>(reader)
+    }
+}
+
+/// Reads an integer written as the wider type `W` and converts it to `N`,
+/// failing with `Error::OutOfRange` if it does not fit.
+fn decode_narrowed<W, N, R>(reader: &mut R) -> Result<N, Error>
+    where W: Decode + Copy + Into<i128>,
+          N: TryFrom<W>,
+          R: Read
+{
+    let n = W::decode(reader)?;
+    N::try_from(n).map_err(|_| Error::OutOfRange(n.into()))
+}
+
+impl Encode for str {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        encode_len(self.len(), writer)?;
+        writer.write_all(self.as_bytes())
+    }
+}
+
+impl Encode for String {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.as_str().encode(writer)
+    }
+}
+
+impl Decode for String {
+    fn decode<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let len = decode_len(reader)?;
+
+        // Reading through `take` avoids trusting `len` for an allocation.
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+
+        if bytes.len() < len {
+            return Err(Error::Truncated);
+        }
+
+        String::from_utf8(bytes).map_err(|_| Error::InvalidUtf8)
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (**self).encode(writer)
+    }
+}
+
+macro_rules! impl_codec_for_tuple {
+    ( $( ( $( $name:ident ),+ ) )* ) => {
+        $(
+            impl<$( $name: Encode ),+> Encode for ( $( $name, )+ ) {
+                #[allow(non_snake_case)]
+                fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    let ( $( ref $name, )+ ) = *self;
+                    $( $name.encode(writer)?; )+
+                    Ok(())
+                }
+            }
+
+            impl<$( $name: Decode ),+> Decode for ( $( $name, )+ ) {
+                fn decode<R: Read>(reader: &mut R) -> Result<Self, Error> {
+                    Ok(( $( $name::decode(reader)?, )+ ))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_tuple! {
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_narrowed, Decode, Encode, Error};
+    use crate::bst::BST;
+    use crate::list_set::Set;
+    use quickcheck::quickcheck;
+
+    fn round_trip<T: Encode + Decode>(value: &T) -> T {
+        let mut buf = Vec::new();
+        value.encode(&mut buf).unwrap();
+        T::decode(&mut buf.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn truncated_set() {
+        let set: Set<u32> = vec![1, 2, 3].into_iter().collect();
+        let mut buf = Vec::new();
+        set.write_to(&mut buf).unwrap();
+        buf.pop();
+
+        match Set::<u32>::read_from(buf.as_slice()) {
+            Err(Error::Truncated) => (),
+            other => panic!("expected Truncated, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unsorted_set() {
+        let mut buf = Vec::new();
+        3usize.encode(&mut buf).unwrap();
+        for elem in &[1u32, 3, 2] {
+            elem.encode(&mut buf).unwrap();
+        }
+
+        match Set::<u32>::read_from(buf.as_slice()) {
+            Err(Error::Unsorted) => (),
+            other => panic!("expected Unsorted, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn duplicate_bst_keys() {
+        let mut buf = Vec::new();
+        2usize.encode(&mut buf).unwrap();
+        (5u8, "a").encode(&mut buf).unwrap();
+        (5u8, "b").encode(&mut buf).unwrap();
+
+        match BST::<u8, String>::read_from(buf.as_slice()) {
+            Err(Error::Unsorted) => (),
+            other => panic!("expected Unsorted, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn out_of_range_message() {
+        assert_eq!( "integer out of range: -5", Error::OutOfRange(-5).to_string() );
+    }
+
+    // `usize` and `isize` narrow as these do, but only on targets where
+    // they are under 64 bits.
+    #[test]
+    fn out_of_range_narrowed() {
+        let mut buf = Vec::new();
+        (i64::from(i32::MAX) + 1).encode(&mut buf).unwrap();
+        u64::MAX.encode(&mut buf).unwrap();
+        let mut reader = buf.as_slice();
+
+        match decode_narrowed::<i64, i32, _>(&mut reader) {
+            Err(Error::OutOfRange(n)) => assert_eq!( i128::from(i32::MAX) + 1, n ),
+            other => panic!("expected OutOfRange, got {:?}", other),
+        }
+
+        match decode_narrowed::<u64, u32, _>(&mut reader) {
+            Err(Error::OutOfRange(n)) => assert_eq!( i128::from(u64::MAX), n ),
+            other => panic!("expected OutOfRange, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn narrowed_in_range() {
+        let mut buf = Vec::new();
+        i64::from(i32::MIN).encode(&mut buf).unwrap();
+
+        assert_eq!( i32::MIN, decode_narrowed::<i64, i32, _>(&mut buf.as_slice()).unwrap() );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn out_of_range_isize() {
+        let mut buf = Vec::new();
+        i64::MIN.encode(&mut buf).unwrap();
+
+        match isize::decode(&mut buf.as_slice()) {
+            Err(Error::OutOfRange(n)) => assert_eq!( i128::from(i64::MIN), n ),
+            other => panic!("expected OutOfRange, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn truncated_string() {
+        let mut buf = Vec::new();
+        "hello".encode(&mut buf).unwrap();
+        buf.truncate(buf.len() - 2);
+
+        match String::decode(&mut buf.as_slice()) {
+            Err(Error::Truncated) => (),
+            other => panic!("expected Truncated, got {:?}", other),
+        }
+    }
+
+    quickcheck! {
+
+        fn prop_round_trip(value: (u8, i64, usize, String)) -> bool {
+            round_trip(&value) == value
+        }
+
+        fn prop_set_round_trip(set: Set<(String, i32)>) -> bool {
+            let mut buf = Vec::new();
+            set.write_to(&mut buf).unwrap();
+            Set::read_from(buf.as_slice()).unwrap() == set
+        }
+
+    }
+}
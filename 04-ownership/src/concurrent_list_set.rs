@@ -0,0 +1,668 @@
+//! Sets that many threads can update at once, represented as sorted,
+//! lock-free linked lists.
+//!
+//! This is Harris's algorithm. A thread deletes a node in two steps: first
+//! it marks the low bit of the node's own link, which stops any thread from
+//! linking a node after it, and then it unlinks the node, which any thread
+//! that passes by may finish for it. Unlinked nodes are freed by the
+//! epoch-based collector in `epoch`, once no thread can still be reading
+//! them.
+
+use crate::list_set::Set;
+use alloc::boxed::Box;
+use core::cmp::Ordering::{Less, Equal, Greater};
+use core::fmt;
+use core::iter;
+use core::ptr;
+use core::sync::atomic::{AtomicIsize, AtomicPtr, AtomicUsize};
+use core::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+
+mod epoch;
+
+use self::epoch::{Collector, Guard};
+
+/// How many nodes to retire between attempts to free garbage.
+const COLLECT_EVERY: usize = 64;
+
+/// A set of elements of type `T` that can be shared between threads and
+/// updated through a shared reference.
+///
+/// Every operation is lock-free: some thread always makes progress, however
+/// the others are scheduled.
+///
+/// # Example
+///
+/// ```
+/// use ownership::concurrent_list_set::ConcurrentListSet;
+/// use std::thread;
+///
+/// let set = ConcurrentListSet::new();
+///
+/// thread::scope(|scope| {
+///     for i in 0 .. 4 {
+///         let set = &set;
+///         scope.spawn(move || {
+///             for elem in 0 .. 10 {
+///                 set.insert(i * 10 + elem);
+///             }
+///         });
+///     }
+/// });
+///
+/// assert_eq!( 40, set.len() );
+/// assert!( set.contains(&25) );
+/// ```
+pub struct ConcurrentListSet<T> {
+    head:      AtomicPtr<Node<T>>,
+    len:       AtomicIsize,
+    garbage:   AtomicPtr<Node<T>>,
+    retired:   AtomicUsize,
+    collector: Collector,
+}
+// Invariants:
+//  - Following the links from `head` visits ascending elements. A node
+//    whose link is marked is deleted, and its link never changes again.
+//  - A node is unlinked by exactly one successful exchange, and the thread
+//    that makes it pushes the node onto `garbage`, a stack threaded through
+//    `retired_next`. Nodes are freed only from there, by `collect`, or by
+//    `drop`.
+
+// SAFETY: elements move between threads in nodes, and are shared by
+// reference between threads that traverse the list.
+unsafe impl<T: Send> Send for ConcurrentListSet<T> {}
+unsafe impl<T: Send + Sync> Sync for ConcurrentListSet<T> {}
+
+struct Node<T> {
+    elem:          T,
+    next:          AtomicPtr<Node<T>>,
+    retired_next:  AtomicPtr<Node<T>>,
+    retired_epoch: AtomicUsize,
+}
+
+impl<T> Node<T> {
+    fn new(elem: T) -> *mut Self {
+        Box::into_raw(Box::new(Node {
+            elem,
+            next:          AtomicPtr::new(ptr::null_mut()),
+            retired_next:  AtomicPtr::new(ptr::null_mut()),
+            retired_epoch: AtomicUsize::new(0),
+        }))
+    }
+}
+
+// Nodes are word-aligned, so the low bit of a link is free for the mark.
+// The mark is set and cleared with `map_addr`, which keeps the pointer's
+// provenance, so an unmarked link can still be dereferenced.
+
+fn is_marked<T>(link: *mut Node<T>) -> bool {
+    link.addr() & 1 == 1
+}
+
+fn marked<T>(link: *mut Node<T>) -> *mut Node<T> {
+    link.map_addr(|addr| addr | 1)
+}
+
+fn unmarked<T>(link: *mut Node<T>) -> *mut Node<T> {
+    link.map_addr(|addr| addr & !1)
+}
+
+impl<T> ConcurrentListSet<T> {
+    /// Creates a new, empty set.
+    pub fn new() -> Self {
+        ConcurrentListSet {
+            head:      AtomicPtr::new(ptr::null_mut()),
+            len:       AtomicIsize::new(0),
+            garbage:   AtomicPtr::new(ptr::null_mut()),
+            retired:   AtomicUsize::new(0),
+            collector: Collector::new(),
+        }
+    }
+
+    /// Returns the number of elements in the set. While other threads are
+    /// updating the set, this may lag behind them.
+    pub fn len(&self) -> usize {
+        self.len.load(Relaxed).max(0) as usize
+    }
+
+    /// Returns whether the set is empty, with the same caveat as `len`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Converts the set into a `list_set::Set`, in O(n) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::concurrent_list_set::ConcurrentListSet;
+    /// use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = ConcurrentListSet::new();
+    /// set.insert(2);
+    /// set.insert(1);
+    ///
+    /// assert_eq!( Set::from_iter(vec![1, 2]), set.into_set() );
+    /// ```
+    pub fn into_set(self) -> Set<T> {
+        let mut cur = self.head.swap(ptr::null_mut(), Relaxed);
+
+        Set::from_sorted_unchecked(iter::from_fn(|| {
+            while !cur.is_null() {
+                // SAFETY: we own the set, so no other thread can see the
+                // nodes; `drop` will free only the garbage.
+                let node = unsafe { Box::from_raw(cur) };
+                let next = node.next.load(Relaxed);
+                cur = unmarked(next);
+
+                if !is_marked(next) {
+                    return Some(node.elem);
+                }
+            }
+
+            None
+        }))
+    }
+
+    // Pushes a node that this thread has just unlinked onto the garbage
+    // stack, and now and then frees what garbage it can.
+    fn retire(&self, node: *mut Node<T>, guard: &Guard) {
+        // SAFETY: the node was unlinked but not yet pushed, so only this
+        // thread can push it, and only `collect` and `drop` free it.
+        unsafe { &*node }.retired_epoch.store(guard.retire_epoch(), Relaxed);
+        self.push_garbage(node);
+
+        if self.retired.fetch_add(1, Relaxed) % COLLECT_EVERY == COLLECT_EVERY - 1 {
+            self.collect(guard);
+        }
+    }
+
+    fn push_garbage(&self, node: *mut Node<T>) {
+        let mut head = self.garbage.load(Relaxed);
+
+        loop {
+            // SAFETY: as in `retire`.
+            unsafe { &*node }.retired_next.store(head, Relaxed);
+
+            match self.garbage.compare_exchange_weak(head, node, Release, Relaxed) {
+                Ok(_) => return,
+                Err(now) => head = now,
+            }
+        }
+    }
+
+    // Frees the garbage retired at least two epochs ago, pushing back the
+    // rest.
+    fn collect(&self, guard: &Guard) {
+        let epoch = guard.try_advance();
+        let mut cur = self.garbage.swap(ptr::null_mut(), Acquire);
+
+        while !cur.is_null() {
+            // SAFETY: taking the whole stack gives this thread the nodes.
+            let node = unsafe { &*cur };
+            let next = node.retired_next.load(Relaxed);
+
+            // Other threads may have retired nodes in a later epoch since
+            // this one advanced it.
+            if node.retired_epoch.load(Relaxed) + 2 <= epoch {
+                // SAFETY: no pinned thread can still reach the node.
+                drop(unsafe { Box::from_raw(cur) });
+            } else {
+                self.push_garbage(cur);
+            }
+
+            cur = next;
+        }
+    }
+}
+
+impl<T: Ord> ConcurrentListSet<T> {
+    /// Checks whether the set contains the given element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::concurrent_list_set::ConcurrentListSet;
+    /// let set = ConcurrentListSet::new();
+    /// set.insert(3);
+    ///
+    /// assert!( set.contains(&3) );
+    /// assert!( !set.contains(&4) );
+    /// ```
+    pub fn contains(&self, element: &T) -> bool {
+        let _guard = self.collector.pin();
+        let mut cur = self.head.load(Acquire);
+
+        // SAFETY: the guard keeps every node we can reach alive.
+        while let Some(node) = unsafe { unmarked(cur).as_ref() } {
+            let next = node.next.load(Acquire);
+
+            match node.elem.cmp(element) {
+                Less    => cur = next,
+                Equal   => return !is_marked(next),
+                Greater => return false,
+            }
+        }
+
+        false
+    }
+
+    /// Adds the element to the set.
+    ///
+    /// Returns `true` if the set did not previously contain the element,
+    /// and `false` if it did.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::concurrent_list_set::ConcurrentListSet;
+    /// let set = ConcurrentListSet::new();
+    ///
+    /// assert!( set.insert(3) );
+    /// assert!( !set.insert(3) );
+    /// ```
+    pub fn insert(&self, element: T) -> bool {
+        let guard = self.collector.pin();
+        let new = Node::new(element);
+        // SAFETY: `new` is ours until it is linked in.
+        let new_ref = unsafe { &*new };
+
+        loop {
+            let (left, right) = self.search(&new_ref.elem, &guard);
+
+            // SAFETY: the guard keeps `right` alive.
+            if unsafe { right.as_ref() }.is_some_and(|node| node.elem == new_ref.elem) {
+                drop(unsafe { Box::from_raw(new) });
+                return false;
+            }
+
+            new_ref.next.store(right, Relaxed);
+
+            if left.compare_exchange(right, new, AcqRel, Acquire).is_ok() {
+                self.len.fetch_add(1, Relaxed);
+                return true;
+            }
+        }
+    }
+
+    /// Removes the given element from the set.
+    ///
+    /// Returns whether the element was present. The element itself is
+    /// dropped later, once no other thread can be reading it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::concurrent_list_set::ConcurrentListSet;
+    /// let set = ConcurrentListSet::new();
+    /// set.insert(3);
+    ///
+    /// assert!( set.remove(&3) );
+    /// assert!( !set.remove(&3) );
+    /// ```
+    pub fn remove(&self, element: &T) -> bool {
+        let guard = self.collector.pin();
+
+        let (left, right, right_next) = loop {
+            let (left, right) = self.search(element, &guard);
+
+            // SAFETY: the guard keeps `right` alive.
+            let node = match unsafe { right.as_ref() } {
+                Some(node) if node.elem == *element => node,
+                _ => return false,
+            };
+
+            let right_next = node.next.load(Acquire);
+
+            // Marking the link is what removes the element.
+            if !is_marked(right_next)
+                && node.next.compare_exchange(right_next, marked(right_next), AcqRel, Acquire).is_ok()
+            {
+                break (left, right, right_next);
+            }
+        };
+
+        self.len.fetch_sub(1, Relaxed);
+
+        if left.compare_exchange(right, right_next, AcqRel, Acquire).is_ok() {
+            self.retire(right, &guard);
+        } else {
+            // Some other thread is in the way; let `search` unlink it.
+            self.search(element, &guard);
+        }
+
+        true
+    }
+
+    // Returns the first unmarked node not less than `element`, or null, and
+    // the link to it from the last unmarked node before it, having unlinked
+    // any marked nodes in between.
+    fn search<'g>(&'g self, element: &T, guard: &'g Guard) -> (&'g AtomicPtr<Node<T>>, *mut Node<T>) {
+        'retry: loop {
+            let mut left = &self.head;
+            let mut left_next = self.head.load(Acquire);
+            let mut cur = left_next;
+
+            let right = loop {
+                // SAFETY: the guard keeps every node we can reach alive.
+                let node = match unsafe { unmarked(cur).as_ref() } {
+                    Some(node) => node,
+                    None => break ptr::null_mut(),
+                };
+
+                let next = node.next.load(Acquire);
+
+                if !is_marked(next) {
+                    if node.elem >= *element {
+                        break unmarked(cur);
+                    }
+
+                    left = &node.next;
+                    left_next = next;
+                }
+
+                cur = next;
+            };
+
+            if left_next != right {
+                if left.compare_exchange(left_next, right, AcqRel, Acquire).is_err() {
+                    continue 'retry;
+                }
+
+                // The exchange unlinked the marked nodes from `left_next` up
+                // to `right`, whose links can no longer change.
+                let mut dead = left_next;
+
+                while dead != right {
+                    // SAFETY: as above.
+                    let next = unmarked(unsafe { &*dead }.next.load(Relaxed));
+                    self.retire(dead, guard);
+                    dead = next;
+                }
+            }
+
+            // SAFETY: as above.
+            match unsafe { right.as_ref() } {
+                Some(node) if is_marked(node.next.load(Acquire)) => continue 'retry,
+                _ => return (left, right),
+            }
+        }
+    }
+}
+
+impl<T> Default for ConcurrentListSet<T> {
+    fn default() -> Self {
+        ConcurrentListSet::new()
+    }
+}
+
+impl<T> Drop for ConcurrentListSet<T> {
+    fn drop(&mut self) {
+        let mut cur = unmarked(*self.head.get_mut());
+
+        while !cur.is_null() {
+            // SAFETY: we own the set, and linked nodes are never garbage.
+            let node = unsafe { Box::from_raw(cur) };
+            cur = unmarked(node.next.load(Relaxed));
+        }
+
+        let mut cur = *self.garbage.get_mut();
+
+        while !cur.is_null() {
+            // SAFETY: as above.
+            let node = unsafe { Box::from_raw(cur) };
+            cur = node.retired_next.load(Relaxed);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ConcurrentListSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let _guard = self.collector.pin();
+        let mut set = f.debug_set();
+        let mut cur = self.head.load(Acquire);
+
+        // SAFETY: as in `contains`.
+        while let Some(node) = unsafe { unmarked(cur).as_ref() } {
+            cur = node.next.load(Acquire);
+
+            if !is_marked(cur) {
+                set.entry(&node.elem);
+            }
+        }
+
+        set.finish()
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::ConcurrentListSet;
+    use quickcheck::quickcheck;
+    use std::collections::BTreeSet;
+
+    quickcheck! {
+        fn prop_model(ops: Vec<(u8, u8)>) -> bool {
+            let set = ConcurrentListSet::new();
+            let mut model = BTreeSet::new();
+
+            let agrees = ops.into_iter().all(|(op, elem)| {
+                let elem = elem % 32;
+
+                match op % 3 {
+                    0 => set.insert(elem) == model.insert(elem),
+                    1 => set.remove(&elem) == model.remove(&elem),
+                    _ => set.contains(&elem) == model.contains(&elem),
+                }
+            });
+
+            agrees
+                && set.len() == model.len()
+                && format!("{:?}", set) == format!("{:?}", model)
+                && set.into_set().iter().eq(model.iter())
+        }
+    }
+
+    // Enough churn from two threads for the collector to free nodes while
+    // the set is shared, yet small enough to run under Miri, which skips
+    // the stress tests.
+    #[test]
+    fn reclamation_small() {
+        let set = ConcurrentListSet::new();
+
+        std::thread::scope(|scope| {
+            for i in 0 .. 2 {
+                let set = &set;
+                scope.spawn(move || {
+                    for key in 0 .. 2 * super::COLLECT_EVERY {
+                        set.insert(2 * key + i);
+                        set.remove(&(2 * key + i));
+                    }
+                });
+            }
+        });
+
+        assert!( set.is_empty() );
+    }
+}
+
+#[cfg(test)]
+mod stress_tests {
+    use super::ConcurrentListSet;
+    use std::collections::BTreeSet;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::{Relaxed, SeqCst}};
+    use std::sync::Arc;
+    use std::thread;
+
+    const THREADS: usize = 8;
+
+    // A cheap per-thread random number generator.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 as usize
+        }
+    }
+
+    // Each thread owns the keys congruent to its index, so its own results
+    // must match a sequential model even though the list is shared.
+    #[test]
+    fn disjoint_keys() {
+        let set = ConcurrentListSet::new();
+
+        let models: Vec<BTreeSet<usize>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0 .. THREADS).map(|i| {
+                let set = &set;
+                scope.spawn(move || {
+                    let mut rng = XorShift(i as u64 + 1);
+                    let mut model = BTreeSet::new();
+
+                    for _ in 0 .. 20_000 {
+                        let key = rng.next() % 64 * THREADS + i;
+
+                        match rng.next() % 3 {
+                            0 => assert_eq!( model.insert(key), set.insert(key) ),
+                            1 => assert_eq!( model.remove(&key), set.remove(&key) ),
+                            _ => assert_eq!( model.contains(&key), set.contains(&key) ),
+                        }
+                    }
+
+                    model
+                })
+            }).collect();
+
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        let expected: BTreeSet<usize> = models.into_iter().flatten().collect();
+        assert_eq!( expected.len(), set.len() );
+        assert!( set.into_set().iter().eq(expected.iter()) );
+    }
+
+    // All threads fight over a few keys. Successful inserts and removes of
+    // a key must alternate, starting with an insert, so per key they differ
+    // by at most one, and by exactly one when the key ends up present.
+    #[test]
+    fn contended_keys() {
+        const KEYS: usize = 8;
+
+        let set = ConcurrentListSet::new();
+        let inserts: Vec<AtomicUsize> = (0 .. KEYS).map(|_| AtomicUsize::new(0)).collect();
+        let removes: Vec<AtomicUsize> = (0 .. KEYS).map(|_| AtomicUsize::new(0)).collect();
+
+        thread::scope(|scope| {
+            for i in 0 .. THREADS {
+                let (set, inserts, removes) = (&set, &inserts, &removes);
+                scope.spawn(move || {
+                    let mut rng = XorShift(0x9e37_79b9 + i as u64);
+
+                    for _ in 0 .. 20_000 {
+                        let key = rng.next() % KEYS;
+
+                        if rng.next().is_multiple_of(2) {
+                            if set.insert(key) {
+                                inserts[key].fetch_add(1, Relaxed);
+                            }
+                        } else if set.remove(&key) {
+                            removes[key].fetch_add(1, Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut present = 0;
+
+        for key in 0 .. KEYS {
+            let net = inserts[key].load(Relaxed) - removes[key].load(Relaxed);
+            assert!( net <= 1, "key {} inserted {} more times than removed", key, net );
+            assert_eq!( net == 1, set.contains(&key) );
+            present += net;
+        }
+
+        assert_eq!( present, set.len() );
+    }
+
+    // One thread inserts even keys in ascending order while another churns
+    // odd keys. Once a reader sees an even key, it must see every smaller
+    // one, since those inserts finished before that key's began.
+    #[test]
+    fn ordered_visibility() {
+        const EVENS: usize = 2_000;
+
+        let set = ConcurrentListSet::new();
+        let done = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            let (set, done) = (&set, &done);
+
+            scope.spawn(move || {
+                for key in 0 .. EVENS {
+                    set.insert(2 * key);
+                }
+                done.store(true, SeqCst);
+            });
+
+            scope.spawn(move || {
+                let mut rng = XorShift(7);
+                while !done.load(SeqCst) {
+                    let key = 2 * (rng.next() % EVENS) + 1;
+                    if rng.next().is_multiple_of(2) { set.insert(key); } else { set.remove(&key); }
+                }
+            });
+
+            for i in 0 .. THREADS - 2 {
+                scope.spawn(move || {
+                    let mut rng = XorShift(100 + i as u64);
+                    while !done.load(SeqCst) {
+                        let key = 2 * (rng.next() % EVENS);
+                        if set.contains(&key) {
+                            let smaller = 2 * (rng.next() % (key / 2 + 1));
+                            assert!( set.contains(&smaller), "saw {} but not {}", key, smaller );
+                        }
+                    }
+                });
+            }
+        });
+
+        assert!( (0 .. EVENS).all(|key| set.contains(&(2 * key))) );
+    }
+
+    // An element that counts its drops.
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct Counted(usize, #[allow(dead_code)] Arc<()>);
+
+    // Removed elements are freed while the set is in use, and every element
+    // is dropped exactly once in the end.
+    #[test]
+    fn reclamation() {
+        let token = Arc::new(());
+        let set = ConcurrentListSet::new();
+
+        thread::scope(|scope| {
+            for i in 0 .. THREADS {
+                let (set, token) = (&set, &token);
+                scope.spawn(move || {
+                    let mut rng = XorShift(31 + i as u64);
+
+                    for _ in 0 .. 10_000 {
+                        let key = rng.next() % 32;
+                        set.insert(Counted(key, Arc::clone(token)));
+                        set.remove(&Counted(key, Arc::clone(token)));
+                    }
+                });
+            }
+        });
+
+        // Without reclamation, every removed node would still hold a count.
+        let live = Arc::strong_count(&token) - 1;
+        assert!( live < THREADS * 10_000 / 2, "{} elements still allocated", live );
+
+        drop(set);
+        assert_eq!( 1, Arc::strong_count(&token) );
+    }
+}
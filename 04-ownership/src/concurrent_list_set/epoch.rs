@@ -0,0 +1,147 @@
+//! Epoch-based reclamation for the nodes of a `ConcurrentListSet`.
+//!
+//! A thread pins the collector for the length of each operation, and while
+//! pinned may hold pointers to nodes that other threads unlink. The global
+//! epoch only advances once every pinned thread has seen its current value,
+//! so a node unlinked in epoch `e` is unreachable to every thread by the
+//! time the epoch reaches `e + 2`, and can be freed.
+
+use alloc::boxed::Box;
+use core::ptr;
+use core::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize};
+use core::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
+
+/// The epochs at which garbage may be freed.
+pub(super) struct Collector {
+    epoch:        AtomicUsize,
+    participants: AtomicPtr<Participant>,
+}
+// Invariant: `participants` is a push-only list, freed only on drop.
+
+struct Participant {
+    // `epoch << 1 | 1` while pinned, or 0.
+    state:  AtomicUsize,
+    in_use: AtomicBool,
+    next:   *mut Participant,
+}
+
+/// Proof that a thread is pinned. Pointers read from the set stay valid
+/// until the guard drops.
+pub(super) struct Guard<'a> {
+    collector:   &'a Collector,
+    participant: &'a Participant,
+}
+
+impl Collector {
+    pub(super) fn new() -> Self {
+        Collector {
+            epoch:        AtomicUsize::new(0),
+            participants: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Pins the current thread at the global epoch.
+    pub(super) fn pin(&self) -> Guard<'_> {
+        let participant = self.acquire();
+        let epoch = self.epoch.load(Relaxed);
+        participant.state.store(epoch << 1 | 1, Relaxed);
+        // Orders the pin before any load of the set's links.
+        fence(SeqCst);
+
+        Guard {
+            collector: self,
+            participant,
+        }
+    }
+
+    // Claims a participant record that no guard is using, adding one if
+    // they are all taken.
+    fn acquire(&self) -> &Participant {
+        let mut cur = self.participants.load(Acquire);
+
+        // SAFETY: participants are published with `Release` and only freed
+        // by `drop`, which has `self` uniquely.
+        while let Some(participant) = unsafe { cur.as_ref() } {
+            if participant.in_use.compare_exchange(false, true, Acquire, Relaxed).is_ok() {
+                return participant;
+            }
+
+            cur = participant.next;
+        }
+
+        let new = Box::into_raw(Box::new(Participant {
+            state:  AtomicUsize::new(0),
+            in_use: AtomicBool::new(true),
+            next:   ptr::null_mut(),
+        }));
+        let mut head = self.participants.load(Relaxed);
+
+        loop {
+            // SAFETY: `new` is not published until the exchange succeeds.
+            unsafe { (*new).next = head; }
+
+            match self.participants.compare_exchange_weak(head, new, Release, Relaxed) {
+                Ok(_) => return unsafe { &*new },
+                Err(now) => head = now,
+            }
+        }
+    }
+}
+
+impl Drop for Collector {
+    fn drop(&mut self) {
+        let mut cur = *self.participants.get_mut();
+
+        while !cur.is_null() {
+            // SAFETY: every participant was allocated by `acquire`, and no
+            // guard can outlive the collector.
+            let participant = unsafe { Box::from_raw(cur) };
+            cur = participant.next;
+        }
+    }
+}
+
+impl<'a> Guard<'a> {
+    /// Returns the epoch to record for a node that this thread has just
+    /// unlinked.
+    pub(super) fn retire_epoch(&self) -> usize {
+        // Orders the unlinking before the load of the epoch.
+        fence(SeqCst);
+        self.collector.epoch.load(Relaxed)
+    }
+
+    /// Advances the global epoch if every pinned thread has seen it, and
+    /// returns the global epoch.
+    pub(super) fn try_advance(&self) -> usize {
+        let global = self.collector.epoch.load(Relaxed);
+        fence(SeqCst);
+
+        let mut cur = self.collector.participants.load(Acquire);
+
+        // SAFETY: as in `Collector::acquire`.
+        while let Some(participant) = unsafe { cur.as_ref() } {
+            let state = participant.state.load(Relaxed);
+
+            if state & 1 == 1 && state >> 1 != global {
+                return global;
+            }
+
+            cur = participant.next;
+        }
+
+        fence(Acquire);
+
+        let next = global + 1;
+        match self.collector.epoch.compare_exchange(global, next, Release, Relaxed) {
+            Ok(_) => next,
+            Err(now) => now,
+        }
+    }
+}
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) {
+        self.participant.state.store(0, Release);
+        self.participant.in_use.store(false, Release);
+    }
+}
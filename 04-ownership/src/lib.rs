@@ -1,2 +1,16 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub mod list_set;
+pub mod skip_set;
+pub mod sorted_vec_set;
+pub mod unrolled_set;
 pub mod bst;
+pub mod concurrent_list_set;
+pub mod bit_set;
+#[cfg(feature = "std")]
+pub mod codec;
+mod merge;
+#[cfg(test)]
+mod testing;
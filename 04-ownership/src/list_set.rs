@@ -1,9 +1,42 @@
 //! Sets, represented as sorted, singly-linked lists.
 
-use std::cmp::Ordering::{self, Less, Equal, Greater};
-use std::default::Default;
-use std::iter::{Extend, FromIterator};
-use std::mem;
+#[cfg(feature = "std")]
+use crate::codec::{self, Decode, Encode};
+use crate::merge::{self, Ascending};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering::{self, Less, Equal, Greater};
+use core::default::Default;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator, Peekable};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+use core::ops::{Bound, RangeBounds};
+use core::ptr::NonNull;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+pub mod bag;
+mod combinatorics;
+pub mod list_map;
+mod parse;
+pub mod persistent;
+mod pool;
+pub mod range_set;
+mod set_ops;
+
+pub use self::bag::Bag;
+pub use self::combinatorics::{Partitions, Product, Subsets, SubsetsOfSize};
+pub use self::list_map::ListMap;
+pub use self::parse::{ParseSetError, ParseSetErrorKind};
+pub use self::persistent::PersistentSet;
+pub use self::pool::PoolStats;
+pub use self::range_set::RangeSet;
+pub use self::set_ops::SetOps;
+
+use self::pool::Pool;
 
 /// A set of elements of type `T`.
 ///
@@ -21,35 +54,62 @@ use std::mem;
 ///     set.insert("c");
 /// }
 /// ```
-#[derive(Debug)]
 pub struct Set<T> {
-    head: Link<T>,
-    len:  usize,
+    head:   Link<T>,
+    tail:   Link<T>,
+    len:    usize,
+    pool:   Pool<T>,
+    marker: PhantomData<Box<Node<T>>>,
 }
-// Invariant: the elements must be sorted according to <T as Ord>.
+// Invariants:
+//  - The elements must be sorted according to <T as Ord>.
+//  - Nodes are allocated and freed through `pool`, by `CursorMut`.
+//  - Each link owns the node it points to, as a `Box` would, but nodes are
+//    only ever reached through the raw pointers, so that `tail` stays valid
+//    however the links before it are borrowed.
+//  - `tail` points to the last node, or is `None` if the set is empty.
 
-type Link<T> = Option<Box<Node<T>>>;
+type Link<T> = Option<NonNull<Node<T>>>;
 
-#[derive(Debug)]
 struct Node<T> {
     data: T,
     link: Link<T>,
 }
 
+// The set owns its elements, as a `Box<Node<T>>` would.
+unsafe impl<T: Send> Send for Set<T> {}
+unsafe impl<T: Sync> Sync for Set<T> {}
+
 impl<T> Drop for Set<T> {
     fn drop(&mut self) {
         let mut head = self.head.take();
 
-        while let Some(next) = head.take() {
-            head = next.link;
+        while let Some(node) = head {
+            // SAFETY: the set owns the node, and nothing can reach it once
+            // `head` has moved past it.
+            head = unsafe { Box::from_raw(node.as_ptr()) }.link;
         }
     }
 }
 
-impl<T> Node<T> {
-    fn new(data: T, link: Link<T>) -> Option<Box<Self>> {
-        Some(Box::new(Node { data, link }))
-    }
+// SAFETY: for these four, `node` must point to a node of a set that is
+// borrowed for `'a`, uniquely for the `_mut` ones, and the result must not
+// outlive it.
+
+unsafe fn node_data<'a, T>(node: NonNull<Node<T>>) -> &'a T {
+    &(*node.as_ptr()).data
+}
+
+unsafe fn node_data_mut<'a, T>(node: NonNull<Node<T>>) -> &'a mut T {
+    &mut (*node.as_ptr()).data
+}
+
+unsafe fn node_link<T>(node: NonNull<Node<T>>) -> Link<T> {
+    (*node.as_ptr()).link
+}
+
+unsafe fn node_link_mut<'a, T>(node: NonNull<Node<T>>) -> &'a mut Link<T> {
+    &mut (*node.as_ptr()).link
 }
 
 impl<T> Set<T> {
@@ -64,11 +124,69 @@ impl<T> Set<T> {
     /// ```
     pub fn new() -> Self {
         Set {
-            len:  0,
-            head: None,
+            len:    0,
+            head:   None,
+            tail:   None,
+            pool:   Pool::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates a new, empty list-set that keeps up to `capacity` freed
+    /// nodes for reuse.
+    ///
+    /// Nodes freed by `remove`, `drain_filter`, the cursor and the in-place
+    /// set operations go to the pool while it has room, and `insert` takes
+    /// them back before asking the allocator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// let mut set = Set::with_node_pool(8);
+    ///
+    /// set.insert(1);
+    /// set.remove(&1);
+    /// set.insert(2);
+    ///
+    /// let stats = set.pool_stats();
+    /// assert_eq!( (1, 1), (stats.hits, stats.misses) );
+    /// ```
+    pub fn with_node_pool(capacity: usize) -> Self {
+        Set {
+            len:    0,
+            head:   None,
+            tail:   None,
+            pool:   Pool::with_capacity(capacity),
+            marker: PhantomData,
         }
     }
 
+    /// Frees the nodes held in the pool, keeping its capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// let mut set = Set::with_node_pool(8);
+    ///
+    /// set.insert(1);
+    /// set.remove(&1);
+    /// assert_eq!( 1, set.pool_stats().pooled );
+    ///
+    /// set.shrink_pool();
+    /// assert_eq!( 0, set.pool_stats().pooled );
+    /// ```
+    pub fn shrink_pool(&mut self) {
+        self.pool.shrink();
+    }
+
+    /// Returns how many node allocations the pool has served and how many
+    /// went to the allocator, and how full the pool is.
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+
     /// Returns whether a set is empty.
     ///
     /// # Example
@@ -124,18 +242,142 @@ impl<T> Set<T> {
     ///
     /// assert_eq!( result, &[&1, &3, &5] );
     /// ```
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         self.into_iter()
     }
 
+    /// Returns the least element of the set, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// assert_eq!( Some(&1), Set::from_iter(vec![3, 1, 2]).first() );
+    /// assert_eq!( None, Set::<i32>::new().first() );
+    /// ```
+    pub fn first(&self) -> Option<&T> {
+        // SAFETY: the set is borrowed for as long as the result.
+        self.head.map(|node| unsafe { node_data(node) })
+    }
+
+    /// Returns the greatest element of the set, if any, in O(1) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// assert_eq!( Some(&3), Set::from_iter(vec![3, 1, 2]).last() );
+    /// assert_eq!( None, Set::<i32>::new().last() );
+    /// ```
+    pub fn last(&self) -> Option<&T> {
+        // SAFETY: as in `first`.
+        self.tail.map(|node| unsafe { node_data(node) })
+    }
+
+    /// Removes and returns the least element of the set, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = Set::from_iter(vec![2, 1]);
+    ///
+    /// assert_eq!( Some(1), set.pop_first() );
+    /// assert_eq!( Some(2), set.pop_first() );
+    /// assert_eq!( None, set.pop_first() );
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        CursorMut::new(self).remove_current()
+    }
+
+    /// Returns the `n`th least element of the set, counting from zero, in
+    /// O(n) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(vec![30, 10, 20]);
+    ///
+    /// assert_eq!( Some(&20), set.nth(1) );
+    /// assert_eq!( None, set.nth(3) );
+    /// ```
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    /// Builds a set from elements that are already strictly ascending, in
+    /// O(1) time per element.
+    ///
+    /// The order is not checked. If the elements are out of order or
+    /// repeated, the resulting set will misbehave (though never unsafely);
+    /// use `try_from_sorted` to check the order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// let set = Set::from_sorted_unchecked(0 .. 5);
+    /// assert_eq!(5, set.len());
+    /// assert!(set.contains(&4));
+    /// ```
+    pub fn from_sorted_unchecked<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut result = Set::new();
+
+        {
+            let mut cur = CursorMut::new(&mut result);
+
+            for elem in iter {
+                cur.insert_unchecked(elem);
+                cur.move_next();
+            }
+        }
+
+        result
+    }
+
+    /// Returns a cursor at the first element of the set.
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor::new(self)
+    }
+
+    /// Returns a cursor at the first element of the set that can edit the
+    /// set in place.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut::new(self)
+    }
+
+    /// Keeps only the elements that satisfy a predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = Set::from_iter(1 .. 10);
+    ///
+    /// set.retain(|elem| elem % 3 == 0);
+    /// assert_eq!(set, Set::from_iter(vec![3, 6, 9]));
+    /// ```
+    pub fn retain<P: FnMut(&T) -> bool>(&mut self, mut pred: P) {
+        self.drain_filter(|elem| !pred(elem)).for_each(drop);
+    }
+
     /// Returns an iterator that removes and returns elements satisfying a predicate, leaving the
     /// rest in the set.
-    pub fn drain_filter<P: FnMut(&T) -> bool>(&mut self, pred: P) -> DrainFilter<T, P> {
-        let len = self.len;
+    pub fn drain_filter<P: FnMut(&T) -> bool>(&mut self, pred: P) -> DrainFilter<'_, T, P> {
         DrainFilter {
             cursor: CursorMut::new(self),
             pred,
-            len,
         }
     }
 }
@@ -164,17 +406,54 @@ impl<T: Ord> Set<T> {
     /// assert!(!set.contains(&6));
     /// ```
     pub fn contains(&self, element: &T) -> bool {
-        let mut current = &self.head;
+        self.cursor_front().seek(element)
+    }
 
-        while let Some(ref node) = *current {
-            match element.cmp(&node.data) {
-                Less => return false,
-                Equal => return true,
-                Greater => current = &node.link,
+    /// Builds a set from elements that should be strictly ascending, in O(1)
+    /// time per element.
+    ///
+    /// Returns `Err` of the first element that is not greater than its
+    /// predecessor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// assert!( Set::try_from_sorted(vec![1, 2, 4]).is_ok() );
+    /// assert_eq!( Err(2), Set::try_from_sorted(vec![1, 3, 2]) );
+    /// assert_eq!( Err(3), Set::try_from_sorted(vec![1, 3, 3]) );
+    /// ```
+    pub fn try_from_sorted<I: IntoIterator<Item=T>>(iter: I) -> Result<Self, T> {
+        let mut result = Set::new();
+
+        {
+            let mut cur = CursorMut::new(&mut result);
+
+            for elem in iter {
+                cur.insert(elem)?;
+                cur.move_next();
             }
         }
 
-        false
+        Ok(result)
+    }
+
+    // Takes the longest ascending prefix of `iter` as a set, dropping any
+    // element equal to its predecessor.
+    fn ascending_run<I: Iterator<Item=T>>(iter: &mut Peekable<I>) -> Self {
+        let mut result = Set::new();
+
+        {
+            let mut cur = CursorMut::new(&mut result);
+
+            while let Some(elem) = iter.next_if(|elem| cur.prev().is_none_or(|prev| prev <= elem)) {
+                if cur.insert(elem).is_ok() {
+                    cur.move_next();
+                }
+            }
+        }
+
+        result
     }
 
     /// Adds the element to the set.
@@ -200,15 +479,11 @@ impl<T: Ord> Set<T> {
     pub fn insert(&mut self, element: T) -> bool {
         let mut cur = CursorMut::new(self);
 
-        while let Some(data) = cur.data() {
-            match element.cmp(data) {
-                Less => break,
-                Equal => return false,
-                Greater => cur.advance(),
-            }
+        if cur.seek(&element) {
+            return false;
         }
 
-        cur.insert(element);
+        cur.insert_unchecked(element);
 
         true
     }
@@ -237,11 +512,11 @@ impl<T: Ord> Set<T> {
                     let old_data = mem::replace(data, element);
                     return Some(old_data);
                 }
-                Greater => cur.advance(),
+                Greater => cur.move_next(),
             }
         }
 
-        cur.insert(element);
+        cur.insert_unchecked(element);
 
         None
     }
@@ -267,15 +542,161 @@ impl<T: Ord> Set<T> {
     pub fn remove(&mut self, element: &T) -> Option<T> {
         let mut cur = CursorMut::new(self);
 
-        while let Some(data) = cur.data() {
+        if cur.seek(element) {
+            cur.remove_current()
+        } else {
+            None
+        }
+    }
+
+    /// Removes and returns the element equal to the given one, if any.
+    ///
+    /// This is the same as `remove`, under the name that `BTreeSet` uses.
+    pub fn take(&mut self, element: &T) -> Option<T> {
+        self.remove(element)
+    }
+
+    /// Returns the element of the set equal to the given one, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(vec![1, 3]);
+    ///
+    /// assert_eq!( Some(&3), set.get(&3) );
+    /// assert_eq!( None, set.get(&2) );
+    /// ```
+    pub fn get(&self, element: &T) -> Option<&T> {
+        let mut cur = self.cursor_front();
+
+        if cur.seek(element) {
+            cur.peek()
+        } else {
+            None
+        }
+    }
+
+    /// Finds the position of the given element in the set.
+    ///
+    /// As with `slice::binary_search`, returns `Ok` of its index if it is
+    /// present, or else `Err` of the index where it would be inserted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(vec![10, 20, 30]);
+    ///
+    /// assert_eq!( Ok(1), set.position(&20) );
+    /// assert_eq!( Err(1), set.position(&15) );
+    /// assert_eq!( Err(3), set.position(&35) );
+    /// ```
+    pub fn position(&self, element: &T) -> Result<usize, usize> {
+        for (index, data) in self.iter().enumerate() {
             match element.cmp(data) {
-                Less => break,
-                Equal => return cur.remove(),
-                Greater => cur.advance(),
+                Less    => return Err(index),
+                Equal   => return Ok(index),
+                Greater => (),
             }
         }
 
-        None
+        Err(self.len)
+    }
+
+    /// Returns an iterator over the elements of the set that lie within
+    /// the given range, in ascending order.
+    ///
+    /// Finding the ends of the range takes time proportional to the
+    /// position of its end, so the iterator knows its exact length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(1 .. 10);
+    ///
+    /// assert_eq!( set.range(3 .. 6).collect::<Vec<_>>(), &[&3, &4, &5] );
+    /// assert_eq!( set.range(7 ..).len(), 3 );
+    /// assert_eq!( set.range(..= 2).collect::<Vec<_>>(), &[&1, &2] );
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        let mut cur = self.cursor_front();
+        let mut skipped = 0;
+
+        while cur.peek().is_some_and(|elem| below(range.start_bound(), elem)) {
+            cur.move_next();
+            skipped += 1;
+        }
+
+        let iter = Iter {
+            link:   cur.link,
+            len:    self.len - skipped,
+            marker: PhantomData,
+        };
+        let len = Iter { ..iter }
+            .take_while(|elem| !above(range.end_bound(), elem))
+            .count();
+
+        Range { iter, len }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Encode> Set<T> {
+    /// Writes the set to `writer` as a length followed by the elements in
+    /// ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(vec![3u32, 1, 2]);
+    /// let mut buf = Vec::new();
+    /// set.write_to(&mut buf).unwrap();
+    ///
+    /// assert_eq!( set, Set::read_from(buf.as_slice()).unwrap() );
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        codec::encode_len(self.len, &mut writer)?;
+
+        for elem in self {
+            elem.encode(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord + Decode> Set<T> {
+    /// Reads a set written by `write_to`.
+    ///
+    /// Fails with `codec::Error::Unsorted` unless the elements are strictly
+    /// ascending.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, codec::Error> {
+        let len = codec::decode_len(&mut reader)?;
+        let mut result = Set::new();
+
+        {
+            let mut cur = result.cursor_front_mut();
+
+            for _ in 0 .. len {
+                cur.insert(T::decode(&mut reader)?)
+                    .map_err(|_| codec::Error::Unsorted)?;
+                cur.move_next();
+            }
+        }
+
+        Ok(result)
     }
 }
 
@@ -286,7 +707,7 @@ mod stack_overflow_tests {
     fn iota(len: usize) -> Set<usize> {
         let mut result = Set::new();
 
-        for i in (0..len).into_iter().rev() {
+        for i in (0..len).rev() {
             result.insert(i);
         }
 
@@ -299,71 +720,360 @@ mod stack_overflow_tests {
     }
 }
 
-#[derive(Debug)]
-struct CursorMut<'a, T: 'a> {
-    link: Option<&'a mut Link<T>>,
-    len:  &'a mut usize,
+/// A cursor over the elements of a `Set`.
+///
+/// A cursor points either at an element or at the end of the set, and can
+/// only move forward.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::list_set::Set;
+/// use std::iter::FromIterator;
+///
+/// let set = Set::from_iter(vec![2, 4, 6]);
+/// let mut cur = set.cursor_front();
+///
+/// assert_eq!( Some(&2), cur.peek() );
+/// assert!(!cur.seek(&3) );
+/// assert_eq!( Some(&4), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( Some(&6), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( None, cur.peek() );
+/// ```
+pub struct Cursor<'a, T: 'a> {
+    link:   Link<T>,
+    marker: PhantomData<&'a T>,
 }
 
-impl<'a, T: 'a> CursorMut<'a, T> {
-    fn new(set: &'a mut Set<T>) -> Self {
-        CursorMut {
-            link: Some(&mut set.head),
-            len:  &mut set.len,
-        }
-    }
+unsafe impl<'a, T: Sync> Send for Cursor<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Cursor<'a, T> {}
 
-    #[allow(dead_code)]
-    fn is_empty(&self) -> bool {
-        self.link.as_ref()
-            .and_then(|o| o.as_ref())
-            .is_none()
+impl<'a, T: 'a> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor { ..*self }
     }
+}
 
-    fn data_mut(&mut self) -> Option<&mut T> {
-        self.link.as_mut()
-            .and_then(|link_ptr| link_ptr.as_mut())
-            .map(|node_ptr| &mut node_ptr.data)
+impl<'a, T: 'a> Cursor<'a, T> {
+    fn new(set: &'a Set<T>) -> Self {
+        Cursor {
+            link:   set.head,
+            marker: PhantomData,
+        }
     }
 
-    fn data(&self) -> Option<&T> {
-        self.link.as_ref()
-            .and_then(|link_ptr| link_ptr.as_ref())
-            .map(|node_ptr| &node_ptr.data)
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&'a T> {
+        // SAFETY: the set is borrowed for `'a`.
+        self.link.map(|node| unsafe { node_data(node) })
     }
 
-    fn advance(&mut self) {
-        self.link = self.link.take()
-            .and_then(|link_ptr| link_ptr.as_mut())
-            .map(|node_ptr| &mut node_ptr.link);
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        if let Some(node) = self.link {
+            // SAFETY: as in `peek`.
+            self.link = unsafe { node_link(node) };
+        }
     }
+}
 
-    fn remove(&mut self) -> Option<T> {
-        let link_ptr = self.link.as_mut()?;
-        let Node { data, link } = *link_ptr.take()?;
-        **link_ptr = link;
-        *self.len -= 1;
-        Some(data)
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for Cursor<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("current", &self.peek())
+            .finish()
     }
+}
 
-    fn insert(&mut self, data: T) {
-        let link_ptr = self.link.as_mut()
-            .expect("CursorMut::insert: empty cursor");
-        **link_ptr = Node::new(data, link_ptr.take());
-        *self.len += 1;
+impl<'a, T: Ord + 'a> Cursor<'a, T> {
+    /// Moves forward to the first element not less than `element`, and
+    /// returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is.
+    pub fn seek(&mut self, element: &T) -> bool {
+        while let Some(data) = self.peek() {
+            match element.cmp(data) {
+                Less => return false,
+                Equal => return true,
+                Greater => self.move_next(),
+            }
+        }
+
+        false
     }
 }
 
-/// An immutable iterator over the elements of a `Set`.
+/// A cursor that can edit a `Set` in place.
+///
+/// Like `Cursor`, it points either at an element or at the end of the set,
+/// and can only move forward. Its edits cannot break the ordering of the
+/// set.
 ///
 /// # Example
 ///
 /// ```
 /// # use ownership::list_set::Set;
-/// let mut set = Set::new();
+/// use std::iter::FromIterator;
 ///
-/// set.insert(2);
-/// set.insert(4);
+/// let mut set = Set::from_iter(vec![1, 3, 5, 7]);
+///
+/// {
+///     let mut cur = set.cursor_front_mut();
+///
+///     assert_eq!( Ok(()), cur.insert(0) );
+///     cur.move_next();
+///     assert_eq!( Some(1), cur.remove_current() );
+///     assert_eq!( Err(4), cur.insert(4) );
+///
+///     assert!( cur.seek(&5) );
+///     assert_eq!( Ok(()), cur.insert(4) );
+///     assert_eq!( Some(&4), cur.peek() );
+/// }
+///
+/// assert_eq!( set, Set::from_iter(vec![0, 3, 4, 5, 7]) );
+/// ```
+pub struct CursorMut<'a, T: 'a> {
+    prev:  Link<T>,
+    index: usize,
+    head:  &'a mut Link<T>,
+    tail:  &'a mut Link<T>,
+    len:   &'a mut usize,
+    pool:  &'a mut Pool<T>,
+}
+// Invariant: `prev` points to the node before the cursor, or is `None` at
+// the front, and `index` is the position of the cursor in the set.
+
+unsafe impl<'a, T: Send> Send for CursorMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for CursorMut<'a, T> {}
+
+impl<'a, T: 'a> CursorMut<'a, T> {
+    fn new(set: &'a mut Set<T>) -> Self {
+        CursorMut {
+            prev:  None,
+            index: 0,
+            head:  &mut set.head,
+            tail:  &mut set.tail,
+            len:   &mut set.len,
+            pool:  &mut set.pool,
+        }
+    }
+
+    // The node under the cursor.
+    fn link(&self) -> Link<T> {
+        match self.prev {
+            // SAFETY: the set is borrowed uniquely by the cursor.
+            Some(prev) => unsafe { node_link(prev) },
+            None => *self.head,
+        }
+    }
+
+    // The link that points to the node under the cursor.
+    fn link_mut(&mut self) -> &mut Link<T> {
+        match self.prev {
+            // SAFETY: as in `link`.
+            Some(prev) => unsafe { node_link_mut(prev) },
+            None => self.head,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn is_empty(&self) -> bool {
+        self.link().is_none()
+    }
+
+    // The element before the cursor.
+    fn prev(&self) -> Option<&T> {
+        // SAFETY: as in `link`.
+        self.prev.map(|node| unsafe { node_data(node) })
+    }
+
+    fn data_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: as in `link`, and the result borrows the cursor.
+        self.link().map(|node| unsafe { node_data_mut(node) })
+    }
+
+    // Like `data_mut`, but borrows the element for as long as the cursor
+    // could.
+    fn into_data_mut(self) -> Option<&'a mut T> {
+        // SAFETY: the set is borrowed uniquely for `'a`, and the cursor is
+        // gone.
+        self.link().map(|node| unsafe { node_data_mut(node) })
+    }
+
+    // The number of elements from the cursor on.
+    fn remaining(&self) -> usize {
+        *self.len - self.index
+    }
+
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&T> {
+        // SAFETY: as in `link`, and the result borrows the cursor.
+        self.link().map(|node| unsafe { node_data(node) })
+    }
+
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        if let Some(node) = self.link() {
+            self.prev = Some(node);
+            self.index += 1;
+        }
+    }
+
+    /// Removes and returns the element under the cursor, leaving the cursor
+    /// at the following element.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.remove_node().map(|node| self.pool.free(node))
+    }
+
+    // Moves forward to the first element for which `cmp`, comparing the
+    // sought element to it, is not `Greater`, and returns whether it is
+    // `Equal`. This lets types stored by key seek by the key alone.
+    fn seek_by<F: FnMut(&T) -> Ordering>(&mut self, mut cmp: F) -> bool {
+        while let Some(data) = self.peek() {
+            match cmp(data) {
+                Less => return false,
+                Equal => return true,
+                Greater => self.move_next(),
+            }
+        }
+
+        false
+    }
+
+    // Moves forward to the first element satisfying `pred`, and removes and
+    // returns it. Elements that fail `pred` may be changed by it, since
+    // they are passed mutably.
+    fn remove_next_where<P: FnMut(&mut T) -> bool>(&mut self, mut pred: P) -> Option<T> {
+        while let Some(data) = self.data_mut() {
+            if pred(data) {
+                return self.remove_current();
+            }
+
+            self.move_next();
+        }
+
+        None
+    }
+
+    // Inserts `data` before the cursor and moves the cursor onto it, without
+    // checking the invariant.
+    fn insert_unchecked(&mut self, data: T) {
+        let node = self.pool.alloc(data);
+        self.insert_node(node);
+    }
+
+    // Unlinks the node under the cursor, without freeing it.
+    fn remove_node(&mut self) -> Option<Box<Node<T>>> {
+        // SAFETY: the set owns the node, and unlinking it hands it to the
+        // box.
+        let mut node = unsafe { Box::from_raw(self.link()?.as_ptr()) };
+        *self.link_mut() = node.link.take();
+
+        if self.link().is_none() {
+            *self.tail = self.prev;
+        }
+
+        *self.len -= 1;
+        Some(node)
+    }
+
+    // Links `node` in before the cursor and moves the cursor onto it, without
+    // checking the invariant.
+    fn insert_node(&mut self, mut node: Box<Node<T>>) {
+        node.link = self.link();
+        let at_end = node.link.is_none();
+        let node = NonNull::from(Box::leak(node));
+        *self.link_mut() = Some(node);
+
+        if at_end {
+            *self.tail = Some(node);
+        }
+
+        *self.len += 1;
+    }
+
+    // Detaches everything from the cursor on as a new set.
+    fn split_rest(&mut self) -> Set<T> {
+        let head = self.link_mut().take();
+        let tail = if head.is_some() { mem::replace(self.tail, self.prev) } else { None };
+        let len = *self.len - self.index;
+        *self.len = self.index;
+
+        Set {
+            head,
+            tail,
+            len,
+            pool: Pool::new(),
+            marker: PhantomData,
+        }
+    }
+
+    // Attaches `rest`, which must follow the set in order, at the end, and
+    // moves the cursor onto its first element.
+    fn append_rest(&mut self, mut rest: Set<T>) {
+        self.prev = *self.tail;
+        self.index = *self.len;
+        *self.link_mut() = rest.head.take();
+
+        if let Some(tail) = rest.tail.take() {
+            *self.tail = Some(tail);
+        }
+
+        *self.len += mem::replace(&mut rest.len, 0);
+    }
+}
+
+// Shows only where the cursor is, not the links and pool it borrows.
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for CursorMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CursorMut")
+            .field("current", &self.peek())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl<'a, T: Ord + 'a> CursorMut<'a, T> {
+    /// Moves forward to the first element not less than `element`, and
+    /// returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is.
+    pub fn seek(&mut self, element: &T) -> bool {
+        self.seek_by(|data| element.cmp(data))
+    }
+
+    /// Inserts `element` just before the cursor and moves the cursor onto
+    /// it.
+    ///
+    /// Returns `Err(element)`, leaving the set unchanged, unless `element`
+    /// is greater than the previous element and less than the current one.
+    pub fn insert(&mut self, element: T) -> Result<(), T> {
+        let after_prev = self.prev().is_none_or(|prev| *prev < element);
+        let before_cur = self.peek().is_none_or(|cur| element < *cur);
+
+        if after_prev && before_cur {
+            self.insert_unchecked(element);
+            Ok(())
+        } else {
+            Err(element)
+        }
+    }
+}
+
+/// An immutable iterator over the elements of a `Set`.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::list_set::Set;
+/// let mut set = Set::new();
+///
+/// set.insert(2);
+/// set.insert(4);
 /// set.insert(3);
 ///
 /// let mut iter = (&set).into_iter();
@@ -373,23 +1083,38 @@ impl<'a, T: 'a> CursorMut<'a, T> {
 /// assert_eq!(Some(&4), iter.next());
 /// assert_eq!(None, iter.next());
 /// ```
-#[derive(Debug)]
 pub struct Iter<'a, T: 'a> {
-    link: &'a Link<T>,
-    len: usize,
+    link:   Link<T>,
+    len:    usize,
+    marker: PhantomData<&'a T>,
+}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
-        match *self.link {
-            Some(ref node_ptr) => {
-                self.link = &node_ptr.link;
-                self.len -= 1;
-                Some(&node_ptr.data)
-            }
-            None => None,
+        let node = self.link?;
+        self.len -= 1;
+
+        // SAFETY: the set is borrowed for `'a`.
+        unsafe {
+            self.link = node_link(node);
+            Some(node_data(node))
         }
     }
 
@@ -404,14 +1129,125 @@ impl<'a, T> ExactSizeIterator for Iter<'a, T> {
     }
 }
 
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> Ascending<'a, T> for Iter<'a, T> {}
+
+// A mutable iterator over the elements of a `Set`, for `ListMap`, whose
+// values can change without moving their keys. As with `CursorMut`, an
+// element must keep its place in the order.
+struct DataMut<'a, T: 'a> {
+    link:   Link<T>,
+    len:    usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<'a, T: Send> Send for DataMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for DataMut<'a, T> {}
+
+impl<T> Set<T> {
+    fn data_mut_iter(&mut self) -> DataMut<'_, T> {
+        DataMut {
+            link:   self.head,
+            len:    self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for DataMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("DataMut").field(&self.len).finish()
+    }
+}
+
+impl<'a, T> Iterator for DataMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        let node = self.link?;
+        self.len -= 1;
+
+        // SAFETY: the set is borrowed uniquely for `'a`, and the iterator
+        // never returns to the node.
+        unsafe {
+            self.link = node_link(node);
+            Some(node_data_mut(node))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for DataMut<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// Whether `elem` falls before a range that starts at `start`.
+pub(crate) fn below<T: Ord>(start: Bound<&T>, elem: &T) -> bool {
+    match start {
+        Bound::Included(start) => elem < start,
+        Bound::Excluded(start) => elem <= start,
+        Bound::Unbounded       => false,
+    }
+}
+
+// Whether `elem` falls after a range that ends at `end`.
+pub(crate) fn above<T: Ord>(end: Bound<&T>, elem: &T) -> bool {
+    match end {
+        Bound::Included(end) => elem > end,
+        Bound::Excluded(end) => elem >= end,
+        Bound::Unbounded     => false,
+    }
+}
+
+/// An iterator over the elements of a `Set` within a range.
+///
+/// Created by [`Set::range`].
+#[derive(Debug)]
+pub struct Range<'a, T: 'a> {
+    iter: Iter<'a, T>,
+    len:  usize,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Range<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> FusedIterator for Range<'a, T> {}
+
 impl<'a, T> IntoIterator for &'a Set<T> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
     fn into_iter(self) -> Iter<'a, T> {
         Iter {
-            link: &self.head,
-            len: self.len,
+            link:   self.head,
+            len:    self.len,
+            marker: PhantomData,
         }
     }
 }
@@ -442,7 +1278,7 @@ impl<T> Iterator for IntoIter<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
-        CursorMut::new(&mut self.0).remove()
+        CursorMut::new(&mut self.0).remove_current()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -465,19 +1301,43 @@ impl<T> IntoIterator for Set<T> {
     }
 }
 
+/// Elements already in the set are kept in preference to equal ones from
+/// the iterator.
 impl<T: Ord> Extend<T> for Set<T> {
     fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
-        for elem in iter {
-            self.insert(elem);
-        }
+        self.union_with(Set::from_iter(iter));
     }
 }
 
+/// Builds a set in O(n) time when the input is sorted, and O(n log n)
+/// otherwise. Of equal elements, the first is kept.
 impl<T: Ord> FromIterator<T> for Set<T> {
     fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
-        let mut result = Set::new();
-        result.extend(iter);
-        result
+        let mut iter = iter.into_iter().peekable();
+        let mut runs = Vec::new();
+
+        while iter.peek().is_some() {
+            runs.push(Set::ascending_run(&mut iter));
+        }
+
+        // Merge neighboring runs until one is left. Earlier runs stay on the
+        // left, so the first of equal elements survives.
+        while runs.len() > 1 {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(2));
+            let mut pairs = runs.into_iter();
+
+            while let Some(mut left) = pairs.next() {
+                if let Some(right) = pairs.next() {
+                    left.union_with(right);
+                }
+
+                merged.push(left);
+            }
+
+            runs = merged;
+        }
+
+        runs.pop().unwrap_or_default()
     }
 }
 
@@ -515,20 +1375,84 @@ impl<T: Ord> PartialEq for Set<T> {
 
 impl<T: Ord> Eq for Set<T> {}
 
+impl<T: Hash> Hash for Set<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Set<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[test]
+fn test_debug() {
+    let mut set: Set<usize> = vec![3, 1, 2].into_iter().collect();
+    assert_eq!( "{1, 2, 3}", format!("{:?}", set) );
+
+    let mut cur = set.cursor_front();
+    cur.move_next();
+    assert_eq!( "Cursor { current: Some(2) }", format!("{:?}", cur) );
+    assert_eq!( "[2, 3]", format!("{:?}", set.range(2 ..).iter) );
+
+    let mut cur = set.cursor_front_mut();
+    cur.move_next();
+    assert_eq!( "CursorMut { current: Some(2), index: 1 }", format!("{:?}", cur) );
+}
+
+#[test]
+fn test_send_sync() {
+    fn send_sync<T: Send + Sync>() {}
+
+    send_sync::<Set<usize>>();
+    send_sync::<Cursor<'_, usize>>();
+    send_sync::<CursorMut<'_, usize>>();
+    send_sync::<Iter<'_, usize>>();
+    send_sync::<IntoIter<usize>>();
+    send_sync::<ListMap<usize, usize>>();
+    send_sync::<list_map::IterMut<'_, usize, usize>>();
+}
+
+/// Formats the set as `{a, b, c}`, or with `{:#}`, one element per line.
+/// Either form parses back with `FromStr`.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::list_set::Set;
+/// use std::iter::FromIterator;
+///
+/// let set = Set::from_iter(vec![3, 1, 2]);
+/// assert_eq!( "{1, 2, 3}", set.to_string() );
+/// assert_eq!( "{\n    1,\n    2,\n    3,\n}", format!("{:#}", set) );
+/// ```
+impl<T: fmt::Display> fmt::Display for Set<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        merge::fmt_set(self, f)
+    }
+}
+
 impl<T: Clone> Clone for Set<T> {
     fn clone(&self) -> Self {
         let mut result = Set::new();
 
         {
-            let mut cur = &mut result.head;
+            // The clone's nodes come straight from the allocator, leaving
+            // its pool statistics at zero.
+            let mut cur = CursorMut::new(&mut result);
 
             for each in self {
-                *cur = Node::new(each.clone(), None);
-                cur  = &mut {cur}.as_mut().unwrap().link;
+                cur.insert_node(Box::new(Node { data: each.clone(), link: None }));
+                cur.move_next();
             }
         }
 
-        result.len = self.len;
         result
     }
 }
@@ -564,13 +1488,13 @@ impl<T: Ord> Set<T> {
     /// assert!(!set3.is_disjoint(&set3));
     /// ```
     pub fn is_disjoint(&self, other: &Set<T>) -> bool {
-        let mut i = &self.head;
-        let mut j = &other.head;
+        let mut i = self.cursor_front();
+        let mut j = other.cursor_front();
 
-        while let (&Some(ref ilink), &Some(ref jlink)) = (i, j) {
-            match ilink.data.cmp(&jlink.data) {
-                Less    => i = &ilink.link,
-                Greater => j = &jlink.link,
+        while let (Some(a), Some(b)) = (i.peek(), j.peek()) {
+            match a.cmp(b) {
+                Less    => i.move_next(),
+                Greater => j.move_next(),
                 Equal   => return false,
             }
         }
@@ -601,31 +1525,29 @@ impl<T: Ord> Set<T> {
     /// assert!( set3.is_subset(&set3));
     /// ```
     pub fn is_subset(&self, other: &Set<T>) -> bool {
-        let mut i = &self.head;
-        let mut j = &other.head;
+        let mut i = self.cursor_front();
+        let mut j = other.cursor_front();
 
-        while let (&Some(ref ilink), &Some(ref jlink)) = (i, j) {
-            match ilink.data.cmp(&jlink.data) {
+        while let (Some(a), Some(b)) = (i.peek(), j.peek()) {
+            match a.cmp(b) {
                 Less    => return false,
-                Greater => j = &jlink.link,
+                Greater => j.move_next(),
                 Equal   => {
-                    i = &ilink.link;
-                    j = &jlink.link;
+                    i.move_next();
+                    j.move_next();
                 }
             }
         }
 
-        i.is_none() || j.is_some()
+        i.peek().is_none() || j.peek().is_some()
     }
 
     /// Returns whether `self` is a superset of `other`.
     pub fn is_superset(&self, other: &Set<T>) -> bool {
         other.is_subset(self)
     }
-}
 
-impl<T: Ord + Clone> Set<T> {
-    /// Returns the intersection of two sets.
+    /// Returns a lazy iterator over the intersection of two sets.
     ///
     /// # Example
     ///
@@ -636,42 +1558,14 @@ impl<T: Ord + Clone> Set<T> {
     /// let set1 = Set::from_iter(vec![1, 3, 5, 7]);
     /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
     ///
-    /// let set3 = Set::from_iter(vec![1, 3]);
-    ///
-    /// assert_eq!(set3, set1.intersection(&set2));
-    /// assert_eq!(set3, set2.intersection(&set1));
+    /// let result: Vec<_> = set1.iter_intersection(&set2).collect();
+    /// assert_eq!( result, &[&1, &3] );
     /// ```
-    pub fn intersection(&self, other: &Set<T>) -> Self {
-        let mut result = Set::new();
-
-        {
-            let mut cur = CursorMut::new(&mut result);
-
-            let mut i = self.into_iter().peekable();
-            let mut j = other.into_iter().peekable();
-
-            while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
-                match a.cmp(b) {
-                    Less => {
-                        i.next();
-                    }
-                    Greater => {
-                        j.next();
-                    }
-                    Equal => {
-                        cur.insert(a.clone());
-                        cur.advance();
-                        i.next();
-                        j.next();
-                    }
-                }
-            }
-        }
-
-        result
+    pub fn iter_intersection<'a>(&'a self, other: &'a Set<T>) -> Intersection<'a, T> {
+        merge::Intersection::new(self.iter(), other.iter())
     }
 
-    /// Returns the union of two sets.
+    /// Returns a lazy iterator over the union of two sets.
     ///
     /// # Example
     ///
@@ -682,56 +1576,92 @@ impl<T: Ord + Clone> Set<T> {
     /// let set1 = Set::from_iter(vec![1, 3, 5, 7]);
     /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
     ///
-    /// let set3 = Set::from_iter(vec![1, 2, 3, 4, 5, 7]);
-    ///
-    /// assert_eq!(set3, set1.union(&set2));
-    /// assert_eq!(set3, set2.union(&set1));
+    /// let result: Vec<_> = set1.iter_union(&set2).collect();
+    /// assert_eq!( result, &[&1, &2, &3, &4, &5, &7] );
     /// ```
-    pub fn union(&self, other: &Set<T>) -> Self {
-        let mut result = Set::new();
-
-        {
-            let mut cur = CursorMut::new(&mut result);
-
-            let mut i = self.into_iter().peekable();
-            let mut j = other.into_iter().peekable();
-
-            while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
-                match a.cmp(b) {
-                    Less => {
-                        cur.insert(a.clone());
-                        cur.advance();
-                        i.next();
-                    }
-                    Greater => {
-                        cur.insert(b.clone());
-                        cur.advance();
-                        j.next();
-                    }
-                    Equal => {
-                        cur.insert(a.clone());
-                        cur.advance();
-                        i.next();
-                        j.next();
-                    }
-                }
-            }
-
-            for a in i {
-                cur.insert(a.clone());
-                cur.advance();
-            }
-
-            for b in j {
-                cur.insert(b.clone());
-                cur.advance();
-            }
-        }
-
-        result
+    pub fn iter_union<'a>(&'a self, other: &'a Set<T>) -> Union<'a, T> {
+        merge::Union::new(self.iter(), other.iter())
     }
 
-    /// Returns the difference of two sets.
+    /// Returns a lazy iterator over the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_difference(&set2).collect();
+    /// assert_eq!( result, &[&5, &7] );
+    /// ```
+    pub fn iter_difference<'a>(&'a self, other: &'a Set<T>) -> Difference<'a, T> {
+        merge::Difference::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_symmetric_difference(&set2).collect();
+    /// assert_eq!( result, &[&2, &4, &5, &7] );
+    /// ```
+    pub fn iter_symmetric_difference<'a>(&'a self, other: &'a Set<T>) -> SymmetricDifference<'a, T> {
+        merge::SymmetricDifference::new(self.iter(), other.iter())
+    }
+}
+
+impl<T: Ord + Clone> Set<T> {
+    /// Returns the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let set3 = Set::from_iter(vec![1, 3]);
+    ///
+    /// assert_eq!(set3, set1.intersection(&set2));
+    /// assert_eq!(set3, set2.intersection(&set1));
+    /// ```
+    pub fn intersection(&self, other: &Set<T>) -> Self {
+        Set::from_sorted_unchecked(self.iter_intersection(other).cloned())
+    }
+
+    /// Returns the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let set3 = Set::from_iter(vec![1, 2, 3, 4, 5, 7]);
+    ///
+    /// assert_eq!(set3, set1.union(&set2));
+    /// assert_eq!(set3, set2.union(&set1));
+    /// ```
+    pub fn union(&self, other: &Set<T>) -> Self {
+        Set::from_sorted_unchecked(self.iter_union(other).cloned())
+    }
+
+    /// Returns the difference of two sets.
     ///
     /// # Example
     ///
@@ -749,38 +1679,7 @@ impl<T: Ord + Clone> Set<T> {
     /// assert_eq!(set4, set2.difference(&set1));
     /// ```
     pub fn difference(&self, other: &Set<T>) -> Self {
-        let mut result = Set::new();
-
-        {
-            let mut cur = CursorMut::new(&mut result);
-
-            let mut i = self.into_iter().peekable();
-            let mut j = other.into_iter().peekable();
-
-            while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
-                match a.cmp(b) {
-                    Less => {
-                        cur.insert(a.clone());
-                        cur.advance();
-                        i.next();
-                    }
-                    Greater => {
-                        j.next();
-                    }
-                    Equal => {
-                        i.next();
-                        j.next();
-                    }
-                }
-            }
-
-            for a in i {
-                cur.insert(a.clone());
-                cur.advance();
-            }
-        }
-
-        result
+        Set::from_sorted_unchecked(self.iter_difference(other).cloned())
     }
 
     /// Returns the symmetric difference of two sets.
@@ -800,55 +1699,349 @@ impl<T: Ord + Clone> Set<T> {
     /// assert_eq!(set3, set2.symmetric_difference(&set1));
     /// ```
     pub fn symmetric_difference(&self, other: &Set<T>) -> Self {
-        let mut result = Set::new();
+        Set::from_sorted_unchecked(self.iter_symmetric_difference(other).cloned())
+    }
+}
 
-        {
-            let mut cur = CursorMut::new(&mut result);
+impl<T: Ord> Set<T> {
+    /// Adds every element of `other` to `self`, relinking the nodes of
+    /// `other` rather than allocating new ones.
+    ///
+    /// Where both sets hold equal elements, the one from `self` is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.union_with(set2);
+    /// assert_eq!(set1, Set::from_iter(vec![1, 2, 3, 4, 5, 7]));
+    /// ```
+    pub fn union_with(&mut self, other: Set<T>) {
+        self.merge(other, false);
+    }
 
-            let mut i = self.into_iter().peekable();
-            let mut j = other.into_iter().peekable();
+    /// Moves every element of `other` into `self`, leaving `other` empty.
+    ///
+    /// Unlike `union_with`, where both sets hold equal elements, the one
+    /// from `other` replaces the one in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = Set::from_iter(vec![1, 3]);
+    /// let mut set2 = Set::from_iter(vec![2, 3, 4]);
+    ///
+    /// set1.append(&mut set2);
+    /// assert_eq!(set1, Set::from_iter(vec![1, 2, 3, 4]));
+    /// assert!(set2.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Set<T>) {
+        self.merge(mem::take(other), true);
+    }
 
-            while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
-                match a.cmp(b) {
-                    Less => {
-                        cur.insert(a.clone());
-                        cur.advance();
-                        i.next();
-                    }
-                    Greater => {
-                        cur.insert(b.clone());
-                        cur.advance();
-                        j.next();
-                    }
-                    Equal => {
-                        i.next();
-                        j.next();
-                    }
+    // Splices the nodes of `other` into `self` in a single pass over both.
+    fn merge(&mut self, mut other: Set<T>, replace: bool) {
+        let mut cur = CursorMut::new(self);
+        let mut rest = CursorMut::new(&mut other);
+
+        while let Some(data) = rest.peek() {
+            if cur.seek(data) {
+                let mut node = rest.remove_node().unwrap();
+
+                if replace {
+                    mem::swap(cur.data_mut().unwrap(), &mut node.data);
                 }
+
+                // The spare node goes to the pool of `self`, which outlives
+                // `other`.
+                cur.pool.free(node);
+            } else if cur.peek().is_none() {
+                // Whatever is left of `other` goes at the end, all at once.
+                cur.append_rest(rest.split_rest());
+                return;
+            } else {
+                cur.insert_node(rest.remove_node().unwrap());
             }
+        }
+    }
 
-            for a in i {
-                cur.insert(a.clone());
-                cur.advance();
+    /// Replaces `self` with the symmetric difference of `self` and `other`,
+    /// relinking the nodes of `other` rather than allocating new ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.symmetric_difference_with(set2);
+    /// assert_eq!(set1, Set::from_iter(vec![2, 4, 5, 7]));
+    /// ```
+    pub fn symmetric_difference_with(&mut self, mut other: Set<T>) {
+        let mut cur = CursorMut::new(self);
+        let mut rest = CursorMut::new(&mut other);
+
+        while let Some(data) = rest.peek() {
+            if cur.seek(data) {
+                cur.pool.free(rest.remove_node().unwrap());
+                cur.remove_current();
+            } else if cur.peek().is_none() {
+                cur.append_rest(rest.split_rest());
+                return;
+            } else {
+                cur.insert_node(rest.remove_node().unwrap());
             }
+        }
+    }
 
-            for b in j {
-                cur.insert(b.clone());
-                cur.advance();
+    /// Removes every element of `self` that is not in `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.intersect_with(&set2);
+    /// assert_eq!(set1, Set::from_iter(vec![1, 3]));
+    /// ```
+    pub fn intersect_with(&mut self, other: &Set<T>) {
+        let mut cur = CursorMut::new(self);
+        let mut other = other.iter().peekable();
+
+        while let Some(data) = cur.peek() {
+            while other.next_if(|elem| *elem < data).is_some() {}
+
+            match other.peek() {
+                None => {
+                    cur.split_rest();
+                    return;
+                }
+                Some(&elem) if elem == data => cur.move_next(),
+                Some(_) => {
+                    cur.remove_current();
+                }
             }
         }
+    }
 
-        result
+    /// Removes every element of `other` from `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.subtract(&set2);
+    /// assert_eq!(set1, Set::from_iter(vec![5, 7]));
+    /// ```
+    pub fn subtract(&mut self, other: &Set<T>) {
+        let mut cur = CursorMut::new(self);
+
+        for elem in other {
+            if cur.seek(elem) {
+                cur.remove_current();
+            } else if cur.peek().is_none() {
+                return;
+            }
+        }
+    }
+
+    /// Splits the set in two at `element`, returning everything not less
+    /// than `element`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = Set::from_iter(vec![1, 3, 5, 7]);
+    ///
+    /// let set2 = set1.split_off(&4);
+    /// assert_eq!(set1, Set::from_iter(vec![1, 3]));
+    /// assert_eq!(set2, Set::from_iter(vec![5, 7]));
+    /// ```
+    pub fn split_off(&mut self, element: &T) -> Set<T> {
+        let mut cur = CursorMut::new(self);
+        cur.seek(element);
+        cur.split_rest()
+    }
+}
+
+/// Returns the union of two sets, as `Set::union`.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::list_set::Set;
+/// use std::iter::FromIterator;
+///
+/// let set1 = Set::from_iter(vec![1, 3, 5, 7]);
+/// let set2 = Set::from_iter(vec![1, 2, 3, 4]);
+///
+/// assert_eq!( Set::from_iter(vec![1, 2, 3, 4, 5, 7]), &set1 | &set2 );
+/// assert_eq!( Set::from_iter(vec![1, 3]),             &set1 & &set2 );
+/// assert_eq!( Set::from_iter(vec![5, 7]),             &set1 - &set2 );
+/// assert_eq!( Set::from_iter(vec![2, 4, 5, 7]),       &set1 ^ &set2 );
+/// ```
+impl<T: Ord + Clone> BitOr<&Set<T>> for &Set<T> {
+    type Output = Set<T>;
+
+    fn bitor(self, other: &Set<T>) -> Set<T> {
+        self.union(other)
+    }
+}
+
+/// Returns the intersection of two sets, as `Set::intersection`.
+impl<T: Ord + Clone> BitAnd<&Set<T>> for &Set<T> {
+    type Output = Set<T>;
+
+    fn bitand(self, other: &Set<T>) -> Set<T> {
+        self.intersection(other)
+    }
+}
+
+/// Returns the difference of two sets, as `Set::difference`.
+impl<T: Ord + Clone> Sub<&Set<T>> for &Set<T> {
+    type Output = Set<T>;
+
+    fn sub(self, other: &Set<T>) -> Set<T> {
+        self.difference(other)
+    }
+}
+
+/// Returns the symmetric difference of two sets, as
+/// `Set::symmetric_difference`.
+impl<T: Ord + Clone> BitXor<&Set<T>> for &Set<T> {
+    type Output = Set<T>;
+
+    fn bitxor(self, other: &Set<T>) -> Set<T> {
+        self.symmetric_difference(other)
+    }
+}
+
+/// Adds the elements of `other` in place, as `Set::union_with`.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::list_set::Set;
+/// use std::iter::FromIterator;
+///
+/// let mut set = Set::from_iter(vec![1, 3, 5, 7]);
+///
+/// set |= Set::from_iter(vec![2, 4]);
+/// set |= &Set::from_iter(vec![8]);
+/// set &= &Set::from_iter(vec![1, 2, 3, 4, 5, 8]);
+/// set -= &Set::from_iter(vec![1]);
+/// set ^= Set::from_iter(vec![4, 6]);
+/// set ^= &Set::from_iter(vec![8, 9]);
+///
+/// assert_eq!( Set::from_iter(vec![2, 3, 5, 6, 9]), set );
+/// ```
+impl<T: Ord> BitOrAssign<Set<T>> for Set<T> {
+    fn bitor_assign(&mut self, other: Set<T>) {
+        self.union_with(other);
     }
 }
 
+/// Adds clones of the elements of `other` that are missing from `self`, in
+/// a single pass over both.
+impl<T: Ord + Clone> BitOrAssign<&Set<T>> for Set<T> {
+    fn bitor_assign(&mut self, other: &Set<T>) {
+        let mut cur = CursorMut::new(self);
+
+        for elem in other {
+            if !cur.seek(elem) {
+                cur.insert_unchecked(elem.clone());
+            }
+        }
+    }
+}
+
+/// Keeps only the elements also in `other`, as `Set::intersect_with`.
+impl<T: Ord> BitAndAssign<&Set<T>> for Set<T> {
+    fn bitand_assign(&mut self, other: &Set<T>) {
+        self.intersect_with(other);
+    }
+}
+
+/// Removes the elements of `other` in place, as `Set::subtract`.
+impl<T: Ord> SubAssign<&Set<T>> for Set<T> {
+    fn sub_assign(&mut self, other: &Set<T>) {
+        self.subtract(other);
+    }
+}
+
+/// Replaces the set with its symmetric difference with `other`, as
+/// `Set::symmetric_difference_with`.
+impl<T: Ord> BitXorAssign<Set<T>> for Set<T> {
+    fn bitxor_assign(&mut self, other: Set<T>) {
+        self.symmetric_difference_with(other);
+    }
+}
+
+/// Replaces the set with its symmetric difference with `other`, cloning the
+/// elements only `other` holds.
+impl<T: Ord + Clone> BitXorAssign<&Set<T>> for Set<T> {
+    fn bitxor_assign(&mut self, other: &Set<T>) {
+        let mut cur = CursorMut::new(self);
+
+        for elem in other {
+            if cur.seek(elem) {
+                cur.remove_current();
+            } else {
+                cur.insert_unchecked(elem.clone());
+            }
+        }
+    }
+}
+
+/// A lazy iterator over the intersection of two `Set`s.
+///
+/// Created by [`Set::iter_intersection`].
+pub type Intersection<'a, T> = merge::Intersection<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the union of two `Set`s.
+///
+/// Created by [`Set::iter_union`].
+pub type Union<'a, T> = merge::Union<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the difference of two `Set`s.
+///
+/// Created by [`Set::iter_difference`].
+pub type Difference<'a, T> = merge::Difference<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the symmetric difference of two `Set`s.
+///
+/// Created by [`Set::iter_symmetric_difference`].
+pub type SymmetricDifference<'a, T> = merge::SymmetricDifference<'a, T, Iter<'a, T>>;
+
 #[derive(Debug)]
 pub struct DrainFilter<'a, T: 'a, P>
     where P: FnMut(&T) -> bool
 {
     cursor: CursorMut<'a, T>,
     pred: P,
-    len: usize,
 }
 
 impl<'a, T, P> Iterator for DrainFilter<'a, T, P>
@@ -857,21 +2050,12 @@ impl<'a, T, P> Iterator for DrainFilter<'a, T, P>
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
-        while let Some(data) = self.cursor.data() {
-            self.len -= 1;
-
-            if (self.pred)(data) {
-                return self.cursor.remove();
-            } else {
-                self.cursor.advance()
-            }
-        }
-
-        None
+        let pred = &mut self.pred;
+        self.cursor.remove_next_where(|data| pred(data))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.len))
+        (0, Some(self.cursor.remaining()))
     }
 }
 
@@ -886,15 +2070,17 @@ impl<'a, T, P> Drop for DrainFilter<'a, T, P>
 #[cfg(any(test, feature = "quickcheck"))]
 mod impl_arbitrary_for_set {
     use super::Set;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
     use quickcheck::{Arbitrary, Gen};
-    use std::iter::FromIterator;
 
     impl<T: Arbitrary + Ord> Arbitrary for Set<T> {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
             FromIterator::from_iter(Vec::<T>::arbitrary(g))
         }
 
-        fn shrink(&self) -> Box<Iterator<Item=Self>> {
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
             Box::new(Vec::from_iter(Set::clone(self))
                 .shrink()
                 .map(FromIterator::from_iter))
@@ -902,51 +2088,786 @@ mod impl_arbitrary_for_set {
     }
 }
 
+#[cfg(any(test, feature = "serde"))]
+mod impl_serde_for_set {
+    use super::Set;
+    use alloc::vec::Vec;
+    use core::fmt;
+    use core::iter::FromIterator;
+    use core::marker::PhantomData;
+    use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+    impl<T: Serialize> Serialize for Set<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.len))?;
+
+            for elem in self {
+                seq.serialize_element(elem)?;
+            }
+
+            seq.end()
+        }
+    }
+
+    impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for Set<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(SetVisitor(PhantomData))
+        }
+    }
+
+    /// The most elements `deserialize` will allocate room for before it
+    /// has read them, as in serde's own `size_hint::cautious`.
+    const MAX_PREALLOCATION: usize = 4096;
+
+    struct SetVisitor<T>(PhantomData<Set<T>>);
+
+    impl<'de, T: Deserialize<'de> + Ord> Visitor<'de> for SetVisitor<T> {
+        type Value = Set<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a sequence")
+        }
+
+        // `from_iter` restores the invariant whatever the order of the
+        // input, and takes O(n) time for the sorted order that `serialize`
+        // writes. The size hint comes from the input, so it only goes so
+        // far toward the initial capacity.
+        fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Set<T>, A::Error> {
+            let hint = access.size_hint().unwrap_or(0);
+            let mut elems = Vec::with_capacity(hint.min(MAX_PREALLOCATION));
+
+            while let Some(elem) = access.next_element()? {
+                elems.push(elem);
+            }
+
+            Ok(Set::from_iter(elems))
+        }
+    }
+
+    // A sequence that claims far more elements than it holds, as a
+    // length-prefixed format could.
+    #[cfg(test)]
+    struct Lying(alloc::vec::IntoIter<u8>);
+
+    #[cfg(test)]
+    impl<'de> SeqAccess<'de> for Lying {
+        type Error = serde::de::value::Error;
+
+        fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+            where S: serde::de::DeserializeSeed<'de>
+        {
+            use serde::de::IntoDeserializer;
+
+            self.0.next()
+                .map(|elem| seed.deserialize(elem.into_deserializer()))
+                .transpose()
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(usize::MAX)
+        }
+    }
+
+    #[test]
+    fn lying_size_hint() {
+        let set = SetVisitor(PhantomData).visit_seq(Lying(vec![2, 1].into_iter())).unwrap();
+        assert_eq!( Set::from_iter(vec![1, 2]), set );
+    }
+}
+
 #[cfg(test)]
 mod random_tests {
-    use super::Set;
+    use super::{ParseSetErrorKind, Set, SetOps};
+    use crate::testing::{agrees, lazy_ops_agree};
     use quickcheck::quickcheck;
+    use std::collections::BTreeSet;
+    #[cfg(feature = "std")]
+    use std::collections::HashSet;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::iter::FromIterator;
+
+    // Runs a property written against `SetOps` for each of `Set`,
+    // `BTreeSet` and, with the `std` feature, `HashSet`.
+    #[cfg(feature = "std")]
+    macro_rules! for_each_set_type {
+        ($prop:ident($($arg:expr),*)) => {
+            $prop::<Set<usize>>($($arg),*)
+                && $prop::<BTreeSet<usize>>($($arg),*)
+                && $prop::<HashSet<usize>>($($arg),*)
+        };
+    }
+
+    #[cfg(not(feature = "std"))]
+    macro_rules! for_each_set_type {
+        ($prop:ident($($arg:expr),*)) => {
+            $prop::<Set<usize>>($($arg),*)
+                && $prop::<BTreeSet<usize>>($($arg),*)
+        };
+    }
+
+    fn build<S: FromIterator<usize>>(elems: &[usize]) -> S {
+        elems.iter().cloned().collect()
+    }
+
+    // Checks, through `SetOps` alone, that `set` holds exactly those of
+    // `candidates` that satisfy `pred`.
+    fn holds_exactly<S, P>(set: &S, candidates: &[usize], pred: P) -> bool
+        where S: SetOps<usize>,
+              P: Fn(&usize) -> bool
+    {
+        let expected: BTreeSet<usize> = candidates.iter().cloned().filter(|e| pred(e)).collect();
+        set.len() == expected.len() && expected.iter().all(|elem| set.contains(elem))
+    }
+
+    fn member<S: SetOps<usize> + FromIterator<usize>>(vec: &[usize], elems: &[usize]) -> bool {
+        let set: S = build(vec);
+
+        elems.iter()
+            .all(|elem| vec.contains(elem) == set.contains(elem))
+    }
+
+    fn intersection<S: SetOps<usize> + FromIterator<usize>>(v1: &[usize], v2: &[usize]) -> bool {
+        let (s1, s2): (S, S) = (build(v1), build(v2));
+        let s3 = s1.intersection(&s2);
+
+        holds_exactly(&s3, &[v1, v2].concat(), |elem| s1.contains(elem) && s2.contains(elem))
+    }
+
+    fn union<S: SetOps<usize> + FromIterator<usize>>(v1: &[usize], v2: &[usize]) -> bool {
+        let (s1, s2): (S, S) = (build(v1), build(v2));
+        let s3 = s1.union(&s2);
+
+        holds_exactly(&s3, &[v1, v2].concat(), |elem| s1.contains(elem) || s2.contains(elem))
+    }
+
+    fn difference<S: SetOps<usize> + FromIterator<usize>>(v1: &[usize], v2: &[usize]) -> bool {
+        let (s1, s2): (S, S) = (build(v1), build(v2));
+        let s3 = s1.difference(&s2);
+
+        holds_exactly(&s3, &[v1, v2].concat(), |elem| s1.contains(elem) && !s2.contains(elem))
+    }
+
+    fn symmetric_difference<S: SetOps<usize> + FromIterator<usize>>(v1: &[usize], v2: &[usize]) -> bool {
+        let (s1, s2): (S, S) = (build(v1), build(v2));
+        let s3 = s1.symmetric_difference(&s2);
+
+        holds_exactly(&s3, &[v1, v2].concat(), |elem| s1.contains(elem) != s2.contains(elem))
+    }
+
+    fn relations<S: SetOps<usize> + FromIterator<usize>>(v1: &[usize], v2: &[usize]) -> bool {
+        let (s1, s2): (S, S) = (build(v1), build(v2));
+
+        s1.is_subset(&s2) == v1.iter().all(|elem| s2.contains(elem))
+            && s1.is_superset(&s2) == v2.iter().all(|elem| s1.contains(elem))
+            && s1.is_disjoint(&s2) == v1.iter().all(|elem| !s2.contains(elem))
+    }
+
+    fn insert_remove<S>(start: &[usize], ops: &[(bool, usize)]) -> bool
+        where S: SetOps<usize> + FromIterator<usize>
+    {
+        let mut set: S = build(start);
+        let mut model: BTreeSet<usize> = build(start);
+
+        ops.iter().all(|&(insert, elem)| {
+            let agrees = if insert {
+                set.insert(elem) == model.insert(elem)
+            } else {
+                set.remove(&elem) == model.remove(&elem)
+            };
+
+            agrees && set.len() == model.len() && set.is_empty() == model.is_empty()
+        }) && model.iter().all(|elem| set.contains(elem))
+    }
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Checks that `iter` yields `len` items, with an exact size hint
+    // throughout.
+    fn counts_down<I: Iterator>(mut iter: I, len: usize) -> bool {
+        (0 ..= len).rev().all(|remaining| {
+            iter.size_hint() == (remaining, Some(remaining)) && iter.next().is_some() == (remaining > 0)
+        }) && iter.next().is_none()
+    }
+
+    // Every subset of `elems`, by brute force over bitmasks.
+    fn all_subsets(elems: &[u8]) -> BTreeSet<Set<u8>> {
+        (0 .. 1u32 << elems.len())
+            .map(|mask| elems.iter().enumerate()
+                .filter(|&(i, _)| mask & 1 << i != 0)
+                .map(|(_, &elem)| elem)
+                .collect())
+            .collect()
+    }
+
+    #[test]
+    fn combinatorics_overflow() {
+        let set = Set::from_iter(0 .. 200);
+
+        assert_eq!( (usize::MAX, None), set.subsets().size_hint() );
+        assert_eq!( (usize::MAX, None), set.subsets_of_size(100).size_hint() );
+        assert_eq!( (usize::MAX, None), set.partitions().size_hint() );
+        assert_eq!( (19_900, Some(19_900)), set.subsets_of_size(2).size_hint() );
+        assert_eq!( (1, Some(1)), set.subsets_of_size(200).size_hint() );
+        assert_eq!( (0, Some(0)), set.subsets_of_size(201).size_hint() );
+
+        // Still lazy: the first few results come at once.
+        assert_eq!( Some(Set::from_iter(0 .. 200)), set.subsets().nth(200) );
+        assert_eq!( 3, set.partitions().take(3).count() );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error_at = |text: &str| {
+            let e = text.parse::<Set<u8>>().unwrap_err();
+            (e.offset(), e.into_kind())
+        };
+
+        assert_eq!( (2, ParseSetErrorKind::ExpectedOpen),    error_at("  1, 2}") );
+        assert_eq!( (1, ParseSetErrorKind::ExpectedElement), error_at("{, 1}") );
+        assert_eq!( (4, ParseSetErrorKind::ExpectedElement), error_at("{1, , 2}") );
+        assert_eq!( (5, ParseSetErrorKind::Unclosed),        error_at("{1, 2") );
+        assert_eq!( (4, ParseSetErrorKind::Unclosed),        error_at("{1, ") );
+        assert_eq!( (4, ParseSetErrorKind::TrailingInput),   error_at("{1} , {2}") );
+        assert!( matches!(error_at("{1, 256}"), (4, ParseSetErrorKind::Element(_))) );
+        assert_eq!( Ok(Set::new()), " {\n} ".parse::<Set<u8>>() );
+    }
 
     quickcheck! {
 
         fn prop_member(vec: Vec<usize>, elems: Vec<usize>) -> bool {
+            for_each_set_type!(member(&vec, &elems))
+        }
+
+        fn prop_intersection(v1: Vec<usize>, v2: Vec<usize>) -> bool {
+            for_each_set_type!(intersection(&v1, &v2))
+        }
+
+        fn prop_difference(v1: Vec<usize>, v2: Vec<usize>) -> bool {
+            for_each_set_type!(difference(&v1, &v2))
+        }
+
+        fn prop_symmetric_difference(v1: Vec<usize>, v2: Vec<usize>) -> bool {
+            for_each_set_type!(symmetric_difference(&v1, &v2))
+        }
+
+        fn prop_relations(v1: Vec<usize>, v2: Vec<usize>) -> bool {
+            // Overlapping halves make subsets and shared elements likely.
+            let v3: Vec<usize> = v1.iter().chain(&v2).cloned().step_by(2).collect();
+
+            for_each_set_type!(relations(&v1, &v2))
+                && for_each_set_type!(relations(&v3, &v1))
+                && for_each_set_type!(relations(&v1, &v3))
+        }
+
+        fn prop_insert_remove(start: Vec<usize>, ops: Vec<(bool, usize)>) -> bool {
+            for_each_set_type!(insert_remove(&start, &ops))
+        }
+
+        fn prop_cursor_insert(s1: Set<usize>, elems: Vec<usize>) -> bool {
+            let mut set = s1.clone();
+
+            for elem in &elems {
+                let mut cur = set.cursor_front_mut();
+
+                if !cur.seek(elem) && cur.insert(*elem).is_err() {
+                    return false;
+                }
+
+                if cur.insert(*elem).is_ok() {
+                    return false;
+                }
+            }
+
+            let mut expected = s1.clone();
+            expected.extend(elems);
+            set == expected && set.iter().zip(set.iter().skip(1)).all(|(a, b)| a < b)
+        }
+
+        fn prop_serde(s1: Set<usize>) -> bool {
+            let json = serde_json::to_string(&s1).unwrap();
+            serde_json::from_str::<Set<usize>>(&json).unwrap() == s1
+        }
+
+        fn prop_serde_unsorted(vec: Vec<usize>) -> bool {
+            let json = serde_json::to_string(&vec).unwrap();
+            let set: Set<usize> = serde_json::from_str(&json).unwrap();
+
+            set.len() == vec.iter().collect::<BTreeSet<_>>().len()
+                && set.iter().zip(set.iter().skip(1)).all(|(a, b)| a < b)
+                && vec.iter().all(|elem| set.contains(elem))
+        }
+
+        fn prop_lazy_ops(s1: Set<usize>, s2: Set<usize>) -> bool {
+            lazy_ops_agree!(s1, s2)
+        }
+
+        fn prop_intersect_with(s1: Set<usize>, s2: Set<usize>) -> bool {
+            let mut s3 = s1.clone();
+            s3.intersect_with(&s2);
+
+            s3 == s1.intersection(&s2) && s3.len() == s3.iter().count()
+        }
+
+        fn prop_subtract(s1: Set<usize>, s2: Set<usize>) -> bool {
+            let mut s3 = s1.clone();
+            s3.subtract(&s2);
+
+            s3 == s1.difference(&s2) && s3.len() == s3.iter().count()
+        }
+
+        fn prop_union_with(s1: Set<usize>, s2: Set<usize>) -> bool {
+            let mut s3 = s1.clone();
+            s3.union_with(s2.clone());
+
+            s3 == s1.union(&s2) && s3.len() == s3.iter().count()
+        }
+
+        fn prop_append(s1: Set<usize>, s2: Set<usize>) -> bool {
+            let mut s3 = s1.clone();
+            let mut s4 = s2.clone();
+            s3.append(&mut s4);
+
+            s3 == s1.union(&s2) && s3.len() == s3.iter().count() && s4.is_empty()
+                && s3.last() == s3.iter().last() && s4.last().is_none()
+        }
+
+        fn prop_split_off(s1: Set<usize>, elem: usize) -> bool {
+            let mut s2 = s1.clone();
+            let s3 = s2.split_off(&elem);
+
+            s2.iter().all(|a| *a < elem) && s3.iter().all(|b| *b >= elem)
+                && s2.union(&s3) == s1
+                && s2.len() == s2.iter().count()
+                && s3.len() == s3.iter().count()
+                && s2.last() == s2.iter().last()
+                && s3.last() == s3.iter().last()
+        }
+
+        fn prop_retain(s1: Set<usize>, modulus: usize) -> bool {
+            let modulus = modulus + 1;
+            let mut s2 = s1.clone();
+            s2.retain(|elem| elem % modulus == 0);
+
+            s1.iter().all(|elem| s2.contains(elem) == (elem % modulus == 0))
+                && s2.len() == s2.iter().count()
+        }
+
+        fn prop_symmetric_difference_with(s1: Set<usize>, s2: Set<usize>) -> bool {
+            let mut s3 = s1.clone();
+            s3 ^= s2.clone();
+
+            s3 == &s1 ^ &s2 && s3.len() == s3.iter().count()
+        }
+
+        fn prop_assign_by_ref(s1: Set<usize>, s2: Set<usize>) -> bool {
+            let mut union = s1.clone();
+            union |= &s2;
+            let mut symmetric = s1.clone();
+            symmetric ^= &s2;
+
+            union == &s1 | &s2 && union.len() == union.iter().count()
+                && symmetric == &s1 ^ &s2 && symmetric.len() == symmetric.iter().count()
+        }
+
+        fn prop_hash(vec: Vec<usize>) -> bool {
+            let s1: Set<usize> = vec.iter().cloned().collect();
+            let s2: Set<usize> = vec.iter().rev().cloned().collect();
+
+            hash_of(&s1) == hash_of(&s2)
+        }
+
+        fn prop_from_iter(vec: Vec<usize>) -> bool {
             let set: Set<usize> = vec.iter().cloned().collect();
+            let model: BTreeSet<usize> = vec.iter().cloned().collect();
+
+            set.len() == model.len() && set.iter().eq(model.iter())
+        }
+
+        fn prop_extend(s1: Set<usize>, vec: Vec<usize>) -> bool {
+            let mut set = s1.clone();
+            set.extend(vec.iter().cloned());
+
+            let mut model: BTreeSet<usize> = s1.iter().cloned().collect();
+            model.extend(vec);
+
+            set.len() == model.len() && set.iter().eq(model.iter())
+                && set.last() == model.iter().next_back()
+        }
+
+        fn prop_try_from_sorted(vec: Vec<usize>) -> bool {
+            let sorted = vec.windows(2).all(|w| w[0] < w[1]);
+
+            match Set::try_from_sorted(vec.iter().cloned()) {
+                Ok(set) => sorted && set.iter().eq(vec.iter()),
+                Err(_)  => !sorted,
+            }
+        }
+
+        fn prop_positional(s1: Set<usize>, elems: Vec<usize>) -> bool {
+            let vec: Vec<usize> = s1.iter().cloned().collect();
+
+            s1.first() == vec.first() && s1.last() == vec.last()
+                && (0 ..= vec.len()).all(|n| s1.nth(n) == vec.get(n))
+                && elems.iter().all(|elem| {
+                    s1.position(elem) == vec.binary_search(elem)
+                        && s1.get(elem) == vec.binary_search(elem).ok().map(|i| &vec[i])
+                })
+        }
+
+        fn prop_range(s1: Set<usize>, start: usize, end: usize) -> bool {
+            use std::ops::Bound::*;
+
+            let model: BTreeSet<usize> = s1.iter().cloned().collect();
+            let (start, end) = (start.min(end), start.max(end));
+            let bounds = [Included(start), Excluded(start), Unbounded];
+
+            bounds.iter().all(|&lo| {
+                [Included(end), Excluded(end), Unbounded].iter().all(|&hi| {
+                    if lo == Excluded(start) && hi == Excluded(end) && start == end {
+                        // `BTreeSet::range` panics on this empty range.
+                        return s1.range((lo, hi)).next().is_none();
+                    }
+
+                    agrees(s1.range((lo, hi)), model.range((lo, hi)))
+                })
+            })
+        }
+
+        // A pooled set behaves like any other, never holds more nodes than
+        // its capacity, and counts one hit or miss per node it links in.
+        fn prop_pool(capacity: u8, s2: Set<usize>, ops: Vec<(u8, usize)>) -> bool {
+            let capacity = usize::from(capacity % 16);
+            let mut set = Set::with_node_pool(capacity);
+            let mut model = BTreeSet::new();
+            let mut inserted = 0;
+
+            ops.into_iter().all(|(op, elem)| {
+                let agrees = match op % 6 {
+                    0 | 1 => {
+                        let fresh = set.insert(elem);
+                        inserted += usize::from(fresh);
+                        fresh == model.insert(elem)
+                    }
+                    2 => set.remove(&elem) == model.take(&elem),
+                    3 => {
+                        set.intersect_with(&s2);
+                        model.retain(|e| s2.contains(e));
+                        true
+                    }
+                    4 => {
+                        set.subtract(&s2);
+                        model.retain(|e| !s2.contains(e));
+                        true
+                    }
+                    _ => {
+                        let drained: Vec<usize> = set.drain_filter(|e| e % 3 == elem % 3).collect();
+                        model.retain(|e| e % 3 != elem % 3);
+                        drained.iter().all(|e| e % 3 == elem % 3)
+                    }
+                };
+
+                let stats = set.pool_stats();
+                agrees && set.iter().eq(model.iter())
+                    && set.last() == model.iter().next_back()
+                    && stats.pooled <= capacity
+                    && stats.hits + stats.misses == inserted
+            })
+        }
 
-            elems.iter()
-                .all(|elem| vec.contains(elem) == set.contains(elem))
+        fn prop_parse_display(s1: Set<i32>) -> bool {
+            s1.to_string().parse() == Ok(s1.clone())
+                && format!("{:#}", s1).parse() == Ok(s1)
         }
 
-        fn prop_intersection(s1: Set<usize>, s2: Set<usize>) -> bool {
-            let s3 = s1.intersection(&s2);
+        // Arbitrary whitespace, duplicates and a trailing comma all parse.
+        fn prop_parse_loose(vec: Vec<(i32, u8)>, trailing: bool) -> bool {
+            let spaces = |n: u8| [" ", "\t", "\n", ""][usize::from(n % 4)];
+            let mut text = String::from("{");
 
-            s1.iter().all(|elem| s3.contains(elem) == s2.contains(elem))
+            for (i, &(elem, n)) in vec.iter().enumerate() {
+                if i > 0 {
+                    text.push(',');
+                }
+                text.push_str(spaces(n));
+                text.push_str(&elem.to_string());
+                text.push_str(spaces(n / 4));
+            }
 
-                &&
+            if trailing && !vec.is_empty() {
+                text.push(',');
+            }
+            text.push_str(" }\n");
 
-            s2.iter().all(|elem| s3.contains(elem) == s1.contains(elem))
+            text.parse() == Ok(vec.into_iter().map(|(elem, _)| elem).collect::<Set<_>>())
+        }
 
-                &&
+        // A bad element is reported where it starts.
+        fn prop_parse_error(vec: Vec<u8>, index: usize) -> bool {
+            let mut elems: Vec<String> = vec.iter().map(u8::to_string).collect();
+            let index = index % (elems.len() + 1);
+            elems.insert(index, "x".to_owned());
 
-            s3.iter().all(|elem| s1.contains(elem) && s2.contains(elem))
+            let text = format!("{{{}}}", elems.join(", "));
+            let offset = 1 + elems[.. index].iter().map(|elem| elem.len() + 2).sum::<usize>();
 
+            match text.parse::<Set<u8>>() {
+                Err(e) => e.offset() == offset
+                    && matches!(e.kind(), ParseSetErrorKind::Element(_)),
+                Ok(_) => false,
+            }
         }
 
-        fn prop_union(s1: Set<usize>, s2: Set<usize>) -> bool {
-            let s3 = s1.union(&s2);
+        // Sets are kept small, since the results grow exponentially.
+        fn prop_subsets(vec: Vec<u8>) -> bool {
+            let set = Set::from_iter(vec.into_iter().take(10));
+            let elems: Vec<u8> = set.iter().cloned().collect();
+            let subsets: Vec<Set<u8>> = set.subsets().collect();
+            let expected: Vec<Set<u8>> = all_subsets(&elems).into_iter().collect();
 
-            s1.iter().all(|elem| s3.contains(elem))
+            subsets == expected && counts_down(set.subsets(), 1 << elems.len())
+        }
 
-                &&
+        fn prop_subsets_of_size(vec: Vec<u8>, k: u8) -> bool {
+            let set = Set::from_iter(vec.into_iter().take(10));
+            let k = usize::from(k % 12);
+            let expected: Vec<Set<u8>> = set.subsets().filter(|subset| subset.len() == k).collect();
 
-            s2.iter().all(|elem| s3.contains(elem))
+            set.subsets_of_size(k).collect::<Vec<_>>() == expected
+                && counts_down(set.subsets_of_size(k), expected.len())
+        }
 
-                &&
+        fn prop_product(s1: Set<u8>, s2: Set<i8>) -> bool {
+            let mut expected = Vec::new();
+            for a in &s1 {
+                for b in &s2 {
+                    expected.push((a, b));
+                }
+            }
+
+            s1.product(&s2).collect::<Vec<_>>() == expected
+                && counts_down(s1.product(&s2), expected.len())
+        }
 
-            s3.iter().all(|elem| s1.contains(elem) || s2.contains(elem))
+        fn prop_partitions(vec: Vec<u8>) -> bool {
+            const BELL: [usize; 8] = [1, 1, 2, 5, 15, 52, 203, 877];
 
+            let set = Set::from_iter(vec.into_iter().take(7));
+            let partitions: Vec<Vec<Set<u8>>> = set.partitions().collect();
+            let distinct: BTreeSet<&Vec<Set<u8>>> = partitions.iter().collect();
+
+            let valid = |blocks: &Vec<Set<u8>>| {
+                let firsts: Vec<&u8> = blocks.iter().filter_map(Set::first).collect();
+                let union = blocks.iter().fold(Set::new(), |union, block| union.union(block));
+
+                firsts.len() == blocks.len()
+                    && firsts.windows(2).all(|w| w[0] < w[1])
+                    && blocks.iter().map(Set::len).sum::<usize>() == set.len()
+                    && union == set
+            };
+
+            partitions.len() == BELL[set.len()]
+                && distinct.len() == partitions.len()
+                && partitions.iter().all(valid)
+                && counts_down(set.partitions(), BELL[set.len()])
+        }
+
+        fn prop_union(v1: Vec<usize>, v2: Vec<usize>) -> bool {
+            for_each_set_type!(union(&v1, &v2))
         }
 
     }
 
 }
 
+
+// Model-based tests: runs sequences of operations on a `Set` and on a
+// `BTreeSet`, checking after every step that the two agree. On failure,
+// quickcheck shrinks both the sequence and each operation in it.
+#[cfg(test)]
+mod model_tests {
+    use super::Set;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use std::collections::BTreeSet;
+    use std::iter::{self, FromIterator};
+
+    // Elements are drawn from a small range so that operations collide.
+    const RANGE: u8 = 32;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(u8),
+        Replace(u8),
+        Remove(u8),
+        // Removes the elements congruent to `residue` modulo `modulus`,
+        // taking only the first `take` of them before dropping the iterator.
+        DrainFilter { modulus: u8, residue: u8, take: u8 },
+        Clone,
+        Intersection(Vec<u8>),
+        Union(Vec<u8>),
+        Difference(Vec<u8>),
+        SymmetricDifference(Vec<u8>),
+        IntersectWith(Vec<u8>),
+        UnionWith(Vec<u8>),
+        Subtract(Vec<u8>),
+        SymmetricDifferenceWith(Vec<u8>),
+    }
+
+    use self::Op::*;
+
+    fn elems<G: Gen>(g: &mut G) -> Vec<u8> {
+        Vec::<u8>::arbitrary(g).into_iter().map(|e| e % RANGE).collect()
+    }
+
+    impl Arbitrary for Op {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let elem = u8::arbitrary(g) % RANGE;
+
+            match u8::arbitrary(g) % 13 {
+                0  => Insert(elem),
+                1  => Replace(elem),
+                2  => Remove(elem),
+                3  => DrainFilter {
+                    modulus: u8::arbitrary(g),
+                    residue: u8::arbitrary(g),
+                    take:    u8::arbitrary(g) % 8,
+                },
+                4  => Clone,
+                5  => Intersection(elems(g)),
+                6  => Union(elems(g)),
+                7  => Difference(elems(g)),
+                8  => SymmetricDifference(elems(g)),
+                9  => IntersectWith(elems(g)),
+                10 => UnionWith(elems(g)),
+                11 => Subtract(elems(g)),
+                _  => SymmetricDifferenceWith(elems(g)),
+            }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            match *self {
+                Insert(elem) => Box::new(elem.shrink().map(Insert)),
+                Replace(elem) => Box::new(elem.shrink().map(Replace)),
+                Remove(elem) => Box::new(elem.shrink().map(Remove)),
+                DrainFilter { modulus, residue, take } => Box::new(
+                    (modulus, residue, take).shrink()
+                        .map(|(modulus, residue, take)| DrainFilter { modulus, residue, take })),
+                Clone => Box::new(iter::empty()),
+                Intersection(ref v) => Box::new(v.shrink().map(Intersection)),
+                Union(ref v) => Box::new(v.shrink().map(Union)),
+                Difference(ref v) => Box::new(v.shrink().map(Difference)),
+                SymmetricDifference(ref v) => Box::new(v.shrink().map(SymmetricDifference)),
+                IntersectWith(ref v) => Box::new(v.shrink().map(IntersectWith)),
+                UnionWith(ref v) => Box::new(v.shrink().map(UnionWith)),
+                Subtract(ref v) => Box::new(v.shrink().map(Subtract)),
+                SymmetricDifferenceWith(ref v) =>
+                    Box::new(v.shrink().map(SymmetricDifferenceWith)),
+            }
+        }
+    }
+
+    // Applies `op` to both sets, returning whether any results it produced
+    // along the way agree.
+    fn step(set: &mut Set<u8>, model: &mut BTreeSet<u8>, op: Op) -> bool {
+        match op {
+            Insert(elem) => set.insert(elem) == model.insert(elem),
+            Replace(elem) => set.replace(elem) == model.replace(elem),
+            Remove(elem) => set.remove(&elem) == model.take(&elem),
+            DrainFilter { modulus, residue, take } => {
+                let modulus = modulus % 5 + 1;
+                let pred = |elem: &u8| elem % modulus == residue % modulus;
+
+                // Dropping the partly consumed iterator removes the rest.
+                let drained: Vec<u8> = set.drain_filter(pred).take(usize::from(take)).collect();
+                let expected: Vec<u8> = model.iter().cloned().filter(pred)
+                    .take(usize::from(take)).collect();
+                model.retain(|elem| !pred(elem));
+
+                drained == expected
+            }
+            Clone => {
+                let clone = set.clone();
+                let agrees = clone == *set;
+                *set = clone;
+                agrees
+            }
+            Intersection(v) => {
+                *set = set.intersection(&Set::from_iter(v.iter().cloned()));
+                *model = model.intersection(&BTreeSet::from_iter(v)).cloned().collect();
+                true
+            }
+            Union(v) => {
+                *set = set.union(&Set::from_iter(v.iter().cloned()));
+                *model = model.union(&BTreeSet::from_iter(v)).cloned().collect();
+                true
+            }
+            Difference(v) => {
+                *set = set.difference(&Set::from_iter(v.iter().cloned()));
+                *model = model.difference(&BTreeSet::from_iter(v)).cloned().collect();
+                true
+            }
+            SymmetricDifference(v) => {
+                *set = set.symmetric_difference(&Set::from_iter(v.iter().cloned()));
+                *model = model.symmetric_difference(&BTreeSet::from_iter(v)).cloned().collect();
+                true
+            }
+            IntersectWith(v) => {
+                set.intersect_with(&Set::from_iter(v.iter().cloned()));
+                model.retain(|elem| v.contains(elem));
+                true
+            }
+            UnionWith(v) => {
+                set.union_with(Set::from_iter(v.iter().cloned()));
+                model.extend(v);
+                true
+            }
+            Subtract(v) => {
+                set.subtract(&Set::from_iter(v.iter().cloned()));
+                model.retain(|elem| !v.contains(elem));
+                true
+            }
+            SymmetricDifferenceWith(v) => {
+                set.symmetric_difference_with(Set::from_iter(v.iter().cloned()));
+                *model = model.symmetric_difference(&BTreeSet::from_iter(v)).cloned().collect();
+                true
+            }
+        }
+    }
+
+    // Checks that `set` holds the same elements as `model`, in ascending
+    // order.
+    fn same(set: &Set<u8>, model: &BTreeSet<u8>) -> bool {
+        set.len() == model.len()
+            && set.is_empty() == model.is_empty()
+            && set.iter().zip(set.iter().skip(1)).all(|(a, b)| a < b)
+            && set.iter().eq(model.iter())
+            && set.last() == model.iter().next_back()
+    }
+
+    quickcheck! {
+
+        // Each step's results agree with the model, and each state compares
+        // against the one before it as the model's states do.
+        fn prop_ops(start: Vec<u8>, ops: Vec<Op>) -> bool {
+            let mut set = Set::from_iter(start.iter().cloned());
+            let mut model = BTreeSet::from_iter(start);
+
+            same(&set, &model) && ops.into_iter().all(|op| {
+                let (prev_set, prev_model) = (set.clone(), model.clone());
+
+                step(&mut set, &mut model, op)
+                    && same(&set, &model)
+                    && set.cmp(&prev_set) == model.cmp(&prev_model)
+                    && prev_set.cmp(&set) == prev_model.cmp(&model)
+                    && (set == prev_set) == (model == prev_model)
+            })
+        }
+
+    }
+
+}
@@ -0,0 +1,719 @@
+//! Multisets, represented as sorted, singly-linked lists of counted
+//! elements.
+
+use super::{CursorMut, Set};
+use alloc::vec::Vec;
+use core::cmp::Ordering::{self, Less, Equal, Greater};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator};
+
+/// A multiset of elements of type `T`, which remembers how many times
+/// each element was inserted.
+///
+/// # Example
+///
+/// ```
+/// use ownership::list_set::Bag;
+///
+/// let mut bag = Bag::new();
+///
+/// bag.insert("a");
+/// bag.insert("b");
+/// bag.insert("a");
+///
+/// assert_eq!(2, bag.count(&"a"));
+/// assert_eq!(3, bag.len());
+/// assert_eq!(2, bag.distinct_len());
+/// ```
+#[derive(Clone)]
+pub struct Bag<T> {
+    entries: Set<Entry<T>>,
+    len:     usize,
+}
+// Invariants:
+//  - Every entry's count is positive.
+//  - `len` is the sum of the counts.
+
+/// An element and its multiplicity. Entries are ordered by their elements
+/// alone, so a `Set` of them holds each element at most once.
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    elem:  T,
+    count: usize,
+}
+
+impl<T: Ord> Ord for Entry<T> {
+    fn cmp(&self, other: &Entry<T>) -> Ordering {
+        self.elem.cmp(&other.elem)
+    }
+}
+
+impl<T: Ord> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Entry<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for Entry<T> {
+    fn eq(&self, other: &Entry<T>) -> bool {
+        self.elem == other.elem
+    }
+}
+
+impl<T: Ord> Eq for Entry<T> {}
+
+impl<T> Bag<T> {
+    /// Creates a new, empty bag.
+    pub fn new() -> Self {
+        Bag {
+            entries: Set::new(),
+            len:     0,
+        }
+    }
+
+    /// Returns whether a bag is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements in the bag, counting each as many
+    /// times as it occurs.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of distinct elements in the bag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let bag = Bag::from_iter(vec![1, 1, 2, 3, 3, 3]);
+    ///
+    /// assert_eq!(6, bag.len());
+    /// assert_eq!(3, bag.distinct_len());
+    /// ```
+    pub fn distinct_len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns an iterator over the distinct elements of the bag, in
+    /// ascending order, each paired with its count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let bag = Bag::from_iter(vec!['b', 'a', 'b']);
+    /// let result: Vec<_> = bag.iter().collect();
+    ///
+    /// assert_eq!( result, &[(&'a', 1), (&'b', 2)] );
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+}
+
+impl<T> Default for Bag<T> {
+    fn default() -> Self {
+        Bag::new()
+    }
+}
+
+// Moves the cursor to the first entry whose element is not less than
+// `element`, and returns whether that element equals it.
+fn seek<T: Ord>(cur: &mut CursorMut<'_, Entry<T>>, element: &T) -> bool {
+    cur.seek_by(|entry| element.cmp(&entry.elem))
+}
+
+impl<T: Ord> Bag<T> {
+    /// Returns how many times the bag contains the given element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let bag = Bag::from_iter(vec![3, 5, 3]);
+    ///
+    /// assert_eq!(0, bag.count(&2));
+    /// assert_eq!(2, bag.count(&3));
+    /// assert_eq!(1, bag.count(&5));
+    /// ```
+    pub fn count(&self, element: &T) -> usize {
+        for entry in &self.entries {
+            match element.cmp(&entry.elem) {
+                Less    => break,
+                Equal   => return entry.count,
+                Greater => (),
+            }
+        }
+
+        0
+    }
+
+    /// Checks whether the bag contains the given element at least once.
+    pub fn contains(&self, element: &T) -> bool {
+        self.count(element) > 0
+    }
+
+    /// Adds one occurrence of the element to the bag.
+    ///
+    /// Returns how many times the bag contained the element before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the bag would overflow `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// let mut bag = Bag::new();
+    ///
+    /// assert_eq!(0, bag.insert(3));
+    /// assert_eq!(1, bag.insert(3));
+    /// assert_eq!(2, bag.count(&3));
+    /// ```
+    pub fn insert(&mut self, element: T) -> usize {
+        self.insert_n(element, 1)
+    }
+
+    /// Adds `n` occurrences of the element to the bag.
+    ///
+    /// Returns how many times the bag contained the element before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the bag would overflow `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// let mut bag = Bag::new();
+    ///
+    /// assert_eq!(0, bag.insert_n("x", 3));
+    /// assert_eq!(3, bag.insert_n("x", 2));
+    /// assert_eq!(5, bag.len());
+    /// ```
+    pub fn insert_n(&mut self, element: T, n: usize) -> usize {
+        if n == 0 {
+            return self.count(&element);
+        }
+
+        // No count exceeds the length, so checking the length suffices.
+        self.len = self.len.checked_add(n)
+            .expect("Bag::insert_n: length overflow");
+        let mut cur = CursorMut::new(&mut self.entries);
+
+        if seek(&mut cur, &element) {
+            let entry = cur.data_mut().unwrap();
+            entry.count += n;
+            entry.count - n
+        } else {
+            cur.insert_unchecked(Entry { elem: element, count: n });
+            0
+        }
+    }
+
+    /// Removes one occurrence of the element from the bag.
+    ///
+    /// Returns whether the element was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut bag = Bag::from_iter(vec![4, 4]);
+    ///
+    /// assert!( bag.remove_one(&4));
+    /// assert!( bag.remove_one(&4));
+    /// assert!(!bag.remove_one(&4));
+    /// assert!( bag.is_empty());
+    /// ```
+    pub fn remove_one(&mut self, element: &T) -> bool {
+        let mut cur = CursorMut::new(&mut self.entries);
+
+        if !seek(&mut cur, element) {
+            return false;
+        }
+
+        let entry = cur.data_mut().unwrap();
+        if entry.count > 1 {
+            entry.count -= 1;
+        } else {
+            cur.remove_current();
+        }
+
+        self.len -= 1;
+        true
+    }
+
+    /// Removes every occurrence of the element from the bag.
+    ///
+    /// Returns how many were removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut bag = Bag::from_iter(vec![4, 7, 4]);
+    ///
+    /// assert_eq!(2, bag.remove_all(&4));
+    /// assert_eq!(0, bag.remove_all(&4));
+    /// assert_eq!(1, bag.len());
+    /// ```
+    pub fn remove_all(&mut self, element: &T) -> usize {
+        let mut cur = CursorMut::new(&mut self.entries);
+
+        if !seek(&mut cur, element) {
+            return 0;
+        }
+
+        let count = cur.remove_current().unwrap().count;
+        self.len -= count;
+        count
+    }
+}
+
+impl<T: Ord + Clone> Bag<T> {
+    /// Returns the union of two bags, in which each element occurs as many
+    /// times as in whichever bag has more of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the result would overflow `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let bag1 = Bag::from_iter(vec![1, 1, 2]);
+    /// let bag2 = Bag::from_iter(vec![1, 2, 2, 3]);
+    ///
+    /// assert_eq!(Bag::from_iter(vec![1, 1, 2, 2, 3]), bag1.union(&bag2));
+    /// ```
+    pub fn union(&self, other: &Bag<T>) -> Self {
+        self.combine(other, usize::max)
+    }
+
+    /// Returns the sum of two bags, in which each element occurs as many
+    /// times as in both bags together.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the result would overflow `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let bag1 = Bag::from_iter(vec![1, 1, 2]);
+    /// let bag2 = Bag::from_iter(vec![1, 2, 2, 3]);
+    ///
+    /// assert_eq!(Bag::from_iter(vec![1, 1, 1, 2, 2, 2, 3]), bag1.sum(&bag2));
+    /// ```
+    pub fn sum(&self, other: &Bag<T>) -> Self {
+        self.combine(other, |a, b| a.checked_add(b).expect("Bag::sum: length overflow"))
+    }
+
+    /// Returns the intersection of two bags, in which each element occurs
+    /// as many times as in whichever bag has fewer of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let bag1 = Bag::from_iter(vec![1, 1, 2]);
+    /// let bag2 = Bag::from_iter(vec![1, 2, 2, 3]);
+    ///
+    /// assert_eq!(Bag::from_iter(vec![1, 2]), bag1.intersection(&bag2));
+    /// ```
+    pub fn intersection(&self, other: &Bag<T>) -> Self {
+        self.combine(other, usize::min)
+    }
+
+    /// Returns the difference of two bags, in which each occurrence of an
+    /// element in `other` cancels one in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Bag;
+    /// use std::iter::FromIterator;
+    ///
+    /// let bag1 = Bag::from_iter(vec![1, 1, 2]);
+    /// let bag2 = Bag::from_iter(vec![1, 2, 2, 3]);
+    ///
+    /// assert_eq!(Bag::from_iter(vec![1]), bag1.difference(&bag2));
+    /// ```
+    pub fn difference(&self, other: &Bag<T>) -> Self {
+        self.combine(other, usize::saturating_sub)
+    }
+
+    // Walks two bags in step, giving each element the count that `f`
+    // computes from its counts in `self` and `other`.
+    fn combine<F: Fn(usize, usize) -> usize>(&self, other: &Bag<T>, f: F) -> Self {
+        let mut a = self.entries.iter().peekable();
+        let mut b = other.entries.iter().peekable();
+        let mut entries = Vec::new();
+
+        loop {
+            let (elem, count) = match (a.peek(), b.peek()) {
+                (Some(&x), Some(&y)) => match x.cmp(y) {
+                    Less    => { a.next(); (&x.elem, f(x.count, 0)) }
+                    Greater => { b.next(); (&y.elem, f(0, y.count)) }
+                    Equal   => {
+                        a.next();
+                        b.next();
+                        (&x.elem, f(x.count, y.count))
+                    }
+                }
+                (Some(&x), None) => { a.next(); (&x.elem, f(x.count, 0)) }
+                (None, Some(&y)) => { b.next(); (&y.elem, f(0, y.count)) }
+                (None, None)     => break,
+            };
+
+            if count > 0 {
+                entries.push(Entry { elem: elem.clone(), count });
+            }
+        }
+
+        let len = entries.iter()
+            .try_fold(0usize, |len, entry| len.checked_add(entry.count))
+            .expect("Bag::combine: length overflow");
+
+        Bag {
+            len,
+            entries: Set::from_sorted_unchecked(entries),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Bag<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> Ord for Bag<T> {
+    fn cmp(&self, other: &Bag<T>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Ord> PartialOrd for Bag<T> {
+    fn partial_cmp(&self, other: &Bag<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for Bag<T> {
+    fn eq(&self, other: &Bag<T>) -> bool {
+        self.len == other.len && self.cmp(other) == Equal
+    }
+}
+
+impl<T: Ord> Eq for Bag<T> {}
+
+impl<T: Hash> Hash for Bag<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for (elem, count) in self {
+            elem.hash(state);
+            count.hash(state);
+        }
+    }
+}
+
+/// An immutable iterator over the distinct elements of a `Bag` and their
+/// counts.
+#[derive(Debug)]
+pub struct Iter<'a, T: 'a>(super::Iter<'a, Entry<T>>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<(&'a T, usize)> {
+        self.0.next().map(|entry| (&entry.elem, entry.count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a Bag<T> {
+    type Item = (&'a T, usize);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        Iter(self.entries.iter())
+    }
+}
+
+/// An iterator that consumes a `Bag`, yielding its distinct elements and
+/// their counts.
+#[derive(Debug)]
+pub struct IntoIter<T>(super::IntoIter<Entry<T>>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (T, usize);
+
+    fn next(&mut self) -> Option<(T, usize)> {
+        self.0.next().map(|entry| (entry.elem, entry.count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<T> IntoIterator for Bag<T> {
+    type Item = (T, usize);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self.entries.into_iter())
+    }
+}
+
+impl<T: Ord> Extend<T> for Bag<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+
+/// Equal elements are counted together; the first of them is kept.
+impl<T: Ord> FromIterator<T> for Bag<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut elems: Vec<T> = iter.into_iter().collect();
+        elems.sort();
+
+        let len = elems.len();
+        let mut entries: Vec<Entry<T>> = Vec::new();
+
+        for elem in elems {
+            match entries.last_mut() {
+                Some(last) if last.elem == elem => last.count += 1,
+                _ => entries.push(Entry { elem, count: 1 }),
+            }
+        }
+
+        Bag {
+            entries: Set::from_sorted_unchecked(entries),
+            len,
+        }
+    }
+}
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_bag {
+    use super::Bag;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl<T: Arbitrary + Ord> Arbitrary for Bag<T> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            FromIterator::from_iter(Vec::<T>::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            let elems: Vec<T> = self.iter()
+                .flat_map(|(elem, count)| core::iter::repeat_n(elem.clone(), count))
+                .collect();
+
+            Box::new(elems.shrink().map(FromIterator::from_iter))
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::Bag;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use std::collections::BTreeMap;
+
+    type Model = BTreeMap<u8, usize>;
+
+    #[test]
+    #[should_panic(expected = "length overflow")]
+    fn insert_n_overflow() {
+        let mut bag = Bag::new();
+        bag.insert_n(1, usize::MAX);
+        bag.insert_n(2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "length overflow")]
+    fn union_overflow() {
+        let mut bag1 = Bag::new();
+        bag1.insert_n(1, usize::MAX);
+        let mut bag2 = Bag::new();
+        bag2.insert_n(2, 1);
+        bag1.union(&bag2);
+    }
+
+    fn model_of(bag: &Bag<u8>) -> Model {
+        bag.iter().map(|(elem, count)| (*elem, count)).collect()
+    }
+
+    fn agrees(bag: &Bag<u8>, model: &Model) -> bool {
+        bag.iter().eq(model.iter().map(|(elem, count)| (elem, *count)))
+            && bag.len() == model.values().sum::<usize>()
+            && bag.distinct_len() == model.len()
+    }
+
+    // Combines two models elementwise, dropping zero counts.
+    fn combine<F: Fn(usize, usize) -> usize>(m1: &Model, m2: &Model, f: F) -> Model {
+        m1.keys().chain(m2.keys())
+            .map(|elem| {
+                let a = m1.get(elem).cloned().unwrap_or(0);
+                let b = m2.get(elem).cloned().unwrap_or(0);
+                (*elem, f(a, b))
+            })
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(u8),
+        InsertN(u8, u8),
+        RemoveOne(u8),
+        RemoveAll(u8),
+        Count(u8),
+    }
+
+    use self::Op::*;
+
+    impl Arbitrary for Op {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let elem = u8::arbitrary(g) % 16;
+
+            match u8::arbitrary(g) % 5 {
+                0 => Insert(elem),
+                1 => InsertN(elem, u8::arbitrary(g) % 4),
+                2 => RemoveOne(elem),
+                3 => RemoveAll(elem),
+                _ => Count(elem),
+            }
+        }
+    }
+
+    quickcheck! {
+
+        fn prop_model(ops: Vec<Op>) -> bool {
+            let mut bag = Bag::new();
+            let mut model = Model::new();
+
+            ops.iter().all(|op| {
+                let ok = match *op {
+                    Insert(e) => {
+                        let before = model.get(&e).cloned().unwrap_or(0);
+                        *model.entry(e).or_insert(0) += 1;
+                        bag.insert(e) == before
+                    }
+                    InsertN(e, n) => {
+                        let before = model.get(&e).cloned().unwrap_or(0);
+                        if n > 0 {
+                            *model.entry(e).or_insert(0) += n as usize;
+                        }
+                        bag.insert_n(e, n as usize) == before
+                    }
+                    RemoveOne(e) => {
+                        let present = match model.get_mut(&e) {
+                            Some(count) if *count > 1 => { *count -= 1; true }
+                            Some(_) => { model.remove(&e); true }
+                            None => false,
+                        };
+                        bag.remove_one(&e) == present
+                    }
+                    RemoveAll(e) => {
+                        bag.remove_all(&e) == model.remove(&e).unwrap_or(0)
+                    }
+                    Count(e) => {
+                        bag.count(&e) == model.get(&e).cloned().unwrap_or(0)
+                    }
+                };
+
+                ok && agrees(&bag, &model)
+            })
+        }
+
+        fn prop_from_iter(vec: Vec<u8>) -> bool {
+            let bag: Bag<u8> = vec.iter().cloned().collect();
+            let mut model = Model::new();
+
+            for elem in vec {
+                *model.entry(elem).or_insert(0) += 1;
+            }
+
+            agrees(&bag, &model)
+        }
+
+        fn prop_union(b1: Bag<u8>, b2: Bag<u8>) -> bool {
+            let expected = combine(&model_of(&b1), &model_of(&b2), usize::max);
+            agrees(&b1.union(&b2), &expected)
+        }
+
+        fn prop_sum(b1: Bag<u8>, b2: Bag<u8>) -> bool {
+            let expected = combine(&model_of(&b1), &model_of(&b2), |a, b| a + b);
+            agrees(&b1.sum(&b2), &expected)
+        }
+
+        fn prop_intersection(b1: Bag<u8>, b2: Bag<u8>) -> bool {
+            let expected = combine(&model_of(&b1), &model_of(&b2), usize::min);
+            agrees(&b1.intersection(&b2), &expected)
+        }
+
+        fn prop_difference(b1: Bag<u8>, b2: Bag<u8>) -> bool {
+            let expected = combine(&model_of(&b1), &model_of(&b2), usize::saturating_sub);
+            agrees(&b1.difference(&b2), &expected)
+        }
+
+        fn prop_laws(b1: Bag<u8>, b2: Bag<u8>) -> bool {
+            b1.union(&b2) == b2.union(&b1)
+                && b1.intersection(&b2) == b2.intersection(&b1)
+                && b1.sum(&b2).len() == b1.len() + b2.len()
+                && b1.difference(&b2).sum(&b1.intersection(&b2)) == b1
+        }
+
+    }
+}
@@ -0,0 +1,393 @@
+//! Lazy combinatorial iterators over the elements of a `Set`: subsets,
+//! products and partitions.
+//!
+//! Each iterator holds the set's elements by reference and builds one result
+//! at a time. Their size hints are exact when the number of results fits in
+//! a `usize`, and `(usize::MAX, None)` when it does not.
+
+use super::{Iter, Set};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+impl<T: Clone> Set<T> {
+    /// Returns a lazy iterator over all subsets of the set, in ascending
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(vec![1, 2, 3]);
+    /// let subsets: Vec<Vec<i32>> = set.subsets()
+    ///     .map(|subset| subset.into_iter().collect())
+    ///     .collect();
+    ///
+    /// assert_eq!( subsets, vec![vec![], vec![1], vec![1, 2], vec![1, 2, 3],
+    ///                           vec![1, 3], vec![2], vec![2, 3], vec![3]] );
+    /// ```
+    pub fn subsets(&self) -> Subsets<'_, T> {
+        let elems: Vec<&T> = self.iter().collect();
+        let remaining = if elems.len() < usize::BITS as usize {
+            Some(1 << elems.len())
+        } else {
+            None
+        };
+
+        Subsets {
+            elems,
+            next: Some(Vec::new()),
+            remaining,
+        }
+    }
+
+    /// Returns a lazy iterator over the subsets of the set with `k`
+    /// elements, in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(vec![1, 2, 3, 4]);
+    /// let pairs: Vec<Vec<i32>> = set.subsets_of_size(2)
+    ///     .map(|subset| subset.into_iter().collect())
+    ///     .collect();
+    ///
+    /// assert_eq!( pairs, vec![vec![1, 2], vec![1, 3], vec![1, 4],
+    ///                         vec![2, 3], vec![2, 4], vec![3, 4]] );
+    /// assert_eq!( 0, set.subsets_of_size(5).count() );
+    /// ```
+    pub fn subsets_of_size(&self, k: usize) -> SubsetsOfSize<'_, T> {
+        let elems: Vec<&T> = self.iter().collect();
+        let remaining = binomial(elems.len(), k);
+        let next = if k <= elems.len() { Some((0 .. k).collect()) } else { None };
+
+        SubsetsOfSize { elems, next, remaining }
+    }
+
+    /// Returns a lazy iterator over the partitions of the set: the ways of
+    /// dividing its elements into non-empty, disjoint blocks.
+    ///
+    /// Within each partition the blocks are ordered by their least element.
+    /// The first partition has every element in one block, and the last
+    /// has each element in a block of its own.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = Set::from_iter(vec![1, 2, 3]);
+    /// let partitions: Vec<Vec<Vec<i32>>> = set.partitions()
+    ///     .map(|blocks| blocks.into_iter().map(|block| block.into_iter().collect()).collect())
+    ///     .collect();
+    ///
+    /// assert_eq!( partitions, vec![
+    ///     vec![vec![1, 2, 3]],
+    ///     vec![vec![1, 2], vec![3]],
+    ///     vec![vec![1, 3], vec![2]],
+    ///     vec![vec![1], vec![2, 3]],
+    ///     vec![vec![1], vec![2], vec![3]],
+    /// ] );
+    /// ```
+    pub fn partitions(&self) -> Partitions<'_, T> {
+        let elems: Vec<&T> = self.iter().collect();
+        let remaining = bell(elems.len());
+
+        Partitions {
+            next: Some(vec![0; elems.len()]),
+            elems,
+            remaining,
+        }
+    }
+}
+
+impl<T> Set<T> {
+    /// Returns a lazy iterator over the pairs of an element of `self` and
+    /// an element of `other`, in lexicographic order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::Set;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = Set::from_iter(vec![1, 2]);
+    /// let set2 = Set::from_iter(vec!['a', 'b', 'c']);
+    ///
+    /// let pairs: Vec<_> = set1.product(&set2).collect();
+    /// assert_eq!( pairs, &[(&1, &'a'), (&1, &'b'), (&1, &'c'),
+    ///                      (&2, &'a'), (&2, &'b'), (&2, &'c')] );
+    /// ```
+    pub fn product<'a, U>(&'a self, other: &'a Set<U>) -> Product<'a, T, U> {
+        let mut outer = self.iter();
+
+        Product {
+            current: outer.next(),
+            outer,
+            inner: other.iter(),
+            other,
+        }
+    }
+}
+
+// The size hint for `remaining` results, where `None` means too many to
+// count.
+fn size_hint(remaining: Option<usize>) -> (usize, Option<usize>) {
+    match remaining {
+        Some(n) => (n, Some(n)),
+        None => (usize::MAX, None),
+    }
+}
+
+// The number of ways to choose `k` of `n` elements, if it fits.
+fn binomial(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Some(0);
+    }
+
+    // The products below only grow up to the middle of the row.
+    let k = k.min(n - k);
+    let mut result = 1;
+
+    for i in 0 .. k {
+        // Exact, since `result * (n - i)` counts ordered choices.
+        let next = result as u128 * (n - i) as u128 / (i + 1) as u128;
+
+        if next > usize::MAX as u128 {
+            return None;
+        }
+
+        result = next as usize;
+    }
+
+    Some(result)
+}
+
+// The number of partitions of `n` elements, if it fits.
+fn bell(n: usize) -> Option<usize> {
+    if n == 0 {
+        return Some(1);
+    }
+
+    // Rows of the Bell triangle. Row `i` starts with the `i`th Bell number
+    // and ends with the next, and no entry of the rows before row `n`
+    // exceeds the `n`th.
+    let mut row = vec![1usize];
+
+    for _ in 1 .. n {
+        let mut next = Vec::with_capacity(row.len() + 1);
+        let mut entry = *row.last().unwrap();
+        next.push(entry);
+
+        for &above in &row {
+            entry = entry.checked_add(above)?;
+            next.push(entry);
+        }
+
+        row = next;
+    }
+
+    row.last().cloned()
+}
+
+/// A lazy iterator over the subsets of a `Set`.
+///
+/// Returned by `Set::subsets`.
+#[derive(Debug)]
+pub struct Subsets<'a, T: 'a> {
+    elems:     Vec<&'a T>,
+    // Indices into `elems` of the next subset, or `None` when done.
+    next:      Option<Vec<usize>>,
+    remaining: Option<usize>,
+}
+
+impl<'a, T: Clone> Iterator for Subsets<'a, T> {
+    type Item = Set<T>;
+
+    fn next(&mut self) -> Option<Set<T>> {
+        let elems = &self.elems;
+        let indices = self.next.as_mut()?;
+        let subset = Set::from_sorted_unchecked(indices.iter().map(|&i| elems[i].clone()));
+        let n = self.elems.len();
+
+        // The next subset in order extends this one by the next element if
+        // there is one, and otherwise moves its second-to-last element on.
+        match indices.last() {
+            Some(&last) if last + 1 < n => indices.push(last + 1),
+            Some(_) => {
+                indices.pop();
+
+                match indices.last_mut() {
+                    Some(last) => *last += 1,
+                    None => self.next = None,
+                }
+            }
+            None if n > 0 => indices.push(0),
+            None => self.next = None,
+        }
+
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+
+        Some(subset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.remaining)
+    }
+}
+
+impl<'a, T: Clone> FusedIterator for Subsets<'a, T> {}
+
+/// A lazy iterator over the subsets of a `Set` with a given number of
+/// elements.
+///
+/// Returned by `Set::subsets_of_size`.
+#[derive(Debug)]
+pub struct SubsetsOfSize<'a, T: 'a> {
+    elems:     Vec<&'a T>,
+    // Indices into `elems` of the next subset, or `None` when done.
+    next:      Option<Vec<usize>>,
+    remaining: Option<usize>,
+}
+
+impl<'a, T: Clone> Iterator for SubsetsOfSize<'a, T> {
+    type Item = Set<T>;
+
+    fn next(&mut self) -> Option<Set<T>> {
+        let elems = &self.elems;
+        let indices = self.next.as_mut()?;
+        let subset = Set::from_sorted_unchecked(indices.iter().map(|&i| elems[i].clone()));
+        let (n, k) = (self.elems.len(), indices.len());
+
+        // Moves on the last index that has room to, and packs the ones after
+        // it right behind it.
+        match (0 .. k).rev().find(|&i| indices[i] < n - k + i) {
+            Some(i) => {
+                indices[i] += 1;
+
+                for j in i + 1 .. k {
+                    indices[j] = indices[j - 1] + 1;
+                }
+            }
+            None => self.next = None,
+        }
+
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+
+        Some(subset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.remaining)
+    }
+}
+
+impl<'a, T: Clone> FusedIterator for SubsetsOfSize<'a, T> {}
+
+/// A lazy iterator over the pairs drawn from two `Set`s.
+///
+/// Returned by `Set::product`.
+#[derive(Debug)]
+pub struct Product<'a, T: 'a, U: 'a> {
+    outer:   Iter<'a, T>,
+    current: Option<&'a T>,
+    inner:   Iter<'a, U>,
+    other:   &'a Set<U>,
+}
+
+impl<'a, T, U> Iterator for Product<'a, T, U> {
+    type Item = (&'a T, &'a U);
+
+    fn next(&mut self) -> Option<(&'a T, &'a U)> {
+        loop {
+            let a = self.current?;
+
+            if let Some(b) = self.inner.next() {
+                return Some((a, b));
+            }
+
+            self.current = self.outer.next();
+            self.inner = self.other.iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.current {
+            Some(_) => self.outer.len().checked_mul(self.other.len())
+                .and_then(|rest| rest.checked_add(self.inner.len())),
+            None => Some(0),
+        };
+
+        size_hint(remaining)
+    }
+}
+
+impl<'a, T, U> FusedIterator for Product<'a, T, U> {}
+
+/// A lazy iterator over the partitions of a `Set`.
+///
+/// Returned by `Set::partitions`.
+#[derive(Debug)]
+pub struct Partitions<'a, T: 'a> {
+    elems:     Vec<&'a T>,
+    // The block of each element in the next partition, or `None` when done.
+    // Each element's block is at most one more than the highest block
+    // before it, so the blocks are numbered by their least elements.
+    next:      Option<Vec<usize>>,
+    remaining: Option<usize>,
+}
+
+impl<'a, T: Clone> Iterator for Partitions<'a, T> {
+    type Item = Vec<Set<T>>;
+
+    fn next(&mut self) -> Option<Vec<Set<T>>> {
+        let blocks = self.next.as_mut()?;
+        let count = blocks.iter().max().map_or(0, |&max| max + 1);
+        let mut partition = vec![Vec::new(); count];
+
+        for (elem, &block) in self.elems.iter().zip(blocks.iter()) {
+            partition[block].push((*elem).clone());
+        }
+
+        // Moves the last element that can go to a later block there, and
+        // puts every element after it back in the first block.
+        let mut highest = Vec::with_capacity(blocks.len());
+        blocks.iter().fold(0, |max, &block| {
+            highest.push(max);
+            max.max(block)
+        });
+
+        match (1 .. blocks.len()).rev().find(|&i| blocks[i] <= highest[i]) {
+            Some(i) => {
+                blocks[i] += 1;
+
+                for block in &mut blocks[i + 1 ..] {
+                    *block = 0;
+                }
+            }
+            None => self.next = None,
+        }
+
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+
+        Some(partition.into_iter().map(Set::from_sorted_unchecked).collect())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.remaining)
+    }
+}
+
+impl<'a, T: Clone> FusedIterator for Partitions<'a, T> {}
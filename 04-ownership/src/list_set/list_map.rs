@@ -0,0 +1,1035 @@
+//! Maps, represented as sorted, singly-linked lists of key/value pairs.
+
+use super::{CursorMut, DataMut, Set};
+use alloc::vec::Vec;
+use core::cmp::Ordering::{self, Less, Equal, Greater};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator, Peekable};
+
+/// A map from keys of type `K` to values of type `V`, ordered by key.
+///
+/// # Example
+///
+/// ```
+/// use ownership::list_set::ListMap;
+///
+/// let mut map = ListMap::new();
+///
+/// map.insert("b", 2);
+/// map.insert("a", 1);
+///
+/// if let Some(value) = map.get_mut(&"b") {
+///     *value *= 10;
+/// }
+///
+/// let pairs: Vec<_> = map.iter().collect();
+/// assert_eq!( pairs, &[(&"a", &1), (&"b", &20)] );
+/// ```
+#[derive(Clone)]
+pub struct ListMap<K, V> {
+    entries: Set<Pair<K, V>>,
+}
+// Invariant: `entries` holds each key at most once, which follows from the
+// order on `Pair`.
+
+/// A key and its value. Pairs are ordered by their keys alone, so a `Set`
+/// of them holds each key at most once.
+#[derive(Debug, Clone)]
+struct Pair<K, V> {
+    key:   K,
+    value: V,
+}
+
+impl<K: Ord, V> Ord for Pair<K, V> {
+    fn cmp(&self, other: &Pair<K, V>) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl<K: Ord, V> PartialOrd for Pair<K, V> {
+    fn partial_cmp(&self, other: &Pair<K, V>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, V> PartialEq for Pair<K, V> {
+    fn eq(&self, other: &Pair<K, V>) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, V> Eq for Pair<K, V> {}
+
+impl<K, V> ListMap<K, V> {
+    /// Creates a new, empty map.
+    pub fn new() -> Self {
+        ListMap {
+            entries: Set::new(),
+        }
+    }
+
+    /// Returns whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns an iterator over the keys and values, in ascending order of
+    /// key.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.into_iter()
+    }
+
+    /// Returns an iterator over the keys and mutable values, in ascending
+    /// order of key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut map = ListMap::from_iter(vec![(1, 10), (2, 20)]);
+    ///
+    /// for (key, value) in map.iter_mut() {
+    ///     *value += key;
+    /// }
+    ///
+    /// assert_eq!( map, ListMap::from_iter(vec![(1, 11), (2, 22)]) );
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.into_iter()
+    }
+
+    /// Returns an iterator over the keys, in ascending order.
+    ///
+    /// Since the keys come in order, they convert to a `Set<&K>` in O(n)
+    /// time with `Set::from`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::{ListMap, Set};
+    /// use std::iter::FromIterator;
+    ///
+    /// let map1 = ListMap::from_iter(vec![(3, 'c'), (1, 'a')]);
+    /// let map2 = ListMap::from_iter(vec![(1, 'x'), (2, 'y'), (3, 'z')]);
+    ///
+    /// let keys1 = Set::from(map1.keys());
+    /// let keys2 = Set::from(map2.keys());
+    ///
+    /// assert!( keys1.is_subset(&keys2) );
+    /// assert_eq!( keys2.difference(&keys1), Set::from_iter(vec![&2]) );
+    /// ```
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.entries.iter())
+    }
+
+    /// Returns an iterator over the values, in ascending order of key.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values(self.entries.iter())
+    }
+
+    /// Removes the key/value pairs for which `pred` returns `true`, leaving
+    /// the rest in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut map = ListMap::from_iter((1 .. 7).map(|key| (key, key * key)));
+    ///
+    /// map.retain(|key, _| key % 3 == 0);
+    /// assert_eq!( map, ListMap::from_iter(vec![(3, 9), (6, 36)]) );
+    /// ```
+    pub fn retain<P: FnMut(&K, &mut V) -> bool>(&mut self, mut pred: P) {
+        self.drain_filter(|key, value| !pred(key, value)).for_each(drop);
+    }
+
+    /// Returns an iterator that removes and returns the key/value pairs
+    /// for which `pred` returns `true`, leaving the rest in the map.
+    ///
+    /// The predicate may change the values it is passed. Dropping the
+    /// iterator removes whatever pairs it has not yet reached.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut map = ListMap::from_iter(vec![(1, 5), (2, 0), (3, 7)]);
+    ///
+    /// let drained: Vec<_> = map.drain_filter(|_, value| {
+    ///     *value -= 1;
+    ///     *value < 0
+    /// }).collect();
+    ///
+    /// assert_eq!( drained, &[(2, -1)] );
+    /// assert_eq!( map, ListMap::from_iter(vec![(1, 4), (3, 6)]) );
+    /// ```
+    pub fn drain_filter<P: FnMut(&K, &mut V) -> bool>(&mut self, pred: P) -> DrainFilter<'_, K, V, P> {
+        DrainFilter {
+            cursor: CursorMut::new(&mut self.entries),
+            pred,
+        }
+    }
+}
+
+impl<K, V> Default for ListMap<K, V> {
+    fn default() -> Self {
+        ListMap::new()
+    }
+}
+
+// Moves the cursor to the first pair whose key is not less than `key`, and
+// returns whether that key equals it.
+fn seek<K: Ord, V>(cur: &mut CursorMut<'_, Pair<K, V>>, key: &K) -> bool {
+    cur.seek_by(|pair| key.cmp(&pair.key))
+}
+
+impl<K: Ord, V> ListMap<K, V> {
+    /// Returns the value for the given key, if present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// use std::iter::FromIterator;
+    ///
+    /// let map = ListMap::from_iter(vec![(2, "two"), (4, "four")]);
+    ///
+    /// assert_eq!( Some(&"two"), map.get(&2) );
+    /// assert_eq!( None, map.get(&3) );
+    /// ```
+    pub fn get(&self, key: &K) -> Option<&V> {
+        for pair in &self.entries {
+            match key.cmp(&pair.key) {
+                Less    => break,
+                Equal   => return Some(&pair.value),
+                Greater => (),
+            }
+        }
+
+        None
+    }
+
+    /// Returns a mutable reference to the value for the given key, if
+    /// present.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut cur = CursorMut::new(&mut self.entries);
+
+        if seek(&mut cur, key) {
+            cur.into_data_mut().map(|pair| &mut pair.value)
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether the map has a value for the given key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Sets the value for the given key, returning the old value if there
+    /// was one. The key itself is not replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// let mut map = ListMap::new();
+    ///
+    /// assert_eq!( None, map.insert('x', 1) );
+    /// assert_eq!( Some(1), map.insert('x', 2) );
+    /// assert_eq!( Some(&2), map.get(&'x') );
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes the given key from the map, returning its value if it was
+    /// present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut map = ListMap::from_iter(vec![(1, 'a'), (2, 'b')]);
+    ///
+    /// assert_eq!( Some('a'), map.remove(&1) );
+    /// assert_eq!( None, map.remove(&1) );
+    /// assert_eq!( 1, map.len() );
+    /// ```
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let mut cur = CursorMut::new(&mut self.entries);
+
+        if seek(&mut cur, key) {
+            cur.remove_current().map(|pair| pair.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the entry for the given key, for inspecting or changing it
+    /// in place with a single search.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// let mut counts = ListMap::new();
+    ///
+    /// for word in "the cat and the hat".split(' ') {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!( Some(&2), counts.get(&"the") );
+    /// assert_eq!( Some(&1), counts.get(&"hat") );
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let mut cursor = CursorMut::new(&mut self.entries);
+
+        if seek(&mut cursor, &key) {
+            Entry::Occupied(OccupiedEntry { cursor })
+        } else {
+            Entry::Vacant(VacantEntry { key, cursor })
+        }
+    }
+
+    /// Returns a lazy iterator over the keys in both maps, with their
+    /// values from each.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// use std::iter::FromIterator;
+    ///
+    /// let names = ListMap::from_iter(vec![(1, "one"), (2, "two"), (3, "three")]);
+    /// let squares = ListMap::from_iter(vec![(2, 4), (3, 9), (4, 16)]);
+    ///
+    /// let result: Vec<_> = names.iter_join(&squares).collect();
+    /// assert_eq!( result, &[(&2, &"two", &4), (&3, &"three", &9)] );
+    /// ```
+    pub fn iter_join<'a, W>(&'a self, other: &'a ListMap<K, W>) -> Join<'a, K, V, W> {
+        Join(self.iter_outer_join(other))
+    }
+
+    /// Returns a lazy iterator over the keys in either map, with their
+    /// values from whichever maps have them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// use ownership::list_set::list_map::EitherOrBoth::*;
+    /// use std::iter::FromIterator;
+    ///
+    /// let names = ListMap::from_iter(vec![(1, "one"), (2, "two")]);
+    /// let squares = ListMap::from_iter(vec![(2, 4), (3, 9)]);
+    ///
+    /// let result: Vec<_> = names.iter_outer_join(&squares).collect();
+    /// assert_eq!( result, &[(&1, Left(&"one")), (&2, Both(&"two", &4)), (&3, Right(&9))] );
+    /// ```
+    pub fn iter_outer_join<'a, W>(&'a self, other: &'a ListMap<K, W>) -> OuterJoin<'a, K, V, W> {
+        OuterJoin {
+            a: self.entries.iter().peekable(),
+            b: other.entries.iter().peekable(),
+        }
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ListMap<K, V> {
+    fn eq(&self, other: &ListMap<K, V>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ListMap<K, V> {}
+
+impl<K: Ord, V: Ord> Ord for ListMap<K, V> {
+    fn cmp(&self, other: &ListMap<K, V>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for ListMap<K, V> {
+    fn partial_cmp(&self, other: &ListMap<K, V>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for ListMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+
+        for pair in self {
+            pair.hash(state);
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ListMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A view into a single key of a `ListMap`, which may or may not have a
+/// value.
+///
+/// Returned by `ListMap::entry`.
+#[derive(Debug)]
+pub enum Entry<'a, K: 'a, V: 'a> {
+    /// The key has no value.
+    Vacant(VacantEntry<'a, K, V>),
+    /// The key has a value.
+    Occupied(OccupiedEntry<'a, K, V>),
+}
+
+/// A key of a `ListMap` that has no value.
+#[derive(Debug)]
+pub struct VacantEntry<'a, K: 'a, V: 'a> {
+    key:    K,
+    cursor: CursorMut<'a, Pair<K, V>>,
+}
+// Invariant: `cursor` is where `key` belongs, between smaller and larger
+// keys.
+
+/// A key of a `ListMap` that has a value.
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, K: 'a, V: 'a> {
+    cursor: CursorMut<'a, Pair<K, V>>,
+}
+// Invariant: `cursor` is at a pair.
+
+impl<'a, K, V> Entry<'a, K, V> {
+    /// Returns the entry's key.
+    pub fn key(&self) -> &K {
+        match *self {
+            Entry::Vacant(ref entry) => entry.key(),
+            Entry::Occupied(ref entry) => entry.key(),
+        }
+    }
+
+    /// Returns the value, inserting `default` first if there was none.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns the value, inserting the result of `default` first if there
+    /// was none.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Changes the value with `f`, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::ListMap;
+    /// let mut map = ListMap::new();
+    ///
+    /// map.entry("a").and_modify(|n| *n += 1).or_insert(1);
+    /// map.entry("a").and_modify(|n| *n += 1).or_insert(1);
+    /// assert_eq!( Some(&2), map.get(&"a") );
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(ref mut entry) = self {
+            f(entry.get_mut());
+        }
+
+        self
+    }
+}
+
+impl<'a, K, V: Default> Entry<'a, K, V> {
+    /// Returns the value, inserting `V::default()` first if there was none.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    /// Returns the key that would be inserted.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the key, leaving the map unchanged.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the key with `value`, and returns the value.
+    pub fn insert(mut self, value: V) -> &'a mut V {
+        self.cursor.insert_unchecked(Pair { key: self.key, value });
+        &mut self.cursor.into_data_mut().unwrap().value
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    fn pair(&self) -> &Pair<K, V> {
+        self.cursor.peek().unwrap()
+    }
+
+    /// Returns the entry's key.
+    pub fn key(&self) -> &K {
+        &self.pair().key
+    }
+
+    /// Returns the value.
+    pub fn get(&self) -> &V {
+        &self.pair().value
+    }
+
+    /// Returns the value mutably.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.cursor.data_mut().unwrap().value
+    }
+
+    /// Returns the value mutably, for as long as the map is borrowed.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.cursor.into_data_mut().unwrap().value
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the key from the map, returning its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes the key from the map, returning it and its value.
+    pub fn remove_entry(mut self) -> (K, V) {
+        let Pair { key, value } = self.cursor.remove_current().unwrap();
+        (key, value)
+    }
+}
+
+/// A key in a full outer join of two `ListMap`s, with the values from
+/// whichever maps have it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EitherOrBoth<A, B> {
+    /// Only the left map has the key.
+    Left(A),
+    /// Only the right map has the key.
+    Right(B),
+    /// Both maps have the key.
+    Both(A, B),
+}
+
+/// A lazy iterator over the keys in either of two `ListMap`s.
+///
+/// Returned by `ListMap::iter_outer_join`.
+#[derive(Debug)]
+pub struct OuterJoin<'a, K: 'a, V: 'a, W: 'a> {
+    a: Peekable<super::Iter<'a, Pair<K, V>>>,
+    b: Peekable<super::Iter<'a, Pair<K, W>>>,
+}
+
+impl<'a, K: Ord, V, W> Iterator for OuterJoin<'a, K, V, W> {
+    type Item = (&'a K, EitherOrBoth<&'a V, &'a W>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let order = match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => a.key.cmp(&b.key),
+            (Some(_), None) => Less,
+            (None, Some(_)) => Greater,
+            (None, None) => return None,
+        };
+
+        Some(match order {
+            Less => {
+                let a = self.a.next().unwrap();
+                (&a.key, EitherOrBoth::Left(&a.value))
+            }
+            Greater => {
+                let b = self.b.next().unwrap();
+                (&b.key, EitherOrBoth::Right(&b.value))
+            }
+            Equal => {
+                let (a, b) = (self.a.next().unwrap(), self.b.next().unwrap());
+                (&a.key, EitherOrBoth::Both(&a.value, &b.value))
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (a.max(b), a.checked_add(b))
+    }
+}
+
+impl<'a, K: Ord, V, W> FusedIterator for OuterJoin<'a, K, V, W> {}
+
+/// A lazy iterator over the keys in both of two `ListMap`s.
+///
+/// Returned by `ListMap::iter_join`.
+#[derive(Debug)]
+pub struct Join<'a, K: 'a, V: 'a, W: 'a>(OuterJoin<'a, K, V, W>);
+
+impl<'a, K: Ord, V, W> Iterator for Join<'a, K, V, W> {
+    type Item = (&'a K, &'a V, &'a W);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.find_map(|(key, values)| match values {
+            EitherOrBoth::Both(a, b) => Some((key, a, b)),
+            _ => None,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.0.a.len().min(self.0.b.len())))
+    }
+}
+
+impl<'a, K: Ord, V, W> FusedIterator for Join<'a, K, V, W> {}
+
+/// An immutable iterator over the keys and values of a `ListMap`.
+#[derive(Debug)]
+pub struct Iter<'a, K: 'a, V: 'a>(super::Iter<'a, Pair<K, V>>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.0.next().map(|pair| (&pair.key, &pair.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> IntoIterator for &'a ListMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        Iter(self.entries.iter())
+    }
+}
+
+/// An iterator over the keys and mutable values of a `ListMap`.
+#[derive(Debug)]
+pub struct IterMut<'a, K: 'a, V: 'a>(DataMut<'a, Pair<K, V>>);
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        self.0.next().map(|pair| (&pair.key, &mut pair.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, K, V> FusedIterator for IterMut<'a, K, V> {}
+
+impl<'a, K, V> IntoIterator for &'a mut ListMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        IterMut(self.entries.data_mut_iter())
+    }
+}
+
+/// An iterator over the keys of a `ListMap`, in ascending order.
+#[derive(Debug)]
+pub struct Keys<'a, K: 'a, V: 'a>(super::Iter<'a, Pair<K, V>>);
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.0.next().map(|pair| &pair.key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, K, V> FusedIterator for Keys<'a, K, V> {}
+
+/// Collects the remaining keys without comparing them, since they are
+/// already in order.
+impl<'a, K, V> From<Keys<'a, K, V>> for Set<&'a K> {
+    fn from(keys: Keys<'a, K, V>) -> Self {
+        Set::from_sorted_unchecked(keys)
+    }
+}
+
+/// An iterator over the values of a `ListMap`, in ascending order of key.
+#[derive(Debug)]
+pub struct Values<'a, K: 'a, V: 'a>(super::Iter<'a, Pair<K, V>>);
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.0.next().map(|pair| &pair.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, K, V> FusedIterator for Values<'a, K, V> {}
+
+/// An iterator that consumes a `ListMap`, yielding its keys and values.
+#[derive(Debug)]
+pub struct IntoIter<K, V>(super::IntoIter<Pair<K, V>>);
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.0.next().map(|pair| (pair.key, pair.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<K, V> IntoIterator for ListMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter(self.entries.into_iter())
+    }
+}
+
+/// An iterator that removes the key/value pairs of a `ListMap` that
+/// satisfy a predicate.
+///
+/// Returned by `ListMap::drain_filter`.
+#[derive(Debug)]
+pub struct DrainFilter<'a, K: 'a, V: 'a, P>
+    where P: FnMut(&K, &mut V) -> bool
+{
+    cursor: CursorMut<'a, Pair<K, V>>,
+    pred:   P,
+}
+
+impl<'a, K, V, P> Iterator for DrainFilter<'a, K, V, P>
+    where P: FnMut(&K, &mut V) -> bool
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let pred = &mut self.pred;
+        self.cursor.remove_next_where(|pair| pred(&pair.key, &mut pair.value))
+            .map(|pair| (pair.key, pair.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.cursor.remaining()))
+    }
+}
+
+impl<'a, K, V, P> Drop for DrainFilter<'a, K, V, P>
+    where P: FnMut(&K, &mut V) -> bool
+{
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+/// Merges new pairs into the map. Of pairs with equal keys, the last wins.
+impl<K: Ord, V> Extend<(K, V)> for ListMap<K, V> {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        self.entries.append(&mut ListMap::from_iter(iter).entries);
+    }
+}
+
+/// Builds a map in O(n log n) time. Of pairs with equal keys, the last is
+/// kept, as if they were inserted in order.
+impl<K: Ord, V> FromIterator<(K, V)> for ListMap<K, V> {
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let mut pairs: Vec<Pair<K, V>> = iter.into_iter()
+            .map(|(key, value)| Pair { key, value })
+            .collect();
+        // The sort is stable, so later pairs stay after earlier ones.
+        pairs.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let mut unique: Vec<Pair<K, V>> = Vec::with_capacity(pairs.len());
+
+        for pair in pairs {
+            match unique.last_mut() {
+                Some(last) if last.key == pair.key => *last = pair,
+                _ => unique.push(pair),
+            }
+        }
+
+        ListMap {
+            entries: Set::from_sorted_unchecked(unique),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_list_map {
+    use super::ListMap;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl<K: Arbitrary + Ord, V: Arbitrary> Arbitrary for ListMap<K, V> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            FromIterator::from_iter(Vec::<(K, V)>::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            let pairs: Vec<(K, V)> = self.iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+
+            Box::new(pairs.shrink().map(FromIterator::from_iter))
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::{EitherOrBoth, Entry, ListMap};
+    use crate::list_set::Set;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::iter::FromIterator;
+
+    type Model = BTreeMap<u8, i32>;
+
+    fn agrees(map: &ListMap<u8, i32>, model: &Model) -> bool {
+        map.iter().eq(model.iter())
+            && map.len() == model.len()
+            && map.keys().eq(model.keys())
+            && map.values().eq(model.values())
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(u8, i32),
+        Remove(u8),
+        Get(u8),
+        GetMut(u8, i32),
+        OrInsert(u8, i32),
+        AndModify(u8, i32),
+        RemoveEntry(u8),
+        // Drains the pairs with odd values, after adding to every value, and
+        // drops the iterator after `take` of them.
+        DrainFilter(i32, u8),
+        IterMut(i32),
+    }
+
+    use self::Op::*;
+
+    impl Arbitrary for Op {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let key = u8::arbitrary(g) % 16;
+            let value = i32::arbitrary(g);
+
+            match u8::arbitrary(g) % 9 {
+                0 => Insert(key, value),
+                1 => Remove(key),
+                2 => Get(key),
+                3 => GetMut(key, value),
+                4 => OrInsert(key, value),
+                5 => AndModify(key, value),
+                6 => RemoveEntry(key),
+                7 => DrainFilter(value, u8::arbitrary(g) % 4),
+                _ => IterMut(value),
+            }
+        }
+    }
+
+    quickcheck! {
+
+        fn prop_model(ops: Vec<Op>) -> bool {
+            let mut map = ListMap::new();
+            let mut model = Model::new();
+
+            ops.into_iter().all(|op| {
+                let ok = match op {
+                    Insert(k, v) => map.insert(k, v) == model.insert(k, v),
+                    Remove(k) => map.remove(&k) == model.remove(&k),
+                    Get(k) => map.get(&k) == model.get(&k) && map.contains_key(&k) == model.contains_key(&k),
+                    GetMut(k, v) => {
+                        if let Some(value) = model.get_mut(&k) {
+                            *value = value.wrapping_add(v);
+                        }
+                        match map.get_mut(&k) {
+                            Some(value) => { *value = value.wrapping_add(v); true }
+                            None => !model.contains_key(&k),
+                        }
+                    }
+                    OrInsert(k, v) => *map.entry(k).or_insert(v) == *model.entry(k).or_insert(v),
+                    AndModify(k, v) => {
+                        map.entry(k).and_modify(|value| *value ^= v).or_default();
+                        model.entry(k).and_modify(|value| *value ^= v).or_default();
+                        true
+                    }
+                    RemoveEntry(k) => match map.entry(k) {
+                        Entry::Occupied(entry) => Some(entry.remove_entry()) == model.remove_entry(&k),
+                        Entry::Vacant(entry) => *entry.key() == k && !model.contains_key(&k),
+                    },
+                    DrainFilter(v, take) => {
+                        let drained: Vec<(u8, i32)> = map
+                            .drain_filter(|_, value| {
+                                *value = value.wrapping_add(v);
+                                *value % 2 != 0
+                            })
+                            .take(usize::from(take))
+                            .collect();
+
+                        // Every pair is changed, since dropping the iterator
+                        // runs it to the end.
+                        for value in model.values_mut() {
+                            *value = value.wrapping_add(v);
+                        }
+                        let expected: Vec<(u8, i32)> = model.iter()
+                            .filter(|&(_, value)| value % 2 != 0)
+                            .map(|(&k, &v)| (k, v))
+                            .take(usize::from(take))
+                            .collect();
+                        model.retain(|_, value| *value % 2 == 0);
+
+                        drained == expected
+                    }
+                    IterMut(v) => {
+                        for (_, value) in map.iter_mut() {
+                            *value = value.wrapping_sub(v);
+                        }
+                        for value in model.values_mut() {
+                            *value = value.wrapping_sub(v);
+                        }
+                        map.iter_mut().len() == model.len()
+                    }
+                };
+
+                ok && agrees(&map, &model)
+            })
+        }
+
+        // Later pairs win, both within one iterator and over earlier ones.
+        fn prop_from_iter_extend(v1: Vec<(u8, i32)>, v2: Vec<(u8, i32)>) -> bool {
+            let mut map = ListMap::from_iter(v1.iter().cloned());
+            let mut model = Model::from_iter(v1);
+            let ok = agrees(&map, &model);
+
+            map.extend(v2.iter().cloned());
+            model.extend(v2);
+            ok && agrees(&map, &model)
+        }
+
+        fn prop_joins(m1: ListMap<u8, i32>, m2: ListMap<u8, bool>) -> bool {
+            let keys: BTreeSet<&u8> = m1.keys().chain(m2.keys()).collect();
+            let outer: Vec<_> = keys.iter()
+                .map(|&key| {
+                    let values = match (m1.get(key), m2.get(key)) {
+                        (Some(a), Some(b)) => EitherOrBoth::Both(a, b),
+                        (Some(a), None) => EitherOrBoth::Left(a),
+                        (None, Some(b)) => EitherOrBoth::Right(b),
+                        (None, None) => unreachable!(),
+                    };
+                    (key, values)
+                })
+                .collect();
+            let inner: Vec<_> = outer.iter()
+                .filter_map(|&(key, values)| match values {
+                    EitherOrBoth::Both(a, b) => Some((key, a, b)),
+                    _ => None,
+                })
+                .collect();
+
+            let (lo, hi) = m1.iter_outer_join(&m2).size_hint();
+            let (_, inner_hi) = m1.iter_join(&m2).size_hint();
+
+            m1.iter_outer_join(&m2).collect::<Vec<_>>() == outer
+                && m1.iter_join(&m2).collect::<Vec<_>>() == inner
+                && lo <= outer.len() && hi.is_some_and(|hi| outer.len() <= hi)
+                && inner_hi.is_some_and(|hi| inner.len() <= hi)
+        }
+
+        fn prop_key_set(m1: ListMap<u8, i32>, m2: ListMap<u8, i32>) -> bool {
+            let (s1, s2) = (Set::from(m1.keys()), Set::from(m2.keys()));
+
+            s1.iter().eq(m1.keys().collect::<Set<_>>().iter())
+                && s1.len() == m1.len()
+                && s1.intersection(&s2).into_iter().eq(m1.iter_join(&m2).map(|(key, _, _)| key))
+        }
+
+        fn prop_eq_ord(m1: ListMap<u8, i32>, m2: ListMap<u8, i32>) -> bool {
+            let model1 = Model::from_iter(m1.iter().map(|(&k, &v)| (k, v)));
+            let model2 = Model::from_iter(m2.iter().map(|(&k, &v)| (k, v)));
+
+            m1.cmp(&m2) == model1.cmp(&model2)
+                && (m1 == m2) == (model1 == model2)
+                && m1 == m1.clone()
+        }
+
+    }
+}
@@ -0,0 +1,154 @@
+//! Parsing sets from text of the form `{1, 2, 3}`, as `Display` writes them.
+
+use super::Set;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FromIterator;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::error;
+
+/// An error from parsing a `Set`, with the byte offset where it arose.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSetError<E> {
+    offset: usize,
+    kind:   ParseSetErrorKind<E>,
+}
+
+/// The ways that parsing a `Set` can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSetErrorKind<E> {
+    /// The input does not start with `{`.
+    ExpectedOpen,
+    /// There is no element between two commas, or before the first comma.
+    ExpectedElement,
+    /// The input ended before the closing `}`.
+    Unclosed,
+    /// Something other than whitespace follows the closing `}`.
+    TrailingInput,
+    /// An element failed to parse, with the element type's own error.
+    Element(E),
+}
+
+impl<E> ParseSetError<E> {
+    fn new(offset: usize, kind: ParseSetErrorKind<E>) -> Self {
+        ParseSetError { offset, kind }
+    }
+
+    /// Returns the byte offset in the input where the error arose. For an
+    /// element that failed to parse, that is where the element starts.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns what went wrong.
+    pub fn kind(&self) -> &ParseSetErrorKind<E> {
+        &self.kind
+    }
+
+    /// Returns what went wrong, consuming the error.
+    pub fn into_kind(self) -> ParseSetErrorKind<E> {
+        self.kind
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ParseSetError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseSetErrorKind::ExpectedOpen    => write!(f, "expected `{{`")?,
+            ParseSetErrorKind::ExpectedElement => write!(f, "expected an element")?,
+            ParseSetErrorKind::Unclosed        => write!(f, "missing closing `}}`")?,
+            ParseSetErrorKind::TrailingInput   => write!(f, "unexpected input after `}}`")?,
+            ParseSetErrorKind::Element(ref e)  => write!(f, "invalid element ({})", e)?,
+        }
+
+        write!(f, " at byte {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: error::Error + 'static> error::Error for ParseSetError<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self.kind {
+            ParseSetErrorKind::Element(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// Returns the offset of the first non-whitespace character at or after
+// `pos`.
+fn skip_whitespace(s: &str, pos: usize) -> usize {
+    s.len() - s[pos ..].trim_start().len()
+}
+
+/// Parses a set written as `{a, b, c}`, in either of the forms that
+/// `Display` writes.
+///
+/// Whitespace may appear around the braces and elements, a trailing comma
+/// is allowed, and of equal elements the first is kept. Elements are
+/// delimited by `,` and `}`, so they cannot contain those characters.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::list_set::{Set, ParseSetErrorKind};
+/// use std::iter::FromIterator;
+///
+/// let set: Set<u32> = " { 3, 1,2, 3, } ".parse().unwrap();
+/// assert_eq!( set, Set::from_iter(vec![1, 2, 3]) );
+///
+/// let error = "{1, x}".parse::<Set<u32>>().unwrap_err();
+/// assert_eq!( 4, error.offset() );
+/// assert!( matches!(error.kind(), ParseSetErrorKind::Element(_)) );
+/// ```
+impl<T: FromStr + Ord> FromStr for Set<T> {
+    type Err = ParseSetError<T::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let open = skip_whitespace(s, 0);
+
+        if !s[open ..].starts_with('{') {
+            return Err(ParseSetError::new(open, ParseSetErrorKind::ExpectedOpen));
+        }
+
+        let mut pos = open + 1;
+        let mut elems = Vec::new();
+
+        loop {
+            pos = skip_whitespace(s, pos);
+
+            if s[pos ..].starts_with('}') {
+                break;
+            }
+
+            let end = match s[pos ..].find([',', '}']) {
+                Some(len) => pos + len,
+                None => return Err(ParseSetError::new(s.len(), ParseSetErrorKind::Unclosed)),
+            };
+
+            let text = s[pos .. end].trim_end();
+
+            if text.is_empty() {
+                return Err(ParseSetError::new(pos, ParseSetErrorKind::ExpectedElement));
+            }
+
+            let elem = text.parse()
+                .map_err(|e| ParseSetError::new(pos, ParseSetErrorKind::Element(e)))?;
+            elems.push(elem);
+
+            pos = end;
+            if s[pos ..].starts_with(',') {
+                pos += 1;
+            }
+        }
+
+        let rest = skip_whitespace(s, pos + 1);
+
+        if rest < s.len() {
+            return Err(ParseSetError::new(rest, ParseSetErrorKind::TrailingInput));
+        }
+
+        Ok(Set::from_iter(elems))
+    }
+}
@@ -0,0 +1,739 @@
+//! Persistent sets, represented as sorted, singly-linked lists of shared
+//! nodes.
+//!
+//! Like `interior_mut::List` in the 2021-04-22 class code, a
+//! `PersistentSet` holds its nodes in `Rc`s so that several lists can share
+//! a tail. Here, though, the nodes are never mutated once shared: changing
+//! a set copies the nodes before the change and shares everything after it
+//! with the old version, which stays as it was.
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cmp::Ordering::{self, Less, Equal, Greater};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::RangeBounds;
+
+/// An immutable-by-sharing set of elements of type `T`.
+///
+/// Cloning is O(1), and the clones share all their nodes. Updating one
+/// version copies only the nodes before the change.
+///
+/// # Example
+///
+/// ```
+/// use ownership::list_set::PersistentSet;
+/// use std::iter::FromIterator;
+///
+/// let v1 = PersistentSet::from_iter(vec![1, 3, 5]);
+/// let mut v2 = v1.clone();
+/// v2.insert(2);
+///
+/// assert_eq!( v1.iter().collect::<Vec<_>>(), &[&1, &3, &5] );
+/// assert_eq!( v2.iter().collect::<Vec<_>>(), &[&1, &2, &3, &5] );
+/// ```
+pub struct PersistentSet<T> {
+    head: Link<T>,
+    len:  usize,
+}
+// Invariants:
+//  - The elements must be sorted according to <T as Ord>.
+//  - A node reachable from more than one `Rc` is never changed.
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    data: T,
+    link: Link<T>,
+}
+
+impl<T> Drop for PersistentSet<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+
+        // Free nodes until reaching one that another version still holds.
+        while let Some(rc) = head.take() {
+            match Rc::try_unwrap(rc) {
+                Ok(mut node) => head = node.link.take(),
+                Err(_)       => break,
+            }
+        }
+    }
+}
+
+impl<T> Clone for PersistentSet<T> {
+    fn clone(&self) -> Self {
+        PersistentSet {
+            head: self.head.clone(),
+            len:  self.len,
+        }
+    }
+}
+
+impl<T> PersistentSet<T> {
+    /// Creates a new, empty set.
+    pub fn new() -> Self {
+        PersistentSet {
+            head: None,
+            len:  0,
+        }
+    }
+
+    /// Returns whether a set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns a borrowing iterator over the elements of the set.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+
+    /// Returns the least element of the set, if any.
+    pub fn first(&self) -> Option<&T> {
+        self.head.as_ref().map(|node_ptr| &node_ptr.data)
+    }
+
+    /// Returns the greatest element of the set, if any, in O(n) time.
+    pub fn last(&self) -> Option<&T> {
+        self.iter().last()
+    }
+
+    /// Returns the `n`th least element of the set, counting from zero.
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    /// Returns whether two sets are the same version, or versions that
+    /// share all of their nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::PersistentSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let v1 = PersistentSet::from_iter(vec![1, 2]);
+    /// let v2 = v1.clone();
+    /// let v3 = PersistentSet::from_iter(vec![1, 2]);
+    ///
+    /// assert!( v1.ptr_eq(&v2));
+    /// assert!(!v1.ptr_eq(&v3));
+    /// ```
+    pub fn ptr_eq(&self, other: &PersistentSet<T>) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None)       => true,
+            _                  => false,
+        }
+    }
+
+    // Builds a set by putting `prefix`, which must precede every element
+    // of `tail`, in front of `tail`.
+    fn from_parts(prefix: Vec<T>, tail: Link<T>, tail_len: usize) -> Self {
+        let len = prefix.len() + tail_len;
+        let mut head = tail;
+
+        for data in prefix.into_iter().rev() {
+            head = Some(Rc::new(Node { data, link: head }));
+        }
+
+        PersistentSet { head, len }
+    }
+}
+
+impl<T> Default for PersistentSet<T> {
+    fn default() -> Self {
+        PersistentSet::new()
+    }
+}
+
+impl<T: Ord> PersistentSet<T> {
+    /// Checks whether the given set contains the given element.
+    pub fn contains(&self, element: &T) -> bool {
+        self.get(element).is_some()
+    }
+
+    /// Returns the element of the set equal to the given one, if any.
+    pub fn get(&self, element: &T) -> Option<&T> {
+        for data in self {
+            match element.cmp(data) {
+                Less    => break,
+                Equal   => return Some(data),
+                Greater => (),
+            }
+        }
+
+        None
+    }
+
+    /// Finds the position of the given element in the set, as
+    /// `Set::position` does.
+    pub fn position(&self, element: &T) -> Result<usize, usize> {
+        for (index, data) in self.iter().enumerate() {
+            match element.cmp(data) {
+                Less    => return Err(index),
+                Equal   => return Ok(index),
+                Greater => (),
+            }
+        }
+
+        Err(self.len)
+    }
+
+    /// Returns an iterator over the elements of the set that lie within
+    /// the given range, as `Set::range` does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::PersistentSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = PersistentSet::from_iter(1 .. 10);
+    /// assert_eq!( set.range(3 .. 6).collect::<Vec<_>>(), &[&3, &4, &5] );
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Iter<'_, T> {
+        let mut iter = self.iter();
+
+        while iter.link.as_ref().is_some_and(|node| super::below(range.start_bound(), &node.data)) {
+            iter.next();
+        }
+
+        let len = Iter { ..iter }
+            .take_while(|elem| !super::above(range.end_bound(), elem))
+            .count();
+
+        Iter { len, ..iter }
+    }
+
+    /// Returns whether two sets are disjoint.
+    pub fn is_disjoint(&self, other: &PersistentSet<T>) -> bool {
+        let (mut a, mut b) = (&self.head, &other.head);
+
+        while let (Some(x), Some(y)) = (a, b) {
+            if Rc::ptr_eq(x, y) {
+                return false;
+            }
+
+            match x.data.cmp(&y.data) {
+                Less    => a = &x.link,
+                Greater => b = &y.link,
+                Equal   => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether `self` is a subset of `other`.
+    pub fn is_subset(&self, other: &PersistentSet<T>) -> bool {
+        if self.len > other.len {
+            return false;
+        }
+
+        let (mut a, mut b) = (&self.head, &other.head);
+
+        while let Some(x) = a {
+            let y = match b {
+                Some(y) => y,
+                None    => return false,
+            };
+
+            if Rc::ptr_eq(x, y) {
+                return true;
+            }
+
+            match x.data.cmp(&y.data) {
+                Less    => return false,
+                Greater => b = &y.link,
+                Equal   => {
+                    a = &x.link;
+                    b = &y.link;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether `self` is a superset of `other`.
+    pub fn is_superset(&self, other: &PersistentSet<T>) -> bool {
+        other.is_subset(self)
+    }
+}
+
+impl<T: Ord + Clone> PersistentSet<T> {
+    /// Adds the element to this version of the set, copying the nodes
+    /// before it that other versions share.
+    ///
+    /// Returns `true` if the set did not previously contain the
+    /// element, and `false` if it did.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::PersistentSet;
+    /// let mut set = PersistentSet::new();
+    ///
+    /// assert!( set.insert(3));
+    /// assert!(!set.insert(3));
+    /// ```
+    pub fn insert(&mut self, element: T) -> bool {
+        if self.contains(&element) {
+            return false;
+        }
+
+        let link = self.seek_mut(&element);
+        let rest = link.take();
+        *link = Some(Rc::new(Node { data: element, link: rest }));
+        self.len += 1;
+        true
+    }
+
+    /// Removes the element from this version of the set, copying the nodes
+    /// before it that other versions share.
+    ///
+    /// Returns whether the element was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::PersistentSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let v1 = PersistentSet::from_iter(vec![1, 2, 3]);
+    /// let mut v2 = v1.clone();
+    ///
+    /// assert!( v2.remove(&2));
+    /// assert!(!v2.remove(&2));
+    /// assert!( v1.contains(&2));
+    /// ```
+    pub fn remove(&mut self, element: &T) -> bool {
+        if !self.contains(element) {
+            return false;
+        }
+
+        let link = self.seek_mut(element);
+        let node = link.take().unwrap();
+        *link = node.link.clone();
+        self.len -= 1;
+        true
+    }
+
+    // Returns the link to the first node not less than `element`, first
+    // making every node before it unshared.
+    fn seek_mut(&mut self, element: &T) -> &mut Link<T> {
+        let mut link = &mut self.head;
+
+        while link.as_ref().is_some_and(|node| node.data < *element) {
+            link = &mut Rc::make_mut({link}.as_mut().unwrap()).link;
+        }
+
+        link
+    }
+
+    /// Returns the intersection of two sets, sharing their common suffix.
+    pub fn intersection(&self, other: &PersistentSet<T>) -> Self {
+        self.merge(other, false, true, false)
+    }
+
+    /// Returns the union of two sets, sharing whatever suffix of either
+    /// set it can.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::PersistentSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let big = PersistentSet::from_iter(0 .. 1000);
+    /// let small = PersistentSet::from_iter(vec![5, 7]);
+    /// let both = big.union(&small);
+    ///
+    /// // Only the nodes up to 7 are new; the rest belong to `big`.
+    /// assert_eq!( 1000, both.len() );
+    /// assert!( both.range(8 ..).eq(big.range(8 ..)) );
+    /// ```
+    pub fn union(&self, other: &PersistentSet<T>) -> Self {
+        self.merge(other, true, true, true)
+    }
+
+    /// Returns the difference of two sets, sharing the suffix of `self`
+    /// past the end of `other`.
+    pub fn difference(&self, other: &PersistentSet<T>) -> Self {
+        self.merge(other, true, false, false)
+    }
+
+    /// Returns the symmetric difference of two sets, sharing the suffix of
+    /// whichever set extends past the other.
+    pub fn symmetric_difference(&self, other: &PersistentSet<T>) -> Self {
+        self.merge(other, true, false, true)
+    }
+
+    // Merges two sets, keeping the elements found only on the left, in
+    // both, or only on the right, as requested. Once one side runs out, or
+    // the two sides reach a node they share, the rest of the result is the
+    // rest of one side (or nothing), so it is shared rather than copied.
+    fn merge(&self, other: &PersistentSet<T>, left: bool, both: bool, right: bool) -> Self {
+        let (mut a, mut a_len) = (&self.head, self.len);
+        let (mut b, mut b_len) = (&other.head, other.len);
+        let mut prefix = Vec::new();
+
+        let (tail, tail_len) = loop {
+            match (a, b) {
+                (Some(x), Some(y)) => {
+                    if Rc::ptr_eq(x, y) {
+                        break if both { (a.clone(), a_len) } else { (None, 0) };
+                    }
+
+                    match x.data.cmp(&y.data) {
+                        Less => {
+                            if left { prefix.push(x.data.clone()); }
+                            a = &x.link;
+                            a_len -= 1;
+                        }
+                        Greater => {
+                            if right { prefix.push(y.data.clone()); }
+                            b = &y.link;
+                            b_len -= 1;
+                        }
+                        Equal => {
+                            if both { prefix.push(x.data.clone()); }
+                            a = &x.link;
+                            a_len -= 1;
+                            b = &y.link;
+                            b_len -= 1;
+                        }
+                    }
+                }
+                (Some(_), None) => break if left { (a.clone(), a_len) } else { (None, 0) },
+                (None, Some(_)) => break if right { (b.clone(), b_len) } else { (None, 0) },
+                (None, None)    => break (None, 0),
+            }
+        };
+
+        PersistentSet::from_parts(prefix, tail, tail_len)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the set as `{a, b, c}`.
+impl<T: fmt::Display> fmt::Display for PersistentSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{")?;
+
+        for (i, elem) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            elem.fmt(f)?;
+        }
+
+        f.write_str("}")
+    }
+}
+
+impl<T: Ord> Ord for PersistentSet<T> {
+    fn cmp(&self, other: &PersistentSet<T>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Ord> PartialOrd for PersistentSet<T> {
+    fn partial_cmp(&self, other: &PersistentSet<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Stops comparing at the first node the two sets share.
+impl<T: Ord> PartialEq for PersistentSet<T> {
+    fn eq(&self, other: &PersistentSet<T>) -> bool {
+        if self.len != other.len {
+            return false;
+        }
+
+        let (mut a, mut b) = (&self.head, &other.head);
+
+        while let (Some(x), Some(y)) = (a, b) {
+            if Rc::ptr_eq(x, y) {
+                return true;
+            }
+
+            if x.data != y.data {
+                return false;
+            }
+
+            a = &x.link;
+            b = &y.link;
+        }
+
+        true
+    }
+}
+
+impl<T: Ord> Eq for PersistentSet<T> {}
+
+impl<T: Hash> Hash for PersistentSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+/// Of equal elements, the first is kept.
+impl<T: Ord> FromIterator<T> for PersistentSet<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut elems: Vec<T> = iter.into_iter().collect();
+        elems.sort();
+        elems.dedup();
+        PersistentSet::from_parts(elems, None, 0)
+    }
+}
+
+/// An immutable iterator over the elements of a `PersistentSet`, in
+/// ascending order.
+#[derive(Debug)]
+pub struct Iter<'a, T: 'a> {
+    link: &'a Link<T>,
+    len:  usize,
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = self.link.as_ref()?;
+        self.link = &node.link;
+        self.len -= 1;
+        Some(&node.data)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a PersistentSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        Iter {
+            link: &self.head,
+            len:  self.len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::{Link, PersistentSet};
+    use alloc::rc::Rc;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use std::collections::BTreeSet;
+    use std::iter::FromIterator;
+
+    // `Rc` is not `Send`, as `Arbitrary` requires, so the properties take
+    // vectors and build sets from them.
+    fn set_of(vec: Vec<u8>) -> PersistentSet<u8> {
+        PersistentSet::from_iter(vec)
+    }
+
+    // The link to the first node holding an element not less than `elem`.
+    fn link_at(set: &PersistentSet<u8>, elem: u8) -> &Link<u8> {
+        let mut link = &set.head;
+
+        while let Some(node) = link {
+            if node.data >= elem {
+                break;
+            }
+
+            link = &node.link;
+        }
+
+        link
+    }
+
+    fn shared(a: &Link<u8>, b: &Link<u8>) -> bool {
+        match (a, b) {
+            (Some(x), Some(y)) => Rc::ptr_eq(x, y),
+            (None, None)       => true,
+            _                  => false,
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(usize, u8),
+        Remove(usize, u8),
+        Union(usize, usize),
+        Intersection(usize, usize),
+        Difference(usize, usize),
+        SymmetricDifference(usize, usize),
+    }
+
+    use self::Op::*;
+
+    impl Arbitrary for Op {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let v = usize::arbitrary(g);
+            let w = usize::arbitrary(g);
+            let elem = u8::arbitrary(g) % 32;
+
+            match u8::arbitrary(g) % 8 {
+                0 ..= 2 => Insert(v, elem),
+                3 ..= 4 => Remove(v, elem),
+                5       => Union(v, w),
+                6       => Intersection(v, w),
+                _       => if elem % 2 == 0 { Difference(v, w) } else { SymmetricDifference(v, w) },
+            }
+        }
+    }
+
+    quickcheck! {
+
+        // Applies each operation to some earlier version, and checks that
+        // every version still matches its model afterward.
+        fn prop_versions(ops: Vec<Op>) -> bool {
+            let mut versions = vec![(PersistentSet::new(), BTreeSet::new())];
+
+            for op in ops {
+                let n = versions.len();
+
+                let next = match op {
+                    Insert(v, elem) => {
+                        let (mut set, mut model) = versions[v % n].clone();
+                        if set.insert(elem) != model.insert(elem) {
+                            return false;
+                        }
+                        (set, model)
+                    }
+                    Remove(v, elem) => {
+                        let (mut set, mut model) = versions[v % n].clone();
+                        if set.remove(&elem) != model.remove(&elem) {
+                            return false;
+                        }
+                        (set, model)
+                    }
+                    Union(v, w) => {
+                        let ((s1, m1), (s2, m2)) = (&versions[v % n], &versions[w % n]);
+                        (s1.union(s2), m1.union(m2).cloned().collect())
+                    }
+                    Intersection(v, w) => {
+                        let ((s1, m1), (s2, m2)) = (&versions[v % n], &versions[w % n]);
+                        (s1.intersection(s2), m1.intersection(m2).cloned().collect())
+                    }
+                    Difference(v, w) => {
+                        let ((s1, m1), (s2, m2)) = (&versions[v % n], &versions[w % n]);
+                        (s1.difference(s2), m1.difference(m2).cloned().collect())
+                    }
+                    SymmetricDifference(v, w) => {
+                        let ((s1, m1), (s2, m2)) = (&versions[v % n], &versions[w % n]);
+                        (s1.symmetric_difference(s2),
+                         m1.symmetric_difference(m2).cloned().collect())
+                    }
+                };
+
+                versions.push(next);
+
+                let agree = versions.iter().all(|(set, model)| {
+                    set.len() == model.len() && set.iter().eq(model.iter())
+                });
+
+                if !agree {
+                    return false;
+                }
+            }
+
+            true
+        }
+
+        // An insertion or removal shares everything after the change with
+        // the old version.
+        fn prop_shares_tail(v1: Vec<u8>, elem: u8) -> bool {
+            let s1 = set_of(v1);
+            let mut s2 = s1.clone();
+
+            if s2.insert(elem) || s2.remove(&elem) {
+                let old = link_at(&s1, elem.saturating_add(1));
+                let new = link_at(&s2, elem.saturating_add(1));
+                elem == u8::MAX || shared(old, new)
+            } else {
+                false
+            }
+        }
+
+        fn prop_union_shares_tail(v1: Vec<u8>, v2: Vec<u8>) -> bool {
+            let (s1, s2) = (set_of(v1), set_of(v2));
+            let s3 = s1.union(&s2);
+
+            match (s1.last(), s2.last()) {
+                (Some(&a), Some(&b)) => {
+                    let (longer, past) = if a > b { (&s1, b) } else { (&s2, a) };
+                    a == b || shared(link_at(longer, past + 1), link_at(&s3, past + 1))
+                }
+                _ => s3.ptr_eq(if s1.is_empty() { &s2 } else { &s1 }),
+            }
+        }
+
+        fn prop_relations(v1: Vec<u8>, v2: Vec<u8>) -> bool {
+            let (s1, s2) = (set_of(v1), set_of(v2));
+            let b1: BTreeSet<u8> = s1.iter().cloned().collect();
+            let b2: BTreeSet<u8> = s2.iter().cloned().collect();
+            let s3 = s1.union(&s2);
+
+            s1.is_disjoint(&s2) == b1.is_disjoint(&b2)
+                && s1.is_subset(&s2) == b1.is_subset(&b2)
+                && s1.is_subset(&s3) && s3.is_superset(&s2)
+                && (s1 == s2) == (b1 == b2)
+        }
+
+        fn prop_read_api(v1: Vec<u8>, elem: u8) -> bool {
+            let s1 = set_of(v1);
+            let vec: Vec<u8> = s1.iter().cloned().collect();
+
+            s1.first() == vec.first() && s1.last() == vec.last()
+                && s1.position(&elem) == vec.binary_search(&elem)
+                && s1.contains(&elem) == vec.contains(&elem)
+                && s1.nth(elem as usize) == vec.get(elem as usize)
+                && s1.range(elem ..).eq(vec.iter().filter(|e| **e >= elem))
+                && s1.range(.. elem).eq(vec.iter().filter(|e| **e < elem))
+        }
+
+    }
+}
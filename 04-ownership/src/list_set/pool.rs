@@ -0,0 +1,107 @@
+//! A free list of nodes, so that a set whose elements churn can reuse its
+//! allocations instead of going back to the allocator.
+
+use super::Node;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// How a set's node allocations have been served. Returned by
+/// `Set::pool_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PoolStats {
+    /// Nodes reused from the pool.
+    pub hits:     usize,
+    /// Nodes that had to come from the allocator.
+    pub misses:   usize,
+    /// Freed nodes that the pool holds now.
+    pub pooled:   usize,
+    /// The most freed nodes that the pool will hold.
+    pub capacity: usize,
+}
+
+pub(super) struct Pool<T> {
+    free:     Vec<Box<MaybeUninit<Node<T>>>>,
+    capacity: usize,
+    hits:     usize,
+    misses:   usize,
+}
+// Invariant: `free` holds at most `capacity` nodes, none of them
+// initialized, and never reallocates.
+
+impl<T> Pool<T> {
+    pub(super) fn new() -> Self {
+        Pool::with_capacity(0)
+    }
+
+    pub(super) fn with_capacity(capacity: usize) -> Self {
+        Pool {
+            free: Vec::with_capacity(capacity),
+            capacity,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    // Returns an unlinked node holding `data`, reusing a pooled one if
+    // there is any.
+    pub(super) fn alloc(&mut self, data: T) -> Box<Node<T>> {
+        let node = Node { data, link: None };
+
+        match self.free.pop() {
+            Some(mut slot) => {
+                self.hits += 1;
+                slot.write(node);
+                // SAFETY: the slot is now initialized, and `MaybeUninit`
+                // has the same layout as the node it wraps.
+                unsafe { Box::from_raw(Box::into_raw(slot).cast()) }
+            }
+            None => {
+                self.misses += 1;
+                Box::new(node)
+            }
+        }
+    }
+
+    // Takes the data out of an unlinked node, keeping the node for reuse if
+    // there is room.
+    pub(super) fn free(&mut self, node: Box<Node<T>>) -> T {
+        if self.free.len() == self.capacity {
+            return node.data;
+        }
+
+        let raw = Box::into_raw(node);
+        // SAFETY: `raw` comes from a box we own. Reading the node out moves
+        // both fields, after which the allocation is uninitialized, as its
+        // new type records.
+        let Node { data, link } = unsafe { ptr::read(raw) };
+        self.free.push(unsafe { Box::from_raw(raw.cast()) });
+
+        debug_assert!(link.is_none(), "Pool::free: node is still linked");
+        data
+    }
+
+    pub(super) fn shrink(&mut self) {
+        self.free.clear();
+    }
+
+    pub(super) fn stats(&self) -> PoolStats {
+        PoolStats {
+            hits:     self.hits,
+            misses:   self.misses,
+            pooled:   self.free.len(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("pooled", &self.free.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
@@ -0,0 +1,550 @@
+//! Sets of disjoint, half-open intervals, represented as sorted,
+//! singly-linked lists.
+
+use super::{CursorMut, Set};
+use crate::merge::Merge;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator};
+use core::ops::Range;
+
+/// Types whose values can be stepped through one at a time, as
+/// `RangeSet::points` requires.
+pub trait Step: Ord + Clone {
+    /// The least value of the type.
+    const MIN: Self;
+    /// The greatest value of the type.
+    const MAX: Self;
+
+    /// Returns the next value after `self`, or `None` if there is none.
+    fn successor(&self) -> Option<Self>;
+}
+
+macro_rules! impl_step_for_int {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl Step for $ty {
+                const MIN: Self = <$ty>::MIN;
+                const MAX: Self = <$ty>::MAX;
+
+                fn successor(&self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_step_for_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A set of values of type `T`, stored as disjoint half-open intervals.
+///
+/// Overlapping and adjacent intervals are merged as they are inserted, so
+/// each run of consecutive values is a single interval. Since intervals
+/// are half-open, `T::MAX` itself can never be a member.
+///
+/// # Example
+///
+/// ```
+/// use ownership::list_set::RangeSet;
+///
+/// let mut ports = RangeSet::new();
+/// ports.insert_range(8000 .. 8010);
+/// ports.insert_range(8010 .. 8020);
+/// ports.remove_range(8005 .. 8007);
+///
+/// assert!( ports.contains(&8004));
+/// assert!(!ports.contains(&8005));
+/// assert_eq!( ports.intervals().collect::<Vec<_>>(),
+///             vec![8000 .. 8005, 8007 .. 8020] );
+/// ```
+#[derive(Clone)]
+pub struct RangeSet<T> {
+    intervals: Set<Interval<T>>,
+}
+// Invariant: every interval is non-empty, and each ends strictly before the
+// next begins, so that no two could be merged.
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Interval<T> {
+    start: T,
+    end:   T,
+}
+
+impl<T> Interval<T> {
+    fn into_range(self) -> Range<T> {
+        self.start .. self.end
+    }
+}
+
+impl<T> RangeSet<T> {
+    /// Creates a new, empty range-set.
+    pub fn new() -> Self {
+        RangeSet { intervals: Set::new() }
+    }
+
+    /// Returns whether a range-set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns the number of maximal intervals in the set.
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet::new()
+    }
+}
+
+impl<T: Step> RangeSet<T> {
+    /// Returns an iterator over the maximal intervals of the set, in
+    /// ascending order.
+    pub fn intervals(&self) -> Intervals<'_, T> {
+        Intervals(self.intervals.iter())
+    }
+
+    /// Returns an iterator over the individual values in the set, in
+    /// ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::RangeSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = RangeSet::from_iter(vec![1 .. 3, 7 .. 9]);
+    /// assert_eq!( set.points().collect::<Vec<_>>(), &[1, 2, 7, 8] );
+    /// ```
+    pub fn points(&self) -> Points<'_, T> {
+        Points {
+            intervals: self.intervals(),
+            current:   None,
+        }
+    }
+
+    /// Checks whether the set contains the given value.
+    pub fn contains(&self, value: &T) -> bool {
+        for interval in &self.intervals {
+            if *value < interval.start {
+                break;
+            }
+
+            if *value < interval.end {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Adds every value in `range` to the set, merging it with any
+    /// intervals that it overlaps or touches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::RangeSet;
+    /// let mut set = RangeSet::new();
+    /// set.insert_range(1 .. 3);
+    /// set.insert_range(5 .. 7);
+    /// set.insert_range(3 .. 5);
+    ///
+    /// assert_eq!( set.intervals().collect::<Vec<_>>(), vec![1 .. 7] );
+    /// ```
+    pub fn insert_range(&mut self, range: Range<T>) {
+        let Range { mut start, mut end } = range;
+
+        if start >= end {
+            return;
+        }
+
+        let mut cur = CursorMut::new(&mut self.intervals);
+
+        // Skip the intervals that end before `start` without touching it.
+        while cur.peek().is_some_and(|interval| interval.end < start) {
+            cur.move_next();
+        }
+
+        // Absorb every interval that overlaps or touches the new one.
+        while cur.peek().is_some_and(|interval| interval.start <= end) {
+            let interval = cur.remove_current().unwrap();
+
+            if interval.start < start {
+                start = interval.start;
+            }
+
+            if interval.end > end {
+                end = interval.end;
+            }
+        }
+
+        cur.insert_unchecked(Interval { start, end });
+    }
+
+    /// Removes every value in `range` from the set, splitting any interval
+    /// that extends past both of its ends.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::RangeSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = RangeSet::from_iter(vec![0 .. 10]);
+    /// set.remove_range(3 .. 5);
+    ///
+    /// assert_eq!( set.intervals().collect::<Vec<_>>(), vec![0 .. 3, 5 .. 10] );
+    /// ```
+    pub fn remove_range(&mut self, range: Range<T>) {
+        let Range { start, end } = range;
+
+        if start >= end {
+            return;
+        }
+
+        let mut cur = CursorMut::new(&mut self.intervals);
+
+        while cur.peek().is_some_and(|interval| interval.end <= start) {
+            cur.move_next();
+        }
+
+        while cur.peek().is_some_and(|interval| interval.start < end) {
+            let interval = cur.remove_current().unwrap();
+
+            if interval.start < start {
+                cur.insert_unchecked(Interval { start: interval.start, end: start.clone() });
+                cur.move_next();
+            }
+
+            if interval.end > end {
+                cur.insert_unchecked(Interval { start: end, end: interval.end });
+                return;
+            }
+        }
+    }
+
+    /// Returns the set of values in either set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::RangeSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = RangeSet::from_iter(vec![0 .. 4, 10 .. 12]);
+    /// let set2 = RangeSet::from_iter(vec![2 .. 6, 12 .. 14]);
+    ///
+    /// assert_eq!( set1.union(&set2).intervals().collect::<Vec<_>>(),
+    ///             vec![0 .. 6, 10 .. 14] );
+    /// ```
+    pub fn union(&self, other: &RangeSet<T>) -> Self {
+        let mut result: Vec<Interval<T>> = Vec::new();
+
+        // The intervals of both sets arrive ordered by their starts, so
+        // each either extends the last interval of the result or begins a
+        // new one.
+        for (interval, _) in Merge::new(self.intervals.iter(), other.intervals.iter()) {
+            match result.last_mut() {
+                Some(last) if interval.start <= last.end => {
+                    if interval.end > last.end {
+                        last.end = interval.end.clone();
+                    }
+                }
+                _ => result.push(interval.clone()),
+            }
+        }
+
+        RangeSet { intervals: Set::from_sorted_unchecked(result) }
+    }
+
+    /// Returns the set of values in both sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::RangeSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = RangeSet::from_iter(vec![0 .. 4, 10 .. 12]);
+    /// let set2 = RangeSet::from_iter(vec![2 .. 11]);
+    ///
+    /// assert_eq!( set1.intersection(&set2).intervals().collect::<Vec<_>>(),
+    ///             vec![2 .. 4, 10 .. 11] );
+    /// ```
+    pub fn intersection(&self, other: &RangeSet<T>) -> Self {
+        let mut i = self.intervals.iter().peekable();
+        let mut j = other.intervals.iter().peekable();
+        let mut result = Vec::new();
+
+        while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
+            let start = (&a.start).max(&b.start);
+            let end = (&a.end).min(&b.end);
+
+            if start < end {
+                result.push(Interval { start: start.clone(), end: end.clone() });
+            }
+
+            // Whichever interval ends first can overlap nothing further.
+            if a.end <= b.end {
+                i.next();
+            } else {
+                j.next();
+            }
+        }
+
+        RangeSet { intervals: Set::from_sorted_unchecked(result) }
+    }
+
+    /// Returns the set of values not in `self`, other than `T::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::RangeSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = RangeSet::from_iter(vec![0 .. 10, 20 .. 30]);
+    ///
+    /// assert_eq!( set.complement().intervals().collect::<Vec<_>>(),
+    ///             vec![10 .. 20, 30 .. 255u8] );
+    /// ```
+    pub fn complement(&self) -> Self {
+        let mut result = Vec::new();
+        let mut start = T::MIN;
+
+        for interval in &self.intervals {
+            if start < interval.start {
+                result.push(Interval { start, end: interval.start.clone() });
+            }
+
+            start = interval.end.clone();
+        }
+
+        if start < T::MAX {
+            result.push(Interval { start, end: T::MAX });
+        }
+
+        RangeSet { intervals: Set::from_sorted_unchecked(result) }
+    }
+
+    /// Returns the set of values in `self` but not in `other`.
+    pub fn difference(&self, other: &RangeSet<T>) -> Self {
+        self.intersection(&other.complement())
+    }
+
+    /// Returns the parts of `range` that the set does not cover.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::list_set::RangeSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let used = RangeSet::from_iter(vec![100 .. 110, 120 .. 130]);
+    ///
+    /// assert_eq!( used.gaps_within(105 .. 125).intervals().collect::<Vec<_>>(),
+    ///             vec![110 .. 120] );
+    /// ```
+    pub fn gaps_within(&self, range: Range<T>) -> Self {
+        let mut within = RangeSet::new();
+        within.insert_range(range);
+        within.difference(self)
+    }
+}
+
+impl<T: Ord> PartialEq for RangeSet<T> {
+    fn eq(&self, other: &RangeSet<T>) -> bool {
+        self.intervals == other.intervals
+    }
+}
+
+impl<T: Ord> Eq for RangeSet<T> {}
+
+impl<T: Hash> Hash for RangeSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.intervals.hash(state);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RangeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(self.intervals.iter().map(|interval| &interval.start .. &interval.end))
+            .finish()
+    }
+}
+
+impl<T: Step> Extend<Range<T>> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item=Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert_range(range);
+        }
+    }
+}
+
+impl<T: Step> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item=Range<T>>>(iter: I) -> Self {
+        let mut result = RangeSet::new();
+        result.extend(iter);
+        result
+    }
+}
+
+/// An iterator over the maximal intervals of a `RangeSet`.
+///
+/// Created by [`RangeSet::intervals`].
+#[derive(Debug)]
+pub struct Intervals<'a, T: 'a>(super::Iter<'a, Interval<T>>);
+
+impl<'a, T: Clone> Iterator for Intervals<'a, T> {
+    type Item = Range<T>;
+
+    fn next(&mut self) -> Option<Range<T>> {
+        self.0.next().cloned().map(Interval::into_range)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T: Clone> ExactSizeIterator for Intervals<'a, T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, T: Clone> FusedIterator for Intervals<'a, T> {}
+
+/// An iterator over the individual values of a `RangeSet`.
+///
+/// Created by [`RangeSet::points`].
+#[derive(Debug)]
+pub struct Points<'a, T: 'a> {
+    intervals: Intervals<'a, T>,
+    current:   Option<Range<T>>,
+}
+
+impl<'a, T: Step> Iterator for Points<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(ref mut range) = self.current {
+                if range.start < range.end {
+                    let next = range.start.successor()
+                        .expect("Points::next: value before end has no successor");
+                    return Some(core::mem::replace(&mut range.start, next));
+                }
+            }
+
+            self.current = Some(self.intervals.next()?);
+        }
+    }
+}
+
+impl<'a, T: Step> FusedIterator for Points<'a, T> {}
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_range_set {
+    use super::{RangeSet, Step};
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl<T: Arbitrary + Step> Arbitrary for RangeSet<T> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            Vec::<(T, T)>::arbitrary(g).into_iter()
+                .map(|(start, end)| start .. end)
+                .collect()
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            let pairs: Vec<(T, T)> = self.intervals()
+                .map(|range| (range.start, range.end))
+                .collect();
+
+            Box::new(pairs.shrink()
+                .map(|pairs| pairs.into_iter().map(|(start, end)| start .. end).collect()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::RangeSet;
+    use quickcheck::quickcheck;
+    use std::collections::BTreeSet;
+
+    type Model = BTreeSet<u8>;
+
+    fn model_of(set: &RangeSet<u8>) -> Model {
+        set.points().collect()
+    }
+
+    // Checks that the intervals are non-empty and could not be merged, and
+    // that the points are those of `model`.
+    fn agrees(set: &RangeSet<u8>, model: &Model) -> bool {
+        let intervals: Vec<_> = set.intervals().collect();
+
+        intervals.iter().all(|range| range.start < range.end)
+            && intervals.windows(2).all(|w| w[0].end < w[1].start)
+            && set.points().eq(model.iter().cloned())
+            && (0 ..= u8::MAX).all(|point| set.contains(&point) == model.contains(&point))
+    }
+
+    quickcheck! {
+
+        fn prop_insert_remove(ops: Vec<(bool, u8, u8)>) -> bool {
+            let mut set = RangeSet::new();
+            let mut model = Model::new();
+
+            ops.into_iter().all(|(insert, start, end)| {
+                if insert {
+                    set.insert_range(start .. end);
+                    model.extend(start .. end);
+                } else {
+                    set.remove_range(start .. end);
+                    model.retain(|point| !(start .. end).contains(point));
+                }
+
+                agrees(&set, &model)
+            })
+        }
+
+        fn prop_union(s1: RangeSet<u8>, s2: RangeSet<u8>) -> bool {
+            let expected = model_of(&s1).union(&model_of(&s2)).cloned().collect();
+            agrees(&s1.union(&s2), &expected)
+        }
+
+        fn prop_intersection(s1: RangeSet<u8>, s2: RangeSet<u8>) -> bool {
+            let expected = model_of(&s1).intersection(&model_of(&s2)).cloned().collect();
+            agrees(&s1.intersection(&s2), &expected)
+        }
+
+        fn prop_difference(s1: RangeSet<u8>, s2: RangeSet<u8>) -> bool {
+            let expected = model_of(&s1).difference(&model_of(&s2)).cloned().collect();
+            agrees(&s1.difference(&s2), &expected)
+        }
+
+        fn prop_complement(s1: RangeSet<u8>) -> bool {
+            let model = model_of(&s1);
+            let expected = (0 .. u8::MAX).filter(|point| !model.contains(point)).collect();
+
+            agrees(&s1.complement(), &expected) && s1.complement().complement() == s1
+        }
+
+        fn prop_gaps_within(s1: RangeSet<u8>, start: u8, end: u8) -> bool {
+            let model = model_of(&s1);
+            let expected = (start .. end).filter(|point| !model.contains(point)).collect();
+
+            agrees(&s1.gaps_within(start .. end), &expected)
+        }
+
+    }
+}
@@ -0,0 +1,216 @@
+//! A trait for the set operations that `Set` shares with the standard
+//! library's sets, so that algorithms can be written once for all of them.
+
+use super::Set;
+use alloc::collections::BTreeSet;
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+/// The operations common to `Set`, `BTreeSet` and, with the `std` feature,
+/// `HashSet`.
+///
+/// Each method behaves as the type's own method of the same name, with two
+/// exceptions. The set-algebra operations return a new set of the same
+/// type, where the standard library's sets return iterators. And `remove`
+/// returns whether the element was present, as the standard library's sets
+/// do, where `Set::remove` returns the removed element.
+///
+/// # Example
+///
+/// ```
+/// use ownership::list_set::{Set, SetOps};
+/// use std::collections::BTreeSet;
+/// # #[cfg(feature = "std")]
+/// use std::collections::HashSet;
+/// use std::iter::FromIterator;
+///
+/// fn common<S: SetOps<u32>>(a: &S, b: &S, c: &S) -> S {
+///     a.intersection(b).intersection(c)
+/// }
+///
+/// let lists: Vec<Set<u32>> = vec![
+///     Set::from_iter(vec![1, 2, 3]), Set::from_iter(vec![2, 3]), Set::from_iter(vec![3, 2, 1]),
+/// ];
+/// let trees: Vec<BTreeSet<u32>> = lists.iter().map(|set| set.iter().cloned().collect()).collect();
+///
+/// assert_eq!( Set::from_iter(vec![2, 3]), common(&lists[0], &lists[1], &lists[2]) );
+/// assert_eq!( BTreeSet::from_iter(vec![2, 3]), common(&trees[0], &trees[1], &trees[2]) );
+///
+/// # #[cfg(feature = "std")] {
+/// let hashes: Vec<HashSet<u32>> = lists.iter().map(|set| set.iter().cloned().collect()).collect();
+/// assert_eq!( HashSet::from_iter(vec![2, 3]), common(&hashes[0], &hashes[1], &hashes[2]) );
+/// # }
+/// ```
+pub trait SetOps<T>: Sized {
+    /// Returns the number of elements in the set.
+    fn len(&self) -> usize;
+
+    /// Returns whether the set is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether the set contains the given element.
+    fn contains(&self, element: &T) -> bool;
+
+    /// Adds the element to the set, returning whether it was absent.
+    fn insert(&mut self, element: T) -> bool;
+
+    /// Removes the element from the set, returning whether it was present.
+    fn remove(&mut self, element: &T) -> bool;
+
+    /// Returns whether `self` is a subset of `other`.
+    fn is_subset(&self, other: &Self) -> bool;
+
+    /// Returns whether `self` is a superset of `other`.
+    fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns whether two sets are disjoint.
+    fn is_disjoint(&self, other: &Self) -> bool;
+
+    /// Returns the intersection of two sets.
+    fn intersection(&self, other: &Self) -> Self;
+
+    /// Returns the union of two sets.
+    fn union(&self, other: &Self) -> Self;
+
+    /// Returns the difference of two sets.
+    fn difference(&self, other: &Self) -> Self;
+
+    /// Returns the symmetric difference of two sets.
+    fn symmetric_difference(&self, other: &Self) -> Self;
+}
+
+impl<T: Ord + Clone> SetOps<T> for Set<T> {
+    fn len(&self) -> usize {
+        Set::len(self)
+    }
+
+    fn contains(&self, element: &T) -> bool {
+        Set::contains(self, element)
+    }
+
+    fn insert(&mut self, element: T) -> bool {
+        Set::insert(self, element)
+    }
+
+    fn remove(&mut self, element: &T) -> bool {
+        Set::remove(self, element).is_some()
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        Set::is_subset(self, other)
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        Set::is_disjoint(self, other)
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        Set::intersection(self, other)
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        Set::union(self, other)
+    }
+
+    fn difference(&self, other: &Self) -> Self {
+        Set::difference(self, other)
+    }
+
+    fn symmetric_difference(&self, other: &Self) -> Self {
+        Set::symmetric_difference(self, other)
+    }
+}
+
+impl<T: Ord + Clone> SetOps<T> for BTreeSet<T> {
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    fn contains(&self, element: &T) -> bool {
+        BTreeSet::contains(self, element)
+    }
+
+    fn insert(&mut self, element: T) -> bool {
+        BTreeSet::insert(self, element)
+    }
+
+    fn remove(&mut self, element: &T) -> bool {
+        BTreeSet::remove(self, element)
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        BTreeSet::is_subset(self, other)
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        BTreeSet::is_disjoint(self, other)
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        BTreeSet::intersection(self, other).cloned().collect()
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        BTreeSet::union(self, other).cloned().collect()
+    }
+
+    fn difference(&self, other: &Self) -> Self {
+        BTreeSet::difference(self, other).cloned().collect()
+    }
+
+    fn symmetric_difference(&self, other: &Self) -> Self {
+        BTreeSet::symmetric_difference(self, other).cloned().collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> SetOps<T> for HashSet<T, S>
+    where T: Eq + Hash + Clone,
+          S: BuildHasher + Default
+{
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn contains(&self, element: &T) -> bool {
+        HashSet::contains(self, element)
+    }
+
+    fn insert(&mut self, element: T) -> bool {
+        HashSet::insert(self, element)
+    }
+
+    fn remove(&mut self, element: &T) -> bool {
+        HashSet::remove(self, element)
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        HashSet::is_subset(self, other)
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        HashSet::is_disjoint(self, other)
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        HashSet::intersection(self, other).cloned().collect()
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        HashSet::union(self, other).cloned().collect()
+    }
+
+    fn difference(&self, other: &Self) -> Self {
+        HashSet::difference(self, other).cloned().collect()
+    }
+
+    fn symmetric_difference(&self, other: &Self) -> Self {
+        HashSet::symmetric_difference(self, other).cloned().collect()
+    }
+}
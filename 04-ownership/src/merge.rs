@@ -0,0 +1,399 @@
+//! The sorted merge that the ordered sets share.
+//!
+//! Each of `list_set::Set`, `SkipSet`, `SortedVecSet` and `UnrolledSet`
+//! walks two ascending sequences in step for its lazy set operations, for
+//! the in-place operations that take the other set by value, and to format
+//! itself. This module does that once, over any ascending iterator.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering::{Less, Equal, Greater};
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Merges whose sides differ in length by at least this factor gallop.
+const GALLOP_RATIO: usize = 8;
+
+/// Returns whether merging sides of the given lengths should gallop,
+/// seeking ahead in the longer side rather than stepping through it.
+pub(crate) fn lopsided(m: usize, n: usize) -> bool {
+    m.min(n).saturating_mul(GALLOP_RATIO) <= m.max(n)
+}
+
+/// An iterator over the elements of a set in ascending order.
+pub trait Ascending<'a, T: 'a>: ExactSizeIterator<Item=&'a T> + Sized {
+    /// Consumes the elements less than `bound`, and returns the next one.
+    ///
+    /// Stepping through them is always correct; sets that can skip ahead
+    /// faster override this.
+    fn seek(&mut self, bound: &T) -> Option<&'a T>
+        where T: Ord
+    {
+        self.find(|elem| *elem >= bound)
+    }
+}
+
+/// Which of the two sides of a merge hold an element.
+#[derive(Debug, Clone, Copy)]
+pub enum Which {
+    Left,
+    Right,
+    Both,
+}
+
+// One side of a merge: the element it has reached, and the rest.
+#[derive(Debug)]
+struct Side<'a, T: 'a, I> {
+    head: Option<&'a T>,
+    rest: I,
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Side<'a, T, I> {
+    fn new(mut rest: I) -> Self {
+        Side {
+            head: rest.next(),
+            rest,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.head.map_or(0, |_| 1) + self.rest.len()
+    }
+
+    fn advance(&mut self) {
+        self.head = self.rest.next();
+    }
+
+    // Moves past `head`, which is less than `bound`, either one element or,
+    // galloping, to the first element not less than `bound`.
+    fn skip(&mut self, bound: &T, gallop: bool) {
+        self.head = if gallop { self.rest.seek(bound) } else { self.rest.next() };
+    }
+}
+
+/// Walks two sets in step, classifying each element by which sets hold it.
+#[derive(Debug)]
+pub struct Merge<'a, T: 'a, I> {
+    a:      Side<'a, T, I>,
+    b:      Side<'a, T, I>,
+    gallop: bool,
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Merge<'a, T, I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        let gallop = lopsided(a.len(), b.len());
+
+        Merge {
+            a: Side::new(a),
+            b: Side::new(b),
+            gallop,
+        }
+    }
+
+    pub(crate) fn lens(&self) -> (usize, usize) {
+        (self.a.len(), self.b.len())
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Iterator for Merge<'a, T, I> {
+    type Item = (&'a T, Which);
+
+    fn next(&mut self) -> Option<(&'a T, Which)> {
+        match (self.a.head, self.b.head) {
+            (Some(a), Some(b)) => match a.cmp(b) {
+                Less => {
+                    self.a.advance();
+                    Some((a, Which::Left))
+                }
+                Greater => {
+                    self.b.advance();
+                    Some((b, Which::Right))
+                }
+                Equal => {
+                    self.a.advance();
+                    self.b.advance();
+                    Some((a, Which::Both))
+                }
+            }
+            (Some(a), None) => {
+                self.a.advance();
+                Some((a, Which::Left))
+            }
+            (None, Some(b)) => {
+                self.b.advance();
+                Some((b, Which::Right))
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> FusedIterator for Merge<'a, T, I> {}
+
+/// A lazy iterator over the intersection of two sets.
+#[derive(Debug)]
+pub struct Intersection<'a, T: 'a, I>(Merge<'a, T, I>);
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Intersection<'a, T, I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        Intersection(Merge::new(a, b))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Iterator for Intersection<'a, T, I> {
+    type Item = &'a T;
+
+    // Nothing more can match once either side runs out, and lopsided
+    // merges skip whole runs that the other side has no match for.
+    fn next(&mut self) -> Option<&'a T> {
+        let Merge { ref mut a, ref mut b, gallop } = self.0;
+
+        while let (Some(x), Some(y)) = (a.head, b.head) {
+            match x.cmp(y) {
+                Less    => a.skip(y, gallop),
+                Greater => b.skip(x, gallop),
+                Equal   => {
+                    a.advance();
+                    b.advance();
+                    return Some(x);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = self.0.lens();
+        (0, Some(a.min(b)))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> FusedIterator for Intersection<'a, T, I> {}
+
+/// A lazy iterator over the union of two sets.
+#[derive(Debug)]
+pub struct Union<'a, T: 'a, I>(Merge<'a, T, I>);
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Union<'a, T, I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        Union(Merge::new(a, b))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Iterator for Union<'a, T, I> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next().map(|(elem, _)| elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = self.0.lens();
+        (a.max(b), a.checked_add(b))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> FusedIterator for Union<'a, T, I> {}
+
+/// A lazy iterator over the difference of two sets.
+#[derive(Debug)]
+pub struct Difference<'a, T: 'a, I>(Merge<'a, T, I>);
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Difference<'a, T, I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        Difference(Merge::new(a, b))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Iterator for Difference<'a, T, I> {
+    type Item = &'a T;
+
+    // Nothing more can come from the left once it runs out, and lopsided
+    // merges skip whole runs of the right that fall between elements of
+    // the left.
+    fn next(&mut self) -> Option<&'a T> {
+        let Merge { ref mut a, ref mut b, gallop } = self.0;
+
+        while let Some(x) = a.head {
+            match b.head.map(|y| y.cmp(x)) {
+                Some(Less)  => b.skip(x, gallop),
+                Some(Equal) => {
+                    a.advance();
+                    b.advance();
+                }
+                _ => {
+                    a.advance();
+                    return Some(x);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = self.0.lens();
+        (a.saturating_sub(b), Some(a))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> FusedIterator for Difference<'a, T, I> {}
+
+/// A lazy iterator over the symmetric difference of two sets.
+#[derive(Debug)]
+pub struct SymmetricDifference<'a, T: 'a, I>(Merge<'a, T, I>);
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> SymmetricDifference<'a, T, I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        SymmetricDifference(Merge::new(a, b))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> Iterator for SymmetricDifference<'a, T, I> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match self.0.next()? {
+                (_, Which::Both) => (),
+                (elem, _) => return Some(elem),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = self.0.lens();
+        (a.max(b) - a.min(b), a.checked_add(b))
+    }
+}
+
+impl<'a, T: Ord + 'a, I: Ascending<'a, T>> FusedIterator for SymmetricDifference<'a, T, I> {}
+
+/// Which of each pair of equal elements `merge_owned` keeps.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Tie {
+    Mine,
+    Theirs,
+    Neither,
+}
+
+/// Merges two strictly ascending sequences into one, keeping one, the
+/// other or neither of each pair of equal elements.
+pub(crate) fn merge_owned<T, I, J>(mine: I, theirs: J, tie: Tie) -> Vec<T>
+    where T: Ord,
+          I: IntoIterator<Item=T>,
+          J: IntoIterator<Item=T>
+{
+    let mut i = mine.into_iter().peekable();
+    let mut j = theirs.into_iter().peekable();
+    let mut result = Vec::with_capacity(i.size_hint().0 + j.size_hint().0);
+
+    while let (Some(a), Some(b)) = (i.peek(), j.peek()) {
+        match a.cmp(b) {
+            Less    => result.extend(i.next()),
+            Greater => result.extend(j.next()),
+            Equal   => {
+                let (a, b) = (i.next(), j.next());
+
+                match tie {
+                    Tie::Mine    => result.extend(a),
+                    Tie::Theirs  => result.extend(b),
+                    Tie::Neither => (),
+                }
+            }
+        }
+    }
+
+    result.extend(i);
+    result.extend(j);
+    result
+}
+
+/// Clones, in ascending order, the elements of two strictly ascending
+/// sequences that are found only on the left, in both, or only on the
+/// right, as requested.
+pub(crate) fn merge_cloned<'a, T, I>(a: I, b: I, left: bool, both: bool, right: bool) -> Vec<T>
+    where T: Ord + Clone + 'a,
+          I: Iterator<Item=&'a T>
+{
+    let mut i = a.peekable();
+    let mut j = b.peekable();
+    let mut result = Vec::new();
+
+    while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
+        match a.cmp(b) {
+            Less => {
+                if left { result.push(a.clone()); }
+                i.next();
+            }
+            Greater => {
+                if right { result.push(b.clone()); }
+                j.next();
+            }
+            Equal => {
+                if both { result.push(a.clone()); }
+                i.next();
+                j.next();
+            }
+        }
+    }
+
+    if left { result.extend(i.cloned()); }
+    if right { result.extend(j.cloned()); }
+    result
+}
+
+/// Formats `elems` as `{a, b, c}`, or with `{:#}`, one element per line.
+pub(crate) fn fmt_set<'a, T, I>(elems: I, f: &mut fmt::Formatter) -> fmt::Result
+    where T: fmt::Display + 'a,
+          I: IntoIterator<Item=&'a T>
+{
+    let mut elems = elems.into_iter().peekable();
+
+    if f.alternate() && elems.peek().is_some() {
+        f.write_str("{\n")?;
+
+        for elem in elems {
+            f.write_str("    ")?;
+            elem.fmt(f)?;
+            f.write_str(",\n")?;
+        }
+
+        return f.write_str("}");
+    }
+
+    f.write_str("{")?;
+
+    for (i, elem) in elems.enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+
+        elem.fmt(f)?;
+    }
+
+    f.write_str("}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Difference, Intersection};
+    use crate::list_set::Set;
+
+    // Once the side that bounds the result runs out, the lazy operations
+    // stop without walking the rest of the other side.
+    #[test]
+    fn lazy_ops_stop_early() {
+        let big: Set<usize> = (1 .. 1000).collect();
+
+        let small: Set<usize> = vec![0].into_iter().collect();
+        let mut intersection = Intersection::new(small.iter(), big.iter());
+        assert_eq!( None, intersection.next() );
+        assert_eq!( (0, 999), intersection.0.lens() );
+
+        let small: Set<usize> = vec![5].into_iter().collect();
+        let mut difference = Difference::new(small.iter(), big.iter());
+        assert_eq!( None, difference.next() );
+        assert_eq!( (0, 994), difference.0.lens() );
+    }
+}
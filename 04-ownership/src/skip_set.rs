@@ -0,0 +1,1781 @@
+//! Sets, represented as skip lists.
+//!
+//! A `SkipSet` offers the same operations as a `list_set::Set`, but each
+//! node also carries a randomly chosen number of express links that skip
+//! over runs of the list, so that searching takes expected O(log n) time
+//! rather than O(n).
+//!
+//! The nodes live in a vector and link to each other by index, which keeps
+//! the whole structure free of `unsafe`.
+//!
+//! The in-place operations that take the other set by value merge the two
+//! sides into a sorted vector and rebuild the towers from it in one pass.
+//! `extend` and the assignment operators that borrow the other set instead
+//! walk forward through `self` once, linking in each new element where it
+//! belongs, so a small batch costs O(k log n) rather than a rebuild.
+//!
+//! A cursor remembers the last node before it on every level, so it can
+//! seek ahead, insert or remove in expected O(log n) time without searching
+//! from the head again.
+
+#[cfg(feature = "std")]
+use crate::codec::{self, Decode, Encode};
+use crate::merge::{self, Ascending, Tie};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering::{self, Less, Equal, Greater};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator, Peekable};
+use core::mem;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+/// The most levels a tower may have.
+const MAX_HEIGHT: usize = 32;
+
+/// The seed used by `SkipSet::new`.
+const DEFAULT_SEED: u64 = 0x853c_49e6_748f_ea9b;
+
+/// A set of elements of type `T`.
+///
+/// # Example
+///
+/// ```
+/// use ownership::skip_set::SkipSet;
+///
+/// let mut set = SkipSet::new();
+///
+/// set.insert("a");
+/// set.insert("b");
+///
+/// if set.contains(&"a") {
+///     set.insert("c");
+/// }
+/// ```
+pub struct SkipSet<T> {
+    nodes: Vec<Option<Node<T>>>,
+    free:  Vec<usize>,
+    head:  Vec<Link>,
+    len:   usize,
+    rng:   Rng,
+}
+// Invariants:
+//  - Following level-0 links from `head[0]` visits every live node once, in
+//    ascending order according to <T as Ord>.
+//  - The level-`l` links visit, in the same order, exactly the nodes whose
+//    towers are more than `l` tall.
+//  - `free` holds the indices of the `None` slots in `nodes`.
+
+/// The index of the next node at some level, if any.
+type Link = Option<usize>;
+
+/// Where a search stopped on some level: `None` for the head, or else the
+/// index of a node.
+type Pred = Option<usize>;
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    data: T,
+    next: Box<[Link]>,
+}
+
+/// A xorshift64* generator, which is plenty for choosing tower heights.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Zero is the one state that xorshift never leaves.
+        Rng(if seed == 0 { DEFAULT_SEED } else { seed })
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Each additional level is half as likely as the one before.
+    fn height(&mut self) -> usize {
+        (self.next_u64().trailing_zeros() as usize + 1).min(MAX_HEIGHT)
+    }
+}
+
+impl<T> SkipSet<T> {
+    /// Creates a new, empty skip-set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// let mut set = SkipSet::new();
+    /// set.insert("hello");
+    /// ```
+    pub fn new() -> Self {
+        SkipSet::with_seed(DEFAULT_SEED)
+    }
+
+    /// Creates a new, empty skip-set whose tower heights are drawn from a
+    /// generator seeded with `seed`.
+    ///
+    /// Sets built with the same seed by the same operations have the same
+    /// shape.
+    pub fn with_seed(seed: u64) -> Self {
+        SkipSet {
+            nodes: Vec::new(),
+            free:  Vec::new(),
+            head:  Vec::new(),
+            len:   0,
+            rng:   Rng::new(seed),
+        }
+    }
+
+    /// Returns whether a set is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// let mut set = SkipSet::new();
+    /// assert!(set.is_empty());
+    ///
+    /// set.insert(5);
+    /// assert!(!set.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// let mut set = SkipSet::new();
+    /// assert_eq!(0, set.len());
+    ///
+    /// set.insert(5);
+    /// set.insert(6);
+    /// set.insert(5);
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns a borrowing iterator over the elements of the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = SkipSet::from_iter(vec![5, 1, 3]);
+    /// let result: Vec<_> = set.iter().collect();
+    ///
+    /// assert_eq!( result, &[&1, &3, &5] );
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+
+    /// Returns a cursor at the first element of the set.
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            set:   self,
+            preds: [None; MAX_HEIGHT],
+        }
+    }
+
+    /// Returns a cursor at the first element of the set that can edit the
+    /// set in place.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            set:   self,
+            preds: [None; MAX_HEIGHT],
+        }
+    }
+
+    /// Returns an iterator that removes and returns elements satisfying a
+    /// predicate, leaving the rest in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SkipSet::from_iter(1 .. 8);
+    /// let evens: Vec<_> = set.drain_filter(|elem| elem % 2 == 0).collect();
+    ///
+    /// assert_eq!( evens, &[2, 4, 6] );
+    /// assert_eq!( set, SkipSet::from_iter(vec![1, 3, 5, 7]) );
+    /// ```
+    pub fn drain_filter<P: FnMut(&T) -> bool>(&mut self, pred: P) -> DrainFilter<'_, T, P> {
+        let height = self.head.len();
+        let len = self.len;
+
+        DrainFilter {
+            preds: [None; MAX_HEIGHT],
+            next:  self.head.first().cloned().flatten(),
+            height,
+            set:   self,
+            pred,
+            len,
+        }
+    }
+
+    /// Keeps only the elements that satisfy a predicate.
+    pub fn retain<P: FnMut(&T) -> bool>(&mut self, mut pred: P) {
+        self.drain_filter(|elem| !pred(elem)).for_each(drop);
+    }
+
+    /// Builds a set from elements that are already strictly ascending, in
+    /// O(1) expected time per element.
+    ///
+    /// The order is not checked. If the elements are out of order or
+    /// repeated, the resulting set will misbehave (though never unsafely).
+    pub fn from_sorted_unchecked<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut result = SkipSet::new();
+        result.refill(iter);
+        result
+    }
+
+    /// Replaces the contents of the set with `iter`, which must be strictly
+    /// ascending, drawing the new towers from the set's own generator.
+    fn refill<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        self.nodes.clear();
+        self.free.clear();
+        self.head.clear();
+        self.len = 0;
+
+        let mut tails: [Pred; MAX_HEIGHT] = [None; MAX_HEIGHT];
+
+        for elem in iter {
+            let height = self.rng.height();
+            self.grow(height);
+
+            let index = self.alloc(Node {
+                data: elem,
+                next: vec![None; height].into_boxed_slice(),
+            });
+
+            for (level, tail) in tails.iter_mut().enumerate().take(height) {
+                self.set_next(*tail, level, Some(index));
+                *tail = Some(index);
+            }
+
+            self.len += 1;
+        }
+    }
+
+    /// Empties the set, returning its elements in ascending order.
+    fn take_sorted(&mut self) -> Vec<T> {
+        let mut nodes = mem::take(&mut self.nodes);
+        let mut result = Vec::with_capacity(self.len);
+        let mut next = self.head.first().cloned().flatten();
+
+        while let Some(index) = next {
+            let node = nodes[index].take().expect("SkipSet: dangling link");
+            next = node.next[0];
+            result.push(node.data);
+        }
+
+        self.free.clear();
+        self.head.clear();
+        self.len = 0;
+        result
+    }
+
+    fn node(&self, index: usize) -> &Node<T> {
+        self.nodes[index].as_ref().expect("SkipSet: dangling link")
+    }
+
+    /// The node after `pred` on the bottom level, which the head may lack.
+    fn after(&self, pred: Pred) -> Link {
+        match pred {
+            None        => self.head.first().cloned().flatten(),
+            Some(index) => self.node(index).next[0],
+        }
+    }
+
+    /// Moves `preds`, the predecessors of some node on each level, past
+    /// that node. Does nothing at the end.
+    fn step(&self, preds: &mut [Pred; MAX_HEIGHT]) {
+        if let Some(index) = self.after(preds[0]) {
+            let height = self.node(index).next.len();
+
+            for pred in &mut preds[.. height] {
+                *pred = Some(index);
+            }
+        }
+    }
+
+    fn next(&self, pred: Pred, level: usize) -> Link {
+        match pred {
+            None        => self.head[level],
+            Some(index) => self.node(index).next[level],
+        }
+    }
+
+    fn set_next(&mut self, pred: Pred, level: usize, link: Link) {
+        match pred {
+            None        => self.head[level] = link,
+            Some(index) => {
+                self.nodes[index].as_mut().expect("SkipSet: dangling link")
+                    .next[level] = link
+            }
+        }
+    }
+
+    /// Makes sure the head has at least `height` levels.
+    fn grow(&mut self, height: usize) {
+        if self.head.len() < height {
+            self.head.resize(height, None);
+        }
+    }
+
+    /// Stores `node` in a free slot, returning its index.
+    fn alloc(&mut self, node: Node<T>) -> usize {
+        if let Some(index) = self.free.pop() {
+            self.nodes[index] = Some(node);
+            index
+        } else {
+            self.nodes.push(Some(node));
+            self.nodes.len() - 1
+        }
+    }
+
+    /// Unlinks the node at `index`, whose predecessors on each of its
+    /// levels are given by `preds`, and returns its element.
+    fn unlink(&mut self, preds: &[Pred], index: usize) -> T {
+        let node = self.nodes[index].take().expect("SkipSet: dangling link");
+
+        for (level, link) in node.next.iter().enumerate() {
+            self.set_next(preds[level], level, *link);
+        }
+
+        self.free.push(index);
+        self.len -= 1;
+
+        while let Some(None) = self.head.last() {
+            self.head.pop();
+        }
+
+        if self.len == 0 {
+            self.nodes.clear();
+            self.free.clear();
+        }
+
+        node.data
+    }
+}
+
+impl<T> Default for SkipSet<T> {
+    fn default() -> Self {
+        SkipSet::new()
+    }
+}
+
+impl<T: Ord> SkipSet<T> {
+    /// Finds, on every level, the last place before any element not less
+    /// than `element`.
+    fn search(&self, element: &T) -> [Pred; MAX_HEIGHT] {
+        let mut preds = [None; MAX_HEIGHT];
+        self.advance(&mut preds, element);
+        preds
+    }
+
+    /// Moves each of `preds` forward along its level to the last place
+    /// before any element not less than `element`, never backward.
+    fn advance(&self, preds: &mut [Pred; MAX_HEIGHT], element: &T) {
+        let mut pred = None;
+
+        for level in (0 .. self.head.len()).rev() {
+            // Resume from whichever of the place reached on the level above
+            // and the place already held on this level is further along.
+            if let Some(held) = preds[level] {
+                if pred.is_none_or(|index| self.node(index).data < self.node(held).data) {
+                    pred = Some(held);
+                }
+            }
+
+            while let Some(index) = self.next(pred, level) {
+                if self.node(index).data < *element {
+                    pred = Some(index);
+                } else {
+                    break;
+                }
+            }
+
+            preds[level] = pred;
+        }
+    }
+
+    /// Returns the index of the node holding `element`, given the result
+    /// of `search`.
+    fn found(&self, preds: &[Pred], element: &T) -> Option<usize> {
+        if self.head.is_empty() {
+            return None;
+        }
+
+        self.next(preds[0], 0)
+            .filter(|&index| self.node(index).data == *element)
+    }
+
+    /// Checks whether the given set contains the given element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = SkipSet::from_iter(vec![3, 5, 4]);
+    ///
+    /// assert!(!set.contains(&2));
+    /// assert!( set.contains(&3));
+    /// assert!( set.contains(&4));
+    /// assert!( set.contains(&5));
+    /// assert!(!set.contains(&6));
+    /// ```
+    pub fn contains(&self, element: &T) -> bool {
+        let mut pred = None;
+
+        for level in (0 .. self.head.len()).rev() {
+            while let Some(index) = self.next(pred, level) {
+                match element.cmp(&self.node(index).data) {
+                    Less    => break,
+                    Equal   => return true,
+                    Greater => pred = Some(index),
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Builds a set from elements that should be strictly ascending, in O(1)
+    /// expected time per element.
+    ///
+    /// Returns `Err` of the first element that is not greater than its
+    /// predecessor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// assert!( SkipSet::try_from_sorted(vec![1, 2, 4]).is_ok() );
+    /// assert_eq!( Err(2), SkipSet::try_from_sorted(vec![1, 3, 2]) );
+    /// assert_eq!( Err(3), SkipSet::try_from_sorted(vec![1, 3, 3]) );
+    /// ```
+    pub fn try_from_sorted<I: IntoIterator<Item=T>>(iter: I) -> Result<Self, T> {
+        let mut elems: Vec<T> = Vec::new();
+
+        for elem in iter {
+            if elems.last().is_some_and(|last| *last >= elem) {
+                return Err(elem);
+            }
+
+            elems.push(elem);
+        }
+
+        Ok(SkipSet::from_sorted_unchecked(elems))
+    }
+
+    /// Adds the element to the set.
+    ///
+    /// Returns `true` if the set did not previously contain the
+    /// element, and `false` if it did.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// let mut set = SkipSet::new();
+    ///
+    /// assert!( set.insert(3));
+    /// assert!( set.insert(5));
+    /// assert!(!set.insert(3));
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn insert(&mut self, element: T) -> bool {
+        let preds = self.search(&element);
+
+        if self.found(&preds, &element).is_some() {
+            return false;
+        }
+
+        self.link(&preds, element);
+        true
+    }
+
+    /// Adds the element to the set if absent, or replaces it if
+    /// present.
+    ///
+    /// Returns `Some` of the old element if it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// let mut set = SkipSet::new();
+    ///
+    /// assert_eq!(None, set.replace(5));
+    /// assert_eq!(Some(5), set.replace(5));
+    /// ```
+    pub fn replace(&mut self, element: T) -> Option<T> {
+        let preds = self.search(&element);
+
+        if let Some(index) = self.found(&preds, &element) {
+            let node = self.nodes[index].as_mut().expect("SkipSet: dangling link");
+            return Some(mem::replace(&mut node.data, element));
+        }
+
+        self.link(&preds, element);
+        None
+    }
+
+    /// Removes the given element from the set.
+    ///
+    /// Returns `Some(data)` where `data` was the element, if removed,
+    /// or `None` if the element didn’t exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// let mut set = SkipSet::new();
+    ///
+    /// assert_eq!(true,    set.insert(5));
+    /// assert_eq!(Some(5), set.remove(&5));
+    /// assert_eq!(None,    set.remove(&5));
+    /// ```
+    pub fn remove(&mut self, element: &T) -> Option<T> {
+        let preds = self.search(element);
+        let index = self.found(&preds, element)?;
+        Some(self.unlink(&preds, index))
+    }
+
+    /// Links a new node for `element` in after `preds`.
+    fn link(&mut self, preds: &[Pred], element: T) {
+        let height = self.rng.height();
+        self.grow(height);
+
+        let next = (0 .. height)
+            .map(|level| self.next(preds[level], level))
+            .collect();
+        let index = self.alloc(Node { data: element, next });
+
+        for (level, pred) in preds.iter().enumerate().take(height) {
+            self.set_next(*pred, level, Some(index));
+        }
+
+        self.len += 1;
+    }
+
+    /// Inserts each element of `iter`, which must be ascending, unless an
+    /// equal one is already present, searching forward from the previous
+    /// element rather than from the head.
+    fn insert_ascending<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let mut preds = [None; MAX_HEIGHT];
+
+        for elem in iter {
+            self.advance(&mut preds, &elem);
+
+            if self.found(&preds, &elem).is_none() {
+                self.link(&preds, elem);
+            }
+        }
+    }
+
+    /// Returns whether two sets are disjoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 2]);
+    /// let set2 = SkipSet::from_iter(vec![3, 4]);
+    /// let set3 = SkipSet::from_iter(vec![1, 3]);
+    ///
+    /// assert!( set1.is_disjoint(&set2));
+    /// assert!(!set1.is_disjoint(&set3));
+    /// ```
+    pub fn is_disjoint(&self, other: &SkipSet<T>) -> bool {
+        let mut i = self.iter().peekable();
+        let mut j = other.iter().peekable();
+
+        while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
+            match a.cmp(b) {
+                Less    => { i.next(); }
+                Greater => { j.next(); }
+                Equal   => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether `self` is a subset of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![2]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3]);
+    ///
+    /// assert!( set1.is_subset(&set2));
+    /// assert!(!set2.is_subset(&set1));
+    /// ```
+    pub fn is_subset(&self, other: &SkipSet<T>) -> bool {
+        self.len <= other.len && self.iter().all(|elem| other.contains(elem))
+    }
+
+    /// Returns whether `self` is a superset of `other`.
+    pub fn is_superset(&self, other: &SkipSet<T>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns a lazy iterator over the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_intersection(&set2).collect();
+    /// assert_eq!( result, &[&1, &3] );
+    /// ```
+    pub fn iter_intersection<'a>(&'a self, other: &'a SkipSet<T>) -> Intersection<'a, T> {
+        merge::Intersection::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_union(&set2).collect();
+    /// assert_eq!( result, &[&1, &2, &3, &4, &5, &7] );
+    /// ```
+    pub fn iter_union<'a>(&'a self, other: &'a SkipSet<T>) -> Union<'a, T> {
+        merge::Union::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_difference(&set2).collect();
+    /// assert_eq!( result, &[&5, &7] );
+    /// ```
+    pub fn iter_difference<'a>(&'a self, other: &'a SkipSet<T>) -> Difference<'a, T> {
+        merge::Difference::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_symmetric_difference(&set2).collect();
+    /// assert_eq!( result, &[&2, &4, &5, &7] );
+    /// ```
+    pub fn iter_symmetric_difference<'a>(&'a self, other: &'a SkipSet<T>)
+        -> SymmetricDifference<'a, T>
+    {
+        merge::SymmetricDifference::new(self.iter(), other.iter())
+    }
+
+    /// Modifies `self` to contain the union of `self` and `other`.
+    ///
+    /// Where both sets hold equal elements, `self`'s is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SkipSet::from_iter(vec![1, 3, 5]);
+    /// set.union_with(SkipSet::from_iter(vec![2, 3, 4]));
+    ///
+    /// assert_eq!( set, SkipSet::from_iter(1 .. 6) );
+    /// ```
+    pub fn union_with(&mut self, mut other: SkipSet<T>) {
+        self.merge_owned(other.take_sorted(), Tie::Mine);
+    }
+
+    /// Moves all elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// Where both sets hold equal elements, `other`'s is kept.
+    pub fn append(&mut self, other: &mut SkipSet<T>) {
+        self.merge_owned(other.take_sorted(), Tie::Theirs);
+    }
+
+    /// Modifies `self` to contain the symmetric difference of `self` and
+    /// `other`.
+    pub fn symmetric_difference_with(&mut self, mut other: SkipSet<T>) {
+        self.merge_owned(other.take_sorted(), Tie::Neither);
+    }
+
+    /// Modifies `self` to contain the intersection of `self` and `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// set.intersect_with(&SkipSet::from_iter(vec![1, 2, 3, 4]));
+    ///
+    /// assert_eq!( set, SkipSet::from_iter(vec![1, 3]) );
+    /// ```
+    pub fn intersect_with(&mut self, other: &SkipSet<T>) {
+        let mut rest = other.iter().peekable();
+        self.retain(|elem| skip_to(&mut rest, elem));
+    }
+
+    /// Removes from `self` every element of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// set.subtract(&SkipSet::from_iter(vec![1, 2, 3, 4]));
+    ///
+    /// assert_eq!( set, SkipSet::from_iter(vec![5, 7]) );
+    /// ```
+    pub fn subtract(&mut self, other: &SkipSet<T>) {
+        let mut rest = other.iter().peekable();
+        self.retain(|elem| !skip_to(&mut rest, elem));
+    }
+
+    /// Splits the set in two at `element`, returning everything greater
+    /// than or equal to it and leaving the rest in `self`.
+    ///
+    /// Only the nodes that move are visited, after an O(log n) search.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SkipSet::from_iter(1 .. 6);
+    /// let high = set.split_off(&3);
+    ///
+    /// assert_eq!( set, SkipSet::from_iter(vec![1, 2]) );
+    /// assert_eq!( high, SkipSet::from_iter(vec![3, 4, 5]) );
+    /// ```
+    pub fn split_off(&mut self, element: &T) -> SkipSet<T> {
+        let preds = self.search(element);
+        let mut high = SkipSet::with_seed(self.rng.next_u64());
+
+        if self.head.is_empty() {
+            return high;
+        }
+
+        let mut moved = Vec::new();
+        let mut next = self.next(preds[0], 0);
+
+        while let Some(index) = next {
+            let node = self.nodes[index].take().expect("SkipSet: dangling link");
+            next = node.next[0];
+            self.free.push(index);
+            moved.push(node.data);
+        }
+
+        for (level, pred) in preds.iter().enumerate().take(self.head.len()) {
+            self.set_next(*pred, level, None);
+        }
+
+        while let Some(None) = self.head.last() {
+            self.head.pop();
+        }
+
+        self.len -= moved.len();
+
+        if self.len == 0 {
+            self.nodes.clear();
+            self.free.clear();
+        }
+
+        high.refill(moved);
+        high
+    }
+
+    // Merges `other` into `self`, keeping one, the other or neither of
+    // each pair of equal elements.
+    fn merge_owned(&mut self, other: Vec<T>, tie: Tie) {
+        let mine = self.take_sorted();
+        self.refill(merge::merge_owned(mine, other, tie));
+    }
+}
+
+/// Advances `rest` past every element less than `element`, and returns
+/// whether the next one equals it.
+fn skip_to<'a, T: Ord + 'a>(rest: &mut Peekable<Iter<'a, T>>, element: &T) -> bool {
+    while rest.next_if(|elem| *elem < element).is_some() {}
+    rest.peek() == Some(&element)
+}
+
+#[cfg(feature = "std")]
+impl<T: Encode> SkipSet<T> {
+    /// Writes the set to `writer` as a length followed by the elements in
+    /// ascending order, the same format as `Set::write_to`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = SkipSet::from_iter(vec![3u32, 1, 2]);
+    /// let mut buf = Vec::new();
+    /// set.write_to(&mut buf).unwrap();
+    ///
+    /// assert_eq!( set, SkipSet::read_from(buf.as_slice()).unwrap() );
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        codec::encode_len(self.len, &mut writer)?;
+
+        for elem in self {
+            elem.encode(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord + Decode> SkipSet<T> {
+    /// Reads a set written by `write_to`.
+    ///
+    /// Fails with `codec::Error::Unsorted` unless the elements are strictly
+    /// ascending.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, codec::Error> {
+        let len = codec::decode_len(&mut reader)?;
+        let mut elems: Vec<T> = Vec::new();
+
+        for _ in 0 .. len {
+            let elem = T::decode(&mut reader)?;
+
+            if elems.last().is_some_and(|last| *last >= elem) {
+                return Err(codec::Error::Unsorted);
+            }
+
+            elems.push(elem);
+        }
+
+        Ok(SkipSet::from_sorted_unchecked(elems))
+    }
+}
+
+impl<T: Ord + Clone> SkipSet<T> {
+    /// Returns the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SkipSet::from_iter(vec![1, 3]), set1.intersection(&set2));
+    /// ```
+    pub fn intersection(&self, other: &SkipSet<T>) -> Self {
+        self.merge(other, false, true, false)
+    }
+
+    /// Returns the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SkipSet::from_iter(vec![1, 2, 3, 4, 5, 7]), set1.union(&set2));
+    /// ```
+    pub fn union(&self, other: &SkipSet<T>) -> Self {
+        self.merge(other, true, true, true)
+    }
+
+    /// Returns the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SkipSet::from_iter(vec![5, 7]), set1.difference(&set2));
+    /// ```
+    pub fn difference(&self, other: &SkipSet<T>) -> Self {
+        self.merge(other, true, false, false)
+    }
+
+    /// Returns the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::skip_set::SkipSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SkipSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SkipSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SkipSet::from_iter(vec![2, 4, 5, 7]), set1.symmetric_difference(&set2));
+    /// ```
+    pub fn symmetric_difference(&self, other: &SkipSet<T>) -> Self {
+        self.merge(other, true, false, true)
+    }
+
+    /// Merges two sets in one pass, keeping the elements found only on the
+    /// left, in both, or only on the right, as requested.
+    fn merge(&self, other: &SkipSet<T>, left: bool, both: bool, right: bool) -> Self {
+        SkipSet::from_sorted_unchecked(
+            merge::merge_cloned(self.iter(), other.iter(), left, both, right))
+    }
+}
+
+impl<T: Ord + Clone> BitOr<&SkipSet<T>> for &SkipSet<T> {
+    type Output = SkipSet<T>;
+
+    fn bitor(self, other: &SkipSet<T>) -> SkipSet<T> {
+        self.union(other)
+    }
+}
+
+impl<T: Ord + Clone> BitAnd<&SkipSet<T>> for &SkipSet<T> {
+    type Output = SkipSet<T>;
+
+    fn bitand(self, other: &SkipSet<T>) -> SkipSet<T> {
+        self.intersection(other)
+    }
+}
+
+impl<T: Ord + Clone> Sub<&SkipSet<T>> for &SkipSet<T> {
+    type Output = SkipSet<T>;
+
+    fn sub(self, other: &SkipSet<T>) -> SkipSet<T> {
+        self.difference(other)
+    }
+}
+
+impl<T: Ord + Clone> BitXor<&SkipSet<T>> for &SkipSet<T> {
+    type Output = SkipSet<T>;
+
+    fn bitxor(self, other: &SkipSet<T>) -> SkipSet<T> {
+        self.symmetric_difference(other)
+    }
+}
+
+impl<T: Ord> BitOrAssign<SkipSet<T>> for SkipSet<T> {
+    fn bitor_assign(&mut self, other: SkipSet<T>) {
+        self.union_with(other);
+    }
+}
+
+/// Adds clones of the elements of `other` that are missing from `self`.
+impl<T: Ord + Clone> BitOrAssign<&SkipSet<T>> for SkipSet<T> {
+    fn bitor_assign(&mut self, other: &SkipSet<T>) {
+        self.insert_ascending(other.iter().cloned());
+    }
+}
+
+impl<T: Ord> BitAndAssign<&SkipSet<T>> for SkipSet<T> {
+    fn bitand_assign(&mut self, other: &SkipSet<T>) {
+        self.intersect_with(other);
+    }
+}
+
+impl<T: Ord> SubAssign<&SkipSet<T>> for SkipSet<T> {
+    fn sub_assign(&mut self, other: &SkipSet<T>) {
+        self.subtract(other);
+    }
+}
+
+impl<T: Ord> BitXorAssign<SkipSet<T>> for SkipSet<T> {
+    fn bitxor_assign(&mut self, other: SkipSet<T>) {
+        self.symmetric_difference_with(other);
+    }
+}
+
+/// Replaces the set with its symmetric difference with `other`, cloning the
+/// elements only `other` holds.
+impl<T: Ord + Clone> BitXorAssign<&SkipSet<T>> for SkipSet<T> {
+    fn bitxor_assign(&mut self, other: &SkipSet<T>) {
+        let mut preds = [None; MAX_HEIGHT];
+
+        for elem in other {
+            self.advance(&mut preds, elem);
+
+            match self.found(&preds, elem) {
+                Some(index) => { self.unlink(&preds, index); }
+                None        => self.link(&preds, elem.clone()),
+            }
+        }
+    }
+}
+
+/// A cursor over the elements of a `SkipSet`.
+///
+/// A cursor points either at an element or at the end of the set, and can
+/// only move forward.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::skip_set::SkipSet;
+/// use std::iter::FromIterator;
+///
+/// let set = SkipSet::from_iter(vec![2, 4, 6]);
+/// let mut cur = set.cursor_front();
+///
+/// assert_eq!( Some(&2), cur.peek() );
+/// assert!(!cur.seek(&3) );
+/// assert_eq!( Some(&4), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( Some(&6), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( None, cur.peek() );
+/// ```
+pub struct Cursor<'a, T: 'a> {
+    set:   &'a SkipSet<T>,
+    preds: [Pred; MAX_HEIGHT],
+}
+// Invariant: `preds[l]` is the last node before the cursor whose tower is
+// more than `l` tall, or `None` if there is no such node.
+
+impl<'a, T: 'a> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor { ..*self }
+    }
+}
+
+impl<'a, T: 'a> Cursor<'a, T> {
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&'a T> {
+        let set = self.set;
+        set.after(self.preds[0]).map(|index| &set.node(index).data)
+    }
+
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        self.set.step(&mut self.preds);
+    }
+}
+
+impl<'a, T: Ord + 'a> Cursor<'a, T> {
+    /// Moves forward to the first element not less than `element`, and
+    /// returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is.
+    pub fn seek(&mut self, element: &T) -> bool {
+        self.set.advance(&mut self.preds, element);
+        self.peek() == Some(element)
+    }
+}
+
+// Shows only where the cursor is, not the whole set it borrows.
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for Cursor<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("current", &self.peek())
+            .finish()
+    }
+}
+
+/// A cursor that can edit a `SkipSet` in place.
+///
+/// Like `Cursor`, it points either at an element or at the end of the set,
+/// and can only move forward. Its edits cannot break the ordering of the
+/// set.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::skip_set::SkipSet;
+/// use std::iter::FromIterator;
+///
+/// let mut set = SkipSet::from_iter(vec![1, 3, 5, 7]);
+///
+/// {
+///     let mut cur = set.cursor_front_mut();
+///
+///     assert_eq!( Ok(()), cur.insert(0) );
+///     cur.move_next();
+///     assert_eq!( Some(1), cur.remove_current() );
+///     assert_eq!( Err(4), cur.insert(4) );
+///
+///     assert!( cur.seek(&5) );
+///     assert_eq!( Ok(()), cur.insert(4) );
+///     assert_eq!( Some(&4), cur.peek() );
+/// }
+///
+/// assert_eq!( set, SkipSet::from_iter(vec![0, 3, 4, 5, 7]) );
+/// ```
+pub struct CursorMut<'a, T: 'a> {
+    set:   &'a mut SkipSet<T>,
+    preds: [Pred; MAX_HEIGHT],
+}
+// Invariant: as for `Cursor`.
+
+impl<'a, T: 'a> CursorMut<'a, T> {
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&T> {
+        self.set.after(self.preds[0]).map(|index| &self.set.node(index).data)
+    }
+
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        self.set.step(&mut self.preds);
+    }
+
+    /// Removes and returns the element under the cursor, leaving the cursor
+    /// at the following element.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let index = self.set.after(self.preds[0])?;
+        Some(self.set.unlink(&self.preds, index))
+    }
+}
+
+// Shows only where the cursor is, not the whole set it borrows.
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for CursorMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CursorMut")
+            .field("current", &self.peek())
+            .finish()
+    }
+}
+
+impl<'a, T: Ord + 'a> CursorMut<'a, T> {
+    /// Moves forward to the first element not less than `element`, and
+    /// returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is.
+    pub fn seek(&mut self, element: &T) -> bool {
+        self.set.advance(&mut self.preds, element);
+        self.peek() == Some(element)
+    }
+
+    /// Inserts `element` just before the cursor and moves the cursor onto
+    /// it.
+    ///
+    /// Returns `Err(element)`, leaving the set unchanged, unless `element`
+    /// is greater than the previous element and less than the current one.
+    pub fn insert(&mut self, element: T) -> Result<(), T> {
+        let after_prev = self.preds[0]
+            .is_none_or(|prev| self.set.node(prev).data < element);
+        let before_cur = self.peek().is_none_or(|cur| element < *cur);
+
+        if after_prev && before_cur {
+            self.set.link(&self.preds, element);
+            Ok(())
+        } else {
+            Err(element)
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SkipSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the set as `{a, b, c}`, or with `{:#}`, one element per line.
+impl<T: fmt::Display> fmt::Display for SkipSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        merge::fmt_set(self, f)
+    }
+}
+
+impl<T: Clone> Clone for SkipSet<T> {
+    fn clone(&self) -> Self {
+        SkipSet {
+            nodes: self.nodes.clone(),
+            free:  self.free.clone(),
+            head:  self.head.clone(),
+            len:   self.len,
+            rng:   self.rng.clone(),
+        }
+    }
+}
+
+impl<T: Ord> Ord for SkipSet<T> {
+    fn cmp(&self, other: &SkipSet<T>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Ord> PartialOrd for SkipSet<T> {
+    fn partial_cmp(&self, other: &SkipSet<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for SkipSet<T> {
+    fn eq(&self, other: &SkipSet<T>) -> bool {
+        self.len == other.len && self.cmp(other) == Equal
+    }
+}
+
+impl<T: Ord> Eq for SkipSet<T> {}
+
+impl<T: Hash> Hash for SkipSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+/// An immutable iterator over the elements of a `SkipSet`, in ascending
+/// order.
+pub struct Iter<'a, T: 'a> {
+    set:  &'a SkipSet<T>,
+    next: Link,
+    len:  usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.set.node(self.next?);
+        self.next = node.next[0];
+        self.len -= 1;
+        Some(&node.data)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> Ascending<'a, T> for Iter<'a, T> {}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(Iter { ..*self }).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SkipSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        Iter {
+            set:  self,
+            next: self.head.first().cloned().flatten(),
+            len:  self.len,
+        }
+    }
+}
+
+/// An iterator that consumes a `SkipSet` as it iterates, in ascending
+/// order.
+#[derive(Debug)]
+pub struct IntoIter<T>(SkipSet<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // The first node follows the head on every level of its tower.
+        let index = self.0.head.first().cloned().flatten()?;
+        Some(self.0.unlink(&[None; MAX_HEIGHT], index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.0.len
+    }
+}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SkipSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+/// Elements already in the set are kept in preference to equal ones from
+/// the iterator, and of equal elements from the iterator, the first.
+impl<T: Ord> Extend<T> for SkipSet<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let mut elems: Vec<T> = iter.into_iter().collect();
+        // Stable, so the first of equal elements is inserted first.
+        elems.sort();
+        self.insert_ascending(elems);
+    }
+}
+
+/// Of equal elements, the first is kept.
+impl<T: Ord> FromIterator<T> for SkipSet<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut elems: Vec<T> = iter.into_iter().collect();
+        elems.sort();
+        elems.dedup();
+        SkipSet::from_sorted_unchecked(elems)
+    }
+}
+
+/// An iterator that removes the elements of a `SkipSet` that satisfy a
+/// predicate. Created by `SkipSet::drain_filter`.
+pub struct DrainFilter<'a, T: 'a, P>
+    where P: FnMut(&T) -> bool
+{
+    set:    &'a mut SkipSet<T>,
+    // The last node kept on each level, so far.
+    preds:  [Pred; MAX_HEIGHT],
+    height: usize,
+    next:   Link,
+    pred:   P,
+    len:    usize,
+}
+
+impl<'a, T, P> Iterator for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(index) = self.next {
+            let node = self.set.node(index);
+            let height = node.next.len();
+            self.next = node.next[0];
+            self.len -= 1;
+
+            if (self.pred)(&node.data) {
+                return Some(self.set.unlink(&self.preds[.. self.height], index));
+            }
+
+            for pred in &mut self.preds[.. height] {
+                *pred = Some(index);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.len))
+    }
+}
+
+impl<'a, T, P> Drop for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<'a, T: fmt::Debug, P> fmt::Debug for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DrainFilter")
+            .field("set", &self.set)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// A lazy iterator over the intersection of two `SkipSet`s.
+///
+/// Created by [`SkipSet::iter_intersection`].
+pub type Intersection<'a, T> = merge::Intersection<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the union of two `SkipSet`s.
+///
+/// Created by [`SkipSet::iter_union`].
+pub type Union<'a, T> = merge::Union<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the difference of two `SkipSet`s.
+///
+/// Created by [`SkipSet::iter_difference`].
+pub type Difference<'a, T> = merge::Difference<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the symmetric difference of two `SkipSet`s.
+///
+/// Created by [`SkipSet::iter_symmetric_difference`].
+pub type SymmetricDifference<'a, T> = merge::SymmetricDifference<'a, T, Iter<'a, T>>;
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_skip_set {
+    use super::SkipSet;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl<T: Arbitrary + Ord> Arbitrary for SkipSet<T> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let mut result = SkipSet::with_seed(u64::arbitrary(g));
+            result.extend(Vec::<T>::arbitrary(g));
+            result
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            Box::new(Vec::from_iter(SkipSet::clone(self))
+                .shrink()
+                .map(FromIterator::from_iter))
+        }
+    }
+}
+
+#[cfg(any(test, feature = "serde"))]
+mod impl_serde_for_skip_set {
+    use super::SkipSet;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+    impl<T: Serialize> Serialize for SkipSet<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.len))?;
+
+            for elem in self {
+                seq.serialize_element(elem)?;
+            }
+
+            seq.end()
+        }
+    }
+
+    // `from_iter` restores the invariant, whatever the order of the input.
+    impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for SkipSet<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Vec::deserialize(deserializer).map(SkipSet::from_iter)
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::SkipSet;
+    use crate::testing::{cursor_agrees, cursor_mut_agrees, in_place_agrees, lazy_ops_agree};
+    #[cfg(feature = "std")]
+    use crate::list_set::Set;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use std::collections::BTreeSet;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(u8),
+        Replace(u8),
+        Remove(u8),
+        Contains(u8),
+        DrainFilter(u8),
+    }
+
+    use self::Op::*;
+
+    impl Arbitrary for Op {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let elem = u8::arbitrary(g) % 64;
+
+            match u8::arbitrary(g) % 9 {
+                0 ..= 2 => Insert(elem),
+                3       => Replace(elem),
+                4 ..= 5 => Remove(elem),
+                6 ..= 7 => Contains(elem),
+                _       => DrainFilter(elem % 8 + 2),
+            }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            match *self {
+                Insert(e)      => Box::new(e.shrink().map(Insert)),
+                Replace(e)     => Box::new(e.shrink().map(Replace)),
+                Remove(e)      => Box::new(e.shrink().map(Remove)),
+                Contains(e)    => Box::new(e.shrink().map(Contains)),
+                DrainFilter(m) => Box::new(m.shrink().filter(|&m| m > 0).map(DrainFilter)),
+            }
+        }
+    }
+
+    fn agrees_with_model(seed: u64, ops: &[Op]) -> bool {
+        let mut set   = SkipSet::with_seed(seed);
+        let mut model = BTreeSet::new();
+
+        for op in ops {
+            let agrees = match *op {
+                Insert(e)   => set.insert(e) == model.insert(e),
+                Replace(e)  => set.replace(e) == model.replace(e),
+                Remove(e)   => set.remove(&e) == model.take(&e),
+                Contains(e) => set.contains(&e) == model.contains(&e),
+                DrainFilter(m) => {
+                    let drained: Vec<u8> = set.drain_filter(|e| e % m == 0).collect();
+                    let expected: Vec<u8> = model.iter().cloned().filter(|e| e % m == 0).collect();
+                    model.retain(|e| e % m != 0);
+                    drained == expected
+                }
+            };
+
+            if !agrees || set.len() != model.len() || !set.iter().eq(model.iter()) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // Whether every level of `set` is ascending and a sublist of the level
+    // below, and the free list accounts for every empty slot.
+    fn well_formed(set: &SkipSet<usize>) -> bool {
+        let level = |l: usize| {
+            let mut result = Vec::new();
+            let mut next = set.head[l];
+
+            while let Some(index) = next {
+                let node = set.node(index);
+                if node.next.len() <= l { return None; }
+                result.push(index);
+                next = node.next[l];
+            }
+
+            Some(result)
+        };
+
+        if set.head.is_empty() {
+            return set.len == 0;
+        }
+
+        let bottom = match level(0) {
+            Some(bottom) => bottom,
+            None => return false,
+        };
+        let mut free = set.free.clone();
+        free.sort();
+        let empty: Vec<usize> = (0 .. set.nodes.len())
+            .filter(|&index| set.nodes[index].is_none())
+            .collect();
+
+        bottom.len() == set.len
+            && set.head.iter().all(Option::is_some)
+            && bottom.windows(2).all(|w| set.node(w[0]).data < set.node(w[1]).data)
+            && bottom.iter().all(|&index| set.node(index).next.len() <= set.head.len())
+            && (1 .. set.head.len()).all(|l| level(l).is_some_and(|upper| {
+                upper.iter().all(|index| set.node(*index).next.len() > l)
+                    && bottom.iter().filter(|index| set.node(**index).next.len() > l)
+                        .eq(upper.iter())
+            }))
+            && free == empty
+    }
+
+    #[test]
+    fn len_iota() {
+        let set: SkipSet<usize> = (0 .. 100_000).rev().collect();
+        assert_eq!(100_000, set.len());
+        assert!(set.contains(&99_999));
+    }
+
+    // Extending a set, or or-ing a small one into it, links in the new
+    // elements without redrawing the towers of the old ones.
+    #[test]
+    fn small_batches_keep_towers() {
+        let towers = |set: &SkipSet<usize>| -> Vec<(usize, usize)> {
+            let mut result = Vec::new();
+            let mut next = set.head[0];
+
+            while let Some(index) = next {
+                let node = set.node(index);
+                if node.data.is_multiple_of(2) { result.push((node.data, node.next.len())); }
+                next = node.next[0];
+            }
+
+            result
+        };
+
+        let mut set: SkipSet<usize> = (0 .. 1000).map(|elem| elem * 2).collect();
+        let before = towers(&set);
+
+        set.extend(vec![7, 3, 7]);
+        assert_eq!( before, towers(&set) );
+
+        set |= &vec![11, 13].into_iter().collect();
+        assert_eq!( before, towers(&set) );
+
+        set ^= &vec![13, 15].into_iter().collect();
+        assert_eq!( before, towers(&set) );
+        assert!( well_formed(&set) );
+        assert_eq!( 1004, set.len() );
+    }
+
+    quickcheck! {
+
+        fn prop_model(seed: u64, ops: Vec<Op>) -> bool {
+            agrees_with_model(seed, &ops)
+        }
+
+        fn prop_member(vec: Vec<usize>, elems: Vec<usize>) -> bool {
+            let set: SkipSet<usize> = vec.iter().cloned().collect();
+
+            elems.iter()
+                .all(|elem| vec.contains(elem) == set.contains(elem))
+        }
+
+        fn prop_intersection(s1: SkipSet<usize>, s2: SkipSet<usize>) -> bool {
+            let s3 = s1.intersection(&s2);
+
+            s1.iter().all(|elem| s3.contains(elem) == s2.contains(elem))
+                && s3.iter().all(|elem| s1.contains(elem) && s2.contains(elem))
+        }
+
+        fn prop_union(s1: SkipSet<usize>, s2: SkipSet<usize>) -> bool {
+            let s3 = s1.union(&s2);
+
+            s1.iter().all(|elem| s3.contains(elem))
+                && s2.iter().all(|elem| s3.contains(elem))
+                && s3.iter().all(|elem| s1.contains(elem) || s2.contains(elem))
+        }
+
+        fn prop_differences(s1: SkipSet<usize>, s2: SkipSet<usize>) -> bool {
+            let d = s1.difference(&s2);
+            let sd = s1.symmetric_difference(&s2);
+
+            s1.iter().all(|elem| d.contains(elem) != s2.contains(elem))
+                && sd == d.union(&s2.difference(&s1))
+        }
+
+        fn prop_into_iter(s1: SkipSet<usize>) -> bool {
+            let expected: Vec<usize> = s1.iter().cloned().collect();
+            s1.into_iter().eq(expected)
+        }
+
+        fn prop_lazy_ops(s1: SkipSet<usize>, s2: SkipSet<usize>) -> bool {
+            lazy_ops_agree!(s1, s2)
+        }
+
+        // Each in-place operation and assignment operator agrees with the
+        // allocating one, and leaves the towers well formed.
+        fn prop_in_place(s1: SkipSet<usize>, s2: SkipSet<usize>) -> bool {
+            in_place_agrees!(s1, s2, well_formed)
+        }
+
+        fn prop_split_off(s1: SkipSet<usize>, elem: usize) -> bool {
+            // Aim the split inside the set more often than not.
+            let elem = s1.iter().nth(elem % (s1.len() + 1)).cloned().unwrap_or(elem);
+            let mut low = s1.clone();
+            let high = low.split_off(&elem);
+
+            well_formed(&low) && well_formed(&high)
+                && low.iter().all(|a| *a < elem) && high.iter().all(|b| *b >= elem)
+                && low.union(&high) == s1
+        }
+
+        fn prop_try_from_sorted(vec: Vec<usize>) -> bool {
+            let sorted = vec.windows(2).all(|w| w[0] < w[1]);
+
+            match SkipSet::try_from_sorted(vec.iter().cloned()) {
+                Ok(set) => sorted && well_formed(&set) && set.iter().eq(vec.iter()),
+                Err(_)  => !sorted,
+            }
+        }
+
+        fn prop_cursor(s1: SkipSet<usize>, elems: Vec<usize>) -> bool {
+            let vec: Vec<usize> = s1.iter().cloned().collect();
+            cursor_agrees!(s1.cursor_front(), &vec, elems)
+        }
+
+        fn prop_cursor_mut(s1: SkipSet<usize>, ops: Vec<(u8, usize)>) -> bool {
+            let mut set = s1.clone();
+            let mut vec: Vec<usize> = s1.iter().cloned().collect();
+
+            cursor_mut_agrees!(set.cursor_front_mut(), &mut vec, ops)
+                && well_formed(&set) && set.len() == vec.len() && set.iter().eq(vec.iter())
+        }
+
+        #[cfg(feature = "std")]
+        fn prop_write_read(s1: SkipSet<usize>) -> bool {
+            let mut buf = Vec::new();
+            s1.write_to(&mut buf).unwrap();
+
+            Set::<usize>::read_from(buf.as_slice()).unwrap().iter().eq(s1.iter())
+                && SkipSet::read_from(buf.as_slice()).unwrap() == s1
+        }
+
+        fn prop_serde(s1: SkipSet<usize>) -> bool {
+            let json = serde_json::to_string(&s1).unwrap();
+            serde_json::from_str::<SkipSet<usize>>(&json).unwrap() == s1
+        }
+
+    }
+}
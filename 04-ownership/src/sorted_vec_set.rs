@@ -0,0 +1,1384 @@
+//! Sets, represented as sorted vectors.
+//!
+//! A `SortedVecSet` offers the same operations as a `list_set::Set`, but
+//! keeps its elements contiguous. Lookups are binary searches, and
+//! iteration is as fast as iterating a slice, though inserting or removing
+//! an element shifts everything after it.
+//!
+//! The set operations merge the two sorted vectors. When one side is much
+//! smaller than the other, they gallop: rather than stepping through the
+//! larger side one element at a time, they search ahead in exponentially
+//! growing strides, so that intersecting `m` elements with `n` takes
+//! O(m log(n/m)) comparisons rather than O(m + n). The lazy
+//! `iter_intersection` and `iter_difference` gallop the same way.
+//!
+//! Cursors seek by galloping too, but `CursorMut` edits shift the elements
+//! after the cursor, just as `insert` and `remove` do.
+
+#[cfg(feature = "std")]
+use crate::codec::{self, Decode, Encode};
+use crate::list_set::Set;
+use crate::merge::{self, Ascending, Tie};
+use alloc::vec::{self, Vec};
+use core::cmp::Ordering::{Less, Equal, Greater};
+use core::fmt;
+use core::iter::{Extend, FromIterator, FusedIterator};
+use core::mem;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+use core::slice;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+/// A set of elements of type `T`.
+///
+/// # Example
+///
+/// ```
+/// use ownership::sorted_vec_set::SortedVecSet;
+///
+/// let mut set = SortedVecSet::new();
+///
+/// set.insert("a");
+/// set.insert("b");
+///
+/// if set.contains(&"a") {
+///     set.insert("c");
+/// }
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortedVecSet<T> {
+    elems: Vec<T>,
+}
+// Invariant: `elems` is strictly ascending according to <T as Ord>.
+
+impl<T> SortedVecSet<T> {
+    /// Creates a new, empty set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// let mut set = SortedVecSet::new();
+    /// set.insert("hello");
+    /// ```
+    pub fn new() -> Self {
+        SortedVecSet { elems: Vec::new() }
+    }
+
+    /// Returns whether a set is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// let mut set = SortedVecSet::new();
+    /// assert!(set.is_empty());
+    ///
+    /// set.insert(5);
+    /// assert!(!set.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Returns the number of elements in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// let mut set = SortedVecSet::new();
+    /// assert_eq!(0, set.len());
+    ///
+    /// set.insert(5);
+    /// set.insert(6);
+    /// set.insert(5);
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    /// Returns a borrowing iterator over the elements of the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = SortedVecSet::from_iter(vec![5, 1, 3]);
+    /// let result: Vec<_> = set.iter().collect();
+    ///
+    /// assert_eq!( result, &[&1, &3, &5] );
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+
+    /// Returns the elements of the set as an ascending slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.elems
+    }
+
+    /// Builds a set from elements that are already strictly ascending, in
+    /// O(1) amortized time per element.
+    ///
+    /// The order is not checked. If the elements are out of order or
+    /// repeated, the resulting set will misbehave (though never unsafely).
+    pub fn from_sorted_unchecked<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        SortedVecSet { elems: iter.into_iter().collect() }
+    }
+
+    /// Returns a cursor at the first element of the set.
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor { rest: &self.elems }
+    }
+
+    /// Returns a cursor at the first element of the set that can edit the
+    /// set in place.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            elems: &mut self.elems,
+            index: 0,
+        }
+    }
+
+    /// Keeps only the elements that satisfy a predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SortedVecSet::from_iter(1 .. 8);
+    /// set.retain(|elem| elem % 3 == 0);
+    ///
+    /// assert_eq!( set.as_slice(), &[3, 6] );
+    /// ```
+    pub fn retain<P: FnMut(&T) -> bool>(&mut self, pred: P) {
+        self.elems.retain(pred);
+    }
+
+    /// Returns an iterator that removes and returns elements satisfying a
+    /// predicate, leaving the rest in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SortedVecSet::from_iter(1 .. 8);
+    /// let evens: Vec<_> = set.drain_filter(|elem| elem % 2 == 0).collect();
+    ///
+    /// assert_eq!( evens, &[2, 4, 6] );
+    /// assert_eq!( set.as_slice(), &[1, 3, 5, 7] );
+    /// ```
+    pub fn drain_filter<P: FnMut(&T) -> bool>(&mut self, pred: P) -> DrainFilter<'_, T, P> {
+        let rest = mem::take(&mut self.elems).into_iter();
+        self.elems.reserve(rest.len());
+
+        DrainFilter {
+            kept: &mut self.elems,
+            rest,
+            pred,
+        }
+    }
+}
+
+impl<T: Ord> SortedVecSet<T> {
+    /// Checks whether the given set contains the given element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = SortedVecSet::from_iter(vec![3, 5, 4]);
+    ///
+    /// assert!(!set.contains(&2));
+    /// assert!( set.contains(&3));
+    /// assert!( set.contains(&4));
+    /// assert!( set.contains(&5));
+    /// assert!(!set.contains(&6));
+    /// ```
+    pub fn contains(&self, element: &T) -> bool {
+        self.elems.binary_search(element).is_ok()
+    }
+
+    /// Builds a set from elements that should be strictly ascending, in O(1)
+    /// amortized time per element.
+    ///
+    /// Returns `Err` of the first element that is not greater than its
+    /// predecessor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// assert!( SortedVecSet::try_from_sorted(vec![1, 2, 4]).is_ok() );
+    /// assert_eq!( Err(2), SortedVecSet::try_from_sorted(vec![1, 3, 2]) );
+    /// assert_eq!( Err(3), SortedVecSet::try_from_sorted(vec![1, 3, 3]) );
+    /// ```
+    pub fn try_from_sorted<I: IntoIterator<Item=T>>(iter: I) -> Result<Self, T> {
+        let mut result = SortedVecSet::new();
+
+        for elem in iter {
+            result.push(elem)?;
+        }
+
+        Ok(result)
+    }
+
+    // Adds `element` at the end, if it belongs there.
+    fn push(&mut self, element: T) -> Result<(), T> {
+        if self.elems.last().is_some_and(|last| *last >= element) {
+            return Err(element);
+        }
+
+        self.elems.push(element);
+        Ok(())
+    }
+
+    /// Adds the element to the set.
+    ///
+    /// Returns `true` if the set did not previously contain the
+    /// element, and `false` if it did.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// let mut set = SortedVecSet::new();
+    ///
+    /// assert!( set.insert(3));
+    /// assert!( set.insert(5));
+    /// assert!(!set.insert(3));
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn insert(&mut self, element: T) -> bool {
+        match self.elems.binary_search(&element) {
+            Ok(_)      => false,
+            Err(index) => {
+                self.elems.insert(index, element);
+                true
+            }
+        }
+    }
+
+    /// Adds the element to the set if absent, or replaces it if
+    /// present.
+    ///
+    /// Returns `Some` of the old element if it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// let mut set = SortedVecSet::new();
+    ///
+    /// assert_eq!(None, set.replace(5));
+    /// assert_eq!(Some(5), set.replace(5));
+    /// ```
+    pub fn replace(&mut self, element: T) -> Option<T> {
+        match self.elems.binary_search(&element) {
+            Ok(index)  => Some(mem::replace(&mut self.elems[index], element)),
+            Err(index) => {
+                self.elems.insert(index, element);
+                None
+            }
+        }
+    }
+
+    /// Removes the given element from the set.
+    ///
+    /// Returns `Some(data)` where `data` was the element, if removed,
+    /// or `None` if the element didn’t exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// let mut set = SortedVecSet::new();
+    ///
+    /// assert_eq!(true,    set.insert(5));
+    /// assert_eq!(Some(5), set.remove(&5));
+    /// assert_eq!(None,    set.remove(&5));
+    /// ```
+    pub fn remove(&mut self, element: &T) -> Option<T> {
+        let index = self.elems.binary_search(element).ok()?;
+        Some(self.elems.remove(index))
+    }
+
+    /// Returns whether two sets are disjoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 2]);
+    /// let set2 = SortedVecSet::from_iter(vec![3, 4]);
+    /// let set3 = SortedVecSet::from_iter(vec![1, 3]);
+    ///
+    /// assert!( set1.is_disjoint(&set2));
+    /// assert!(!set1.is_disjoint(&set3));
+    /// ```
+    pub fn is_disjoint(&self, other: &SortedVecSet<T>) -> bool {
+        let (mut a, mut b) = (self.as_slice(), other.as_slice());
+        let skip = skipper(a.len(), b.len());
+
+        while let (Some(x), Some(y)) = (a.first(), b.first()) {
+            match x.cmp(y) {
+                Less    => a = &a[skip(a, y) ..],
+                Greater => b = &b[skip(b, x) ..],
+                Equal   => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether `self` is a subset of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![2]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3]);
+    ///
+    /// assert!( set1.is_subset(&set2));
+    /// assert!(!set2.is_subset(&set1));
+    /// ```
+    pub fn is_subset(&self, other: &SortedVecSet<T>) -> bool {
+        if self.len() > other.len() {
+            return false;
+        }
+
+        let (mut a, mut b) = (self.as_slice(), other.as_slice());
+        let skip = skipper(a.len(), b.len());
+
+        while let (Some(x), Some(y)) = (a.first(), b.first()) {
+            match x.cmp(y) {
+                Less    => return false,
+                Greater => b = &b[skip(b, x) ..],
+                Equal   => {
+                    a = &a[1 ..];
+                    b = &b[1 ..];
+                }
+            }
+        }
+
+        a.is_empty()
+    }
+
+    /// Returns whether `self` is a superset of `other`.
+    pub fn is_superset(&self, other: &SortedVecSet<T>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns a lazy iterator over the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_intersection(&set2).collect();
+    /// assert_eq!( result, &[&1, &3] );
+    /// ```
+    pub fn iter_intersection<'a>(&'a self, other: &'a SortedVecSet<T>) -> Intersection<'a, T> {
+        merge::Intersection::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_union(&set2).collect();
+    /// assert_eq!( result, &[&1, &2, &3, &4, &5, &7] );
+    /// ```
+    pub fn iter_union<'a>(&'a self, other: &'a SortedVecSet<T>) -> Union<'a, T> {
+        merge::Union::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_difference(&set2).collect();
+    /// assert_eq!( result, &[&5, &7] );
+    /// ```
+    pub fn iter_difference<'a>(&'a self, other: &'a SortedVecSet<T>) -> Difference<'a, T> {
+        merge::Difference::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_symmetric_difference(&set2).collect();
+    /// assert_eq!( result, &[&2, &4, &5, &7] );
+    /// ```
+    pub fn iter_symmetric_difference<'a>(&'a self, other: &'a SortedVecSet<T>)
+        -> SymmetricDifference<'a, T>
+    {
+        merge::SymmetricDifference::new(self.iter(), other.iter())
+    }
+
+    /// Modifies `self` to contain the union of `self` and `other`.
+    ///
+    /// Where both sets hold equal elements, `self`'s is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SortedVecSet::from_iter(vec![1, 3, 5]);
+    /// set.union_with(SortedVecSet::from_iter(vec![2, 3, 4]));
+    ///
+    /// assert_eq!( set.as_slice(), &[1, 2, 3, 4, 5] );
+    /// ```
+    pub fn union_with(&mut self, other: SortedVecSet<T>) {
+        self.merge_owned(other.elems, Tie::Mine);
+    }
+
+    /// Moves all elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// Where both sets hold equal elements, `other`'s is kept.
+    pub fn append(&mut self, other: &mut SortedVecSet<T>) {
+        self.merge_owned(mem::take(&mut other.elems), Tie::Theirs);
+    }
+
+    /// Modifies `self` to contain the symmetric difference of `self` and
+    /// `other`.
+    pub fn symmetric_difference_with(&mut self, other: SortedVecSet<T>) {
+        self.merge_owned(other.elems, Tie::Neither);
+    }
+
+    /// Modifies `self` to contain the intersection of `self` and `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// set.intersect_with(&SortedVecSet::from_iter(vec![1, 2, 3, 4]));
+    ///
+    /// assert_eq!( set.as_slice(), &[1, 3] );
+    /// ```
+    pub fn intersect_with(&mut self, other: &SortedVecSet<T>) {
+        let mut rest = other.as_slice();
+
+        self.elems.retain(|elem| {
+            rest = &rest[gallop(rest, elem) ..];
+            rest.first() == Some(elem)
+        });
+    }
+
+    /// Removes from `self` every element of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// set.subtract(&SortedVecSet::from_iter(vec![1, 2, 3, 4]));
+    ///
+    /// assert_eq!( set.as_slice(), &[5, 7] );
+    /// ```
+    pub fn subtract(&mut self, other: &SortedVecSet<T>) {
+        let mut rest = other.as_slice();
+
+        self.elems.retain(|elem| {
+            rest = &rest[gallop(rest, elem) ..];
+            rest.first() != Some(elem)
+        });
+    }
+
+    /// Splits the set in two at `element`, returning everything greater
+    /// than or equal to it and leaving the rest in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = SortedVecSet::from_iter(1 .. 6);
+    /// let high = set.split_off(&3);
+    ///
+    /// assert_eq!( set.as_slice(), &[1, 2] );
+    /// assert_eq!( high.as_slice(), &[3, 4, 5] );
+    /// ```
+    pub fn split_off(&mut self, element: &T) -> SortedVecSet<T> {
+        let index = self.elems.partition_point(|elem| elem < element);
+        SortedVecSet { elems: self.elems.split_off(index) }
+    }
+
+    // Merges `other` into `self`, keeping one, the other or neither of
+    // each pair of equal elements.
+    fn merge_owned(&mut self, other: Vec<T>, tie: Tie) {
+        let mine = mem::take(&mut self.elems);
+        self.elems = merge::merge_owned(mine, other, tie);
+    }
+}
+
+/// Returns the number of leading elements of `slice` that are less than
+/// `bound`, by exponential then binary search.
+fn gallop<T: Ord>(slice: &[T], bound: &T) -> usize {
+    let mut hi = 1;
+
+    while hi <= slice.len() && slice[hi - 1] < *bound {
+        hi *= 2;
+    }
+
+    // Everything before `lo` is less than `bound`, and unless `hi` ran off
+    // the end, `slice[hi - 1]` is not.
+    let lo = hi / 2;
+    let hi = hi.min(slice.len());
+    lo + slice[lo .. hi].partition_point(|elem| elem < bound)
+}
+
+/// Returns the number of leading elements of `slice` that are less than
+/// `bound`, by linear search.
+fn step<T: Ord>(slice: &[T], bound: &T) -> usize {
+    slice.iter().take_while(|elem| *elem < bound).count()
+}
+
+/// Chooses how to skip ahead when merging sides of the given lengths.
+fn skipper<T: Ord>(m: usize, n: usize) -> fn(&[T], &T) -> usize {
+    if merge::lopsided(m, n) {
+        gallop
+    } else {
+        step
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Encode> SortedVecSet<T> {
+    /// Writes the set to `writer` as a length followed by the elements in
+    /// ascending order, the same format as `Set::write_to`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = SortedVecSet::from_iter(vec![3u32, 1, 2]);
+    /// let mut buf = Vec::new();
+    /// set.write_to(&mut buf).unwrap();
+    ///
+    /// assert_eq!( set, SortedVecSet::read_from(buf.as_slice()).unwrap() );
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        codec::encode_len(self.len(), &mut writer)?;
+
+        for elem in self {
+            elem.encode(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord + Decode> SortedVecSet<T> {
+    /// Reads a set written by `write_to`.
+    ///
+    /// Fails with `codec::Error::Unsorted` unless the elements are strictly
+    /// ascending.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, codec::Error> {
+        let len = codec::decode_len(&mut reader)?;
+        let mut result = SortedVecSet::new();
+
+        for _ in 0 .. len {
+            result.push(T::decode(&mut reader)?)
+                .map_err(|_| codec::Error::Unsorted)?;
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T: Ord + Clone> SortedVecSet<T> {
+    /// Returns the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SortedVecSet::from_iter(vec![1, 3]), set1.intersection(&set2));
+    /// ```
+    pub fn intersection(&self, other: &SortedVecSet<T>) -> Self {
+        self.merge(other, false, true, false)
+    }
+
+    /// Returns the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SortedVecSet::from_iter(vec![1, 2, 3, 4, 5, 7]), set1.union(&set2));
+    /// ```
+    pub fn union(&self, other: &SortedVecSet<T>) -> Self {
+        self.merge(other, true, true, true)
+    }
+
+    /// Returns the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SortedVecSet::from_iter(vec![5, 7]), set1.difference(&set2));
+    /// ```
+    pub fn difference(&self, other: &SortedVecSet<T>) -> Self {
+        self.merge(other, true, false, false)
+    }
+
+    /// Returns the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::sorted_vec_set::SortedVecSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = SortedVecSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(SortedVecSet::from_iter(vec![2, 4, 5, 7]),
+    ///            set1.symmetric_difference(&set2));
+    /// ```
+    pub fn symmetric_difference(&self, other: &SortedVecSet<T>) -> Self {
+        self.merge(other, true, false, true)
+    }
+
+    // Merges two sets, keeping the elements found only on the left, in
+    // both, or only on the right, as requested. Runs from one side that
+    // fall between elements of the other are skipped or copied whole.
+    fn merge(&self, other: &SortedVecSet<T>, left: bool, both: bool, right: bool) -> Self {
+        let (mut a, mut b) = (self.as_slice(), other.as_slice());
+        let skip = skipper(a.len(), b.len());
+        let mut result = Vec::new();
+
+        while let (Some(x), Some(y)) = (a.first(), b.first()) {
+            match x.cmp(y) {
+                Less => {
+                    let (run, rest) = a.split_at(skip(a, y));
+                    if left { result.extend_from_slice(run); }
+                    a = rest;
+                }
+                Greater => {
+                    let (run, rest) = b.split_at(skip(b, x));
+                    if right { result.extend_from_slice(run); }
+                    b = rest;
+                }
+                Equal => {
+                    if both { result.push(x.clone()); }
+                    a = &a[1 ..];
+                    b = &b[1 ..];
+                }
+            }
+        }
+
+        if left { result.extend_from_slice(a); }
+        if right { result.extend_from_slice(b); }
+
+        SortedVecSet { elems: result }
+    }
+}
+
+impl<T: Ord + Clone> BitOr<&SortedVecSet<T>> for &SortedVecSet<T> {
+    type Output = SortedVecSet<T>;
+
+    fn bitor(self, other: &SortedVecSet<T>) -> SortedVecSet<T> {
+        self.union(other)
+    }
+}
+
+impl<T: Ord + Clone> BitAnd<&SortedVecSet<T>> for &SortedVecSet<T> {
+    type Output = SortedVecSet<T>;
+
+    fn bitand(self, other: &SortedVecSet<T>) -> SortedVecSet<T> {
+        self.intersection(other)
+    }
+}
+
+impl<T: Ord + Clone> Sub<&SortedVecSet<T>> for &SortedVecSet<T> {
+    type Output = SortedVecSet<T>;
+
+    fn sub(self, other: &SortedVecSet<T>) -> SortedVecSet<T> {
+        self.difference(other)
+    }
+}
+
+impl<T: Ord + Clone> BitXor<&SortedVecSet<T>> for &SortedVecSet<T> {
+    type Output = SortedVecSet<T>;
+
+    fn bitxor(self, other: &SortedVecSet<T>) -> SortedVecSet<T> {
+        self.symmetric_difference(other)
+    }
+}
+
+impl<T: Ord> BitOrAssign<SortedVecSet<T>> for SortedVecSet<T> {
+    fn bitor_assign(&mut self, other: SortedVecSet<T>) {
+        self.union_with(other);
+    }
+}
+
+/// Adds clones of the elements of `other` that are missing from `self`.
+impl<T: Ord + Clone> BitOrAssign<&SortedVecSet<T>> for SortedVecSet<T> {
+    fn bitor_assign(&mut self, other: &SortedVecSet<T>) {
+        let missing = other.iter_difference(self).cloned().collect();
+        self.merge_owned(missing, Tie::Mine);
+    }
+}
+
+impl<T: Ord> BitAndAssign<&SortedVecSet<T>> for SortedVecSet<T> {
+    fn bitand_assign(&mut self, other: &SortedVecSet<T>) {
+        self.intersect_with(other);
+    }
+}
+
+impl<T: Ord> SubAssign<&SortedVecSet<T>> for SortedVecSet<T> {
+    fn sub_assign(&mut self, other: &SortedVecSet<T>) {
+        self.subtract(other);
+    }
+}
+
+impl<T: Ord> BitXorAssign<SortedVecSet<T>> for SortedVecSet<T> {
+    fn bitxor_assign(&mut self, other: SortedVecSet<T>) {
+        self.symmetric_difference_with(other);
+    }
+}
+
+/// Replaces the set with its symmetric difference with `other`, cloning the
+/// elements only `other` holds.
+impl<T: Ord + Clone> BitXorAssign<&SortedVecSet<T>> for SortedVecSet<T> {
+    fn bitxor_assign(&mut self, other: &SortedVecSet<T>) {
+        let missing = other.iter_difference(self).cloned().collect();
+        self.subtract(other);
+        self.merge_owned(missing, Tie::Mine);
+    }
+}
+
+/// A cursor over the elements of a `SortedVecSet`.
+///
+/// A cursor points either at an element or at the end of the set, and can
+/// only move forward.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::sorted_vec_set::SortedVecSet;
+/// use std::iter::FromIterator;
+///
+/// let set = SortedVecSet::from_iter(vec![2, 4, 6]);
+/// let mut cur = set.cursor_front();
+///
+/// assert_eq!( Some(&2), cur.peek() );
+/// assert!(!cur.seek(&3) );
+/// assert_eq!( Some(&4), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( Some(&6), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( None, cur.peek() );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cursor<'a, T: 'a> {
+    rest: &'a [T],
+}
+
+impl<'a, T: 'a> Cursor<'a, T> {
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&'a T> {
+        self.rest.first()
+    }
+
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        if let Some((_, rest)) = self.rest.split_first() {
+            self.rest = rest;
+        }
+    }
+}
+
+impl<'a, T: Ord + 'a> Cursor<'a, T> {
+    /// Moves forward, galloping, to the first element not less than
+    /// `element`, and returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is.
+    pub fn seek(&mut self, element: &T) -> bool {
+        self.rest = &self.rest[gallop(self.rest, element) ..];
+        self.peek() == Some(element)
+    }
+}
+
+/// A cursor that can edit a `SortedVecSet` in place.
+///
+/// Like `Cursor`, it points either at an element or at the end of the set,
+/// and can only move forward. Its edits cannot break the ordering of the
+/// set.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::sorted_vec_set::SortedVecSet;
+/// use std::iter::FromIterator;
+///
+/// let mut set = SortedVecSet::from_iter(vec![1, 3, 5, 7]);
+///
+/// {
+///     let mut cur = set.cursor_front_mut();
+///
+///     assert_eq!( Ok(()), cur.insert(0) );
+///     cur.move_next();
+///     assert_eq!( Some(1), cur.remove_current() );
+///     assert_eq!( Err(4), cur.insert(4) );
+///
+///     assert!( cur.seek(&5) );
+///     assert_eq!( Ok(()), cur.insert(4) );
+///     assert_eq!( Some(&4), cur.peek() );
+/// }
+///
+/// assert_eq!( set.as_slice(), &[0, 3, 4, 5, 7] );
+/// ```
+pub struct CursorMut<'a, T: 'a> {
+    elems: &'a mut Vec<T>,
+    index: usize,
+}
+// Invariant: `index` is at most `elems.len()`.
+
+impl<'a, T: 'a> CursorMut<'a, T> {
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&T> {
+        self.elems.get(self.index)
+    }
+
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        if self.index < self.elems.len() {
+            self.index += 1;
+        }
+    }
+
+    /// Removes and returns the element under the cursor, leaving the cursor
+    /// at the following element.
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.index < self.elems.len() {
+            Some(self.elems.remove(self.index))
+        } else {
+            None
+        }
+    }
+}
+
+// Shows only where the cursor is, not the whole vector it borrows.
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for CursorMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CursorMut")
+            .field("current", &self.peek())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl<'a, T: Ord + 'a> CursorMut<'a, T> {
+    /// Moves forward, galloping, to the first element not less than
+    /// `element`, and returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is.
+    pub fn seek(&mut self, element: &T) -> bool {
+        self.index += gallop(&self.elems[self.index ..], element);
+        self.peek() == Some(element)
+    }
+
+    /// Inserts `element` just before the cursor and moves the cursor onto
+    /// it.
+    ///
+    /// Returns `Err(element)`, leaving the set unchanged, unless `element`
+    /// is greater than the previous element and less than the current one.
+    pub fn insert(&mut self, element: T) -> Result<(), T> {
+        let after_prev = self.index.checked_sub(1)
+            .is_none_or(|prev| self.elems[prev] < element);
+        let before_cur = self.peek().is_none_or(|cur| element < *cur);
+
+        if after_prev && before_cur {
+            self.elems.insert(self.index, element);
+            Ok(())
+        } else {
+            Err(element)
+        }
+    }
+}
+
+/// A lazy iterator over the intersection of two `SortedVecSet`s.
+///
+/// Created by [`SortedVecSet::iter_intersection`].
+pub type Intersection<'a, T> = merge::Intersection<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the union of two `SortedVecSet`s.
+///
+/// Created by [`SortedVecSet::iter_union`].
+pub type Union<'a, T> = merge::Union<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the difference of two `SortedVecSet`s.
+///
+/// Created by [`SortedVecSet::iter_difference`].
+pub type Difference<'a, T> = merge::Difference<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the symmetric difference of two `SortedVecSet`s.
+///
+/// Created by [`SortedVecSet::iter_symmetric_difference`].
+pub type SymmetricDifference<'a, T> = merge::SymmetricDifference<'a, T, Iter<'a, T>>;
+
+impl<T: fmt::Debug> fmt::Debug for SortedVecSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the set as `{a, b, c}`, or with `{:#}`, one element per line.
+impl<T: fmt::Display> fmt::Display for SortedVecSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        merge::fmt_set(self, f)
+    }
+}
+
+/// Converts in O(n), reusing the elements in order.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::sorted_vec_set::SortedVecSet;
+/// use ownership::list_set::Set;
+/// use std::iter::FromIterator;
+///
+/// let set = Set::from_iter(vec![3, 1, 2]);
+/// let vec_set = SortedVecSet::from(set.clone());
+///
+/// assert_eq!( vec_set.as_slice(), &[1, 2, 3] );
+/// assert_eq!( Set::from(vec_set), set );
+/// ```
+impl<T> From<Set<T>> for SortedVecSet<T> {
+    fn from(set: Set<T>) -> Self {
+        SortedVecSet::from_sorted_unchecked(set)
+    }
+}
+
+/// Converts in O(n), reusing the elements in order.
+impl<T> From<SortedVecSet<T>> for Set<T> {
+    fn from(set: SortedVecSet<T>) -> Self {
+        Set::from_sorted_unchecked(set)
+    }
+}
+
+/// An immutable iterator over the elements of a `SortedVecSet`, in
+/// ascending order.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T: 'a>(slice::Iter<'a, T>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.0.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+// Gallops, so that lopsided lazy merges skip long runs in O(log n) time.
+impl<'a, T> Ascending<'a, T> for Iter<'a, T> {
+    fn seek(&mut self, bound: &T) -> Option<&'a T>
+        where T: Ord
+    {
+        let rest = self.0.as_slice();
+        self.0 = rest[gallop(rest, bound) ..].iter();
+        self.0.next()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedVecSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        Iter(self.elems.iter())
+    }
+}
+
+/// An iterator that consumes a `SortedVecSet` as it iterates, in ascending
+/// order.
+#[derive(Debug)]
+pub struct IntoIter<T>(vec::IntoIter<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SortedVecSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self.elems.into_iter())
+    }
+}
+
+impl<T: Ord> Extend<T> for SortedVecSet<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        self.union_with(SortedVecSet::from_iter(iter));
+    }
+}
+
+/// Of equal elements, the first is kept.
+impl<T: Ord> FromIterator<T> for SortedVecSet<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut elems: Vec<T> = iter.into_iter().collect();
+        // Stable, so `dedup` sees equal elements in their original order.
+        elems.sort();
+        elems.dedup();
+        SortedVecSet { elems }
+    }
+}
+
+/// An iterator that removes the elements of a `SortedVecSet` that satisfy
+/// a predicate. Created by `SortedVecSet::drain_filter`.
+pub struct DrainFilter<'a, T: 'a, P>
+    where P: FnMut(&T) -> bool
+{
+    // The set's own vector, holding the elements kept so far.
+    kept: &'a mut Vec<T>,
+    rest: vec::IntoIter<T>,
+    pred: P,
+}
+
+impl<'a, T, P> Iterator for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for elem in &mut self.rest {
+            if (self.pred)(&elem) {
+                return Some(elem);
+            }
+
+            self.kept.push(elem);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.rest.len()))
+    }
+}
+
+impl<'a, T, P> Drop for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<'a, T: fmt::Debug, P> fmt::Debug for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DrainFilter")
+            .field("kept", &self.kept)
+            .field("rest", &self.rest.as_slice())
+            .finish()
+    }
+}
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_sorted_vec_set {
+    use super::SortedVecSet;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl<T: Arbitrary + Ord> Arbitrary for SortedVecSet<T> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            FromIterator::from_iter(Vec::<T>::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            Box::new(self.elems
+                .shrink()
+                .map(FromIterator::from_iter))
+        }
+    }
+}
+
+#[cfg(any(test, feature = "serde"))]
+mod impl_serde_for_sorted_vec_set {
+    use super::SortedVecSet;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<T: Serialize> Serialize for SortedVecSet<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.elems.serialize(serializer)
+        }
+    }
+
+    // Sorting restores the invariant, whatever the order of the input.
+    impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for SortedVecSet<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Vec::deserialize(deserializer).map(SortedVecSet::from_iter)
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::SortedVecSet;
+    use crate::list_set::Set;
+    use crate::testing::{agrees, cursor_agrees, cursor_mut_agrees, in_place_agrees, lazy_ops_agree};
+    use quickcheck::quickcheck;
+    use std::collections::BTreeSet;
+
+    fn is_sorted(set: &SortedVecSet<usize>) -> bool {
+        set.as_slice().windows(2).all(|w| w[0] < w[1])
+    }
+
+    fn model(set: &SortedVecSet<usize>) -> BTreeSet<usize> {
+        set.iter().cloned().collect()
+    }
+
+    quickcheck! {
+
+        fn prop_member(vec: Vec<usize>, elems: Vec<usize>) -> bool {
+            let set: SortedVecSet<usize> = vec.iter().cloned().collect();
+
+            elems.iter()
+                .all(|elem| vec.contains(elem) == set.contains(elem))
+        }
+
+        fn prop_insert_remove(vec: Vec<usize>, elems: Vec<usize>) -> bool {
+            let mut set: SortedVecSet<usize> = vec.iter().cloned().collect();
+            let mut model: BTreeSet<usize> = vec.iter().cloned().collect();
+
+            elems.iter().all(|elem| {
+                let agrees = if elem % 2 == 0 {
+                    set.insert(*elem) == model.insert(*elem)
+                } else {
+                    set.remove(&(elem / 2)) == model.take(&(elem / 2))
+                };
+
+                agrees && is_sorted(&set) && set.iter().eq(model.iter())
+            })
+        }
+
+        fn prop_ops(s1: SortedVecSet<usize>, s2: SortedVecSet<usize>) -> bool {
+            let (b1, b2) = (model(&s1), model(&s2));
+
+            s1.intersection(&s2).iter().eq(b1.intersection(&b2))
+                && s1.union(&s2).iter().eq(b1.union(&b2))
+                && s1.difference(&s2).iter().eq(b1.difference(&b2))
+                && s1.symmetric_difference(&s2).iter().eq(b1.symmetric_difference(&b2))
+                && s1.is_disjoint(&s2) == b1.is_disjoint(&b2)
+                && s1.is_subset(&s2) == b1.is_subset(&b2)
+        }
+
+        // One side is far larger than the other, so the merges gallop.
+        fn prop_lopsided_ops(s1: SortedVecSet<usize>, stride: usize) -> bool {
+            let s1: SortedVecSet<usize> = s1.iter().map(|elem| elem % 2_000).collect();
+            let s2: SortedVecSet<usize> = (0 .. 2_000).step_by(stride % 5 + 1).collect();
+            let (b1, b2) = (model(&s1), model(&s2));
+
+            s1.intersection(&s2).iter().eq(b1.intersection(&b2))
+                && s2.intersection(&s1).iter().eq(b2.intersection(&b1))
+                && s1.union(&s2).iter().eq(b1.union(&b2))
+                && s1.difference(&s2).iter().eq(b1.difference(&b2))
+                && s2.difference(&s1).iter().eq(b2.difference(&b1))
+                && s1.symmetric_difference(&s2).iter().eq(b1.symmetric_difference(&b2))
+                && s1.is_disjoint(&s2) == b1.is_disjoint(&b2)
+                && s1.is_subset(&s2) == b1.is_subset(&b2)
+                && agrees(s1.iter_intersection(&s2), b1.intersection(&b2))
+                && agrees(s2.iter_intersection(&s1), b2.intersection(&b1))
+                && agrees(s1.iter_difference(&s2), b1.difference(&b2))
+                && agrees(s2.iter_difference(&s1), b2.difference(&b1))
+        }
+
+        fn prop_lazy_ops(s1: SortedVecSet<usize>, s2: SortedVecSet<usize>) -> bool {
+            lazy_ops_agree!(s1, s2)
+        }
+
+        fn prop_in_place(s1: SortedVecSet<usize>, s2: SortedVecSet<usize>) -> bool {
+            in_place_agrees!(s1, s2, is_sorted)
+        }
+
+        fn prop_cursor(s1: SortedVecSet<usize>, elems: Vec<usize>) -> bool {
+            cursor_agrees!(s1.cursor_front(), s1.as_slice(), elems)
+        }
+
+        fn prop_cursor_mut(s1: SortedVecSet<usize>, ops: Vec<(u8, usize)>) -> bool {
+            let mut set = s1.clone();
+            let mut vec = s1.as_slice().to_vec();
+
+            cursor_mut_agrees!(set.cursor_front_mut(), &mut vec, ops)
+                && is_sorted(&set) && set.as_slice() == vec.as_slice()
+        }
+
+        fn prop_split_off(s1: SortedVecSet<usize>, elem: usize) -> bool {
+            let mut s2 = s1.clone();
+            let s3 = s2.split_off(&elem);
+
+            s2.iter().all(|a| *a < elem) && s3.iter().all(|b| *b >= elem)
+                && s2.union(&s3) == s1
+        }
+
+        fn prop_drain_filter(s1: SortedVecSet<usize>, modulus: usize) -> bool {
+            let modulus = modulus + 1;
+            let mut s2 = s1.clone();
+            let drained: Vec<usize> = s2.drain_filter(|elem| elem % modulus == 0).collect();
+
+            drained.iter().all(|elem| elem % modulus == 0)
+                && s2.iter().all(|elem| elem % modulus != 0)
+                && s2.union(&drained.into_iter().collect()) == s1
+        }
+
+        fn prop_from_iter(vec: Vec<usize>) -> bool {
+            let set: SortedVecSet<usize> = vec.iter().cloned().collect();
+            let model: BTreeSet<usize> = vec.iter().cloned().collect();
+
+            set.len() == model.len() && set.iter().eq(model.iter())
+        }
+
+        fn prop_try_from_sorted(vec: Vec<usize>) -> bool {
+            let sorted = vec.windows(2).all(|w| w[0] < w[1]);
+
+            match SortedVecSet::try_from_sorted(vec.iter().cloned()) {
+                Ok(set) => sorted && set.iter().eq(vec.iter()),
+                Err(_)  => !sorted,
+            }
+        }
+
+        fn prop_convert(s1: Set<usize>) -> bool {
+            let s2 = SortedVecSet::from(s1.clone());
+
+            s2.iter().eq(s1.iter()) && Set::from(s2) == s1
+        }
+
+        #[cfg(feature = "std")]
+        fn prop_write_read(s1: SortedVecSet<usize>) -> bool {
+            let mut buf = Vec::new();
+            s1.write_to(&mut buf).unwrap();
+
+            Set::<usize>::read_from(buf.as_slice()).unwrap().iter().eq(s1.iter())
+                && SortedVecSet::read_from(buf.as_slice()).unwrap() == s1
+        }
+
+        fn prop_serde(s1: SortedVecSet<usize>) -> bool {
+            let json = serde_json::to_string(&s1).unwrap();
+            serde_json::from_str::<SortedVecSet<usize>>(&json).unwrap() == s1
+        }
+
+    }
+}
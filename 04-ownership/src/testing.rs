@@ -0,0 +1,160 @@
+//! Properties that the tests of the ordered sets share.
+//!
+//! The sets have no trait in common, so the properties that call their
+//! methods are macros; each expands to a `bool` saying whether the property
+//! held.
+
+use std::collections::BTreeSet;
+
+/// Checks that `actual` yields the same elements as `expected`, and that
+/// its size hint is honest at every step.
+pub(crate) fn agrees<'a, T, I, J>(mut actual: I, expected: J) -> bool
+    where T: PartialEq + 'a,
+          I: Iterator<Item=&'a T>,
+          J: Iterator<Item=&'a T>
+{
+    let expected: Vec<_> = expected.collect();
+
+    for (i, elem) in expected.iter().enumerate() {
+        let remaining = expected.len() - i;
+        let (lo, hi) = actual.size_hint();
+
+        if lo > remaining || hi.is_some_and(|hi| hi < remaining) {
+            return false;
+        }
+
+        if actual.next() != Some(*elem) {
+            return false;
+        }
+    }
+
+    actual.next().is_none() && actual.next().is_none()
+}
+
+/// Collects the elements of a set into a `BTreeSet`, to model it.
+pub(crate) fn model<'a, T, I>(elems: I) -> BTreeSet<T>
+    where T: Ord + Clone + 'a,
+          I: IntoIterator<Item=&'a T>
+{
+    elems.into_iter().cloned().collect()
+}
+
+/// Checks that applying `op` to a clone of `set` gives `expected`, and
+/// leaves the set well formed.
+pub(crate) fn same_after<S, F, W>(set: &S, op: F, expected: S, well_formed: W) -> bool
+    where S: Clone + PartialEq,
+          F: FnOnce(&mut S),
+          W: Fn(&S) -> bool
+{
+    let mut set = set.clone();
+    op(&mut set);
+    well_formed(&set) && set == expected
+}
+
+/// Checks that the lazy set operations on `$s1` and `$s2` agree with those
+/// of `BTreeSet`.
+macro_rules! lazy_ops_agree {
+    ($s1:expr, $s2:expr) => {{
+        use $crate::testing::{agrees, model};
+
+        let (s1, s2) = (&$s1, &$s2);
+        let (b1, b2) = (model(s1.iter()), model(s2.iter()));
+
+        agrees(s1.iter_intersection(s2), b1.intersection(&b2))
+            && agrees(s1.iter_union(s2), b1.union(&b2))
+            && agrees(s1.iter_difference(s2), b1.difference(&b2))
+            && agrees(s1.iter_symmetric_difference(s2), b1.symmetric_difference(&b2))
+    }};
+}
+
+/// Checks that each in-place operation and assignment operator on `$s1`
+/// and `$s2` agrees with the allocating one, and leaves the result well
+/// formed according to `$well_formed`.
+macro_rules! in_place_agrees {
+    ($s1:expr, $s2:expr, $well_formed:expr) => {{
+        use $crate::testing::same_after as check;
+
+        let (s1, s2, well_formed) = (&$s1, &$s2, $well_formed);
+        let mut appended = s1.clone();
+        let mut emptied = s2.clone();
+        appended.append(&mut emptied);
+
+        check(s1, |set| set.union_with(s2.clone()), s1.union(s2), well_formed)
+            && check(s1, |set| *set |= s2.clone(), s1.union(s2), well_formed)
+            && check(s1, |set| *set |= s2, s1.union(s2), well_formed)
+            && check(s1, |set| set.extend(s2.iter().cloned()), s1.union(s2), well_formed)
+            && check(s1, |set| set.intersect_with(s2), s1.intersection(s2), well_formed)
+            && check(s1, |set| *set &= s2, s1.intersection(s2), well_formed)
+            && check(s1, |set| set.subtract(s2), s1.difference(s2), well_formed)
+            && check(s1, |set| *set -= s2, s1.difference(s2), well_formed)
+            && check(s1, |set| set.symmetric_difference_with(s2.clone()),
+                     s1.symmetric_difference(s2), well_formed)
+            && check(s1, |set| *set ^= s2.clone(), s1.symmetric_difference(s2), well_formed)
+            && check(s1, |set| *set ^= s2, s1.symmetric_difference(s2), well_formed)
+            && well_formed(&appended) && appended == s1.union(s2)
+            && well_formed(&emptied) && emptied.is_empty()
+    }};
+}
+
+/// Checks that moving and seeking a `Cursor` agrees with a position in
+/// `$vec`, a slice of the same elements, and never moves backward.
+macro_rules! cursor_agrees {
+    ($cur:expr, $vec:expr, $elems:expr) => {{
+        let vec: &[_] = $vec;
+        let mut cur = $cur;
+        let mut pos = 0;
+
+        $elems.into_iter().enumerate().all(|(i, elem)| {
+            let found = if i % 3 == 0 {
+                cur.move_next();
+                pos = (pos + 1).min(vec.len());
+                cur.peek() == vec.get(pos)
+            } else {
+                pos += vec[pos ..].partition_point(|e| *e < elem);
+                cur.seek(&elem) == (vec.get(pos) == Some(&elem))
+            };
+
+            found && cur.peek() == vec.get(pos)
+        })
+    }};
+}
+
+/// Checks that every edit through a `CursorMut` agrees with the same edit
+/// to `$vec`, a vector of the same elements, and a position in it.
+macro_rules! cursor_mut_agrees {
+    ($cur:expr, $vec:expr, $ops:expr) => {{
+        let vec: &mut Vec<_> = $vec;
+        let mut cur = $cur;
+        let mut pos = 0;
+
+        $ops.into_iter().all(|(op, elem): (u8, _)| {
+            let agrees = match op % 4 {
+                0 => {
+                    pos += vec[pos ..].partition_point(|e| *e < elem);
+                    cur.seek(&elem) == (vec.get(pos) == Some(&elem))
+                }
+                1 => {
+                    let fits = (pos == 0 || vec[pos - 1] < elem)
+                        && vec.get(pos).is_none_or(|cur| elem < *cur);
+                    if fits {
+                        vec.insert(pos, elem);
+                    }
+                    cur.insert(elem).is_ok() == fits
+                }
+                2 => {
+                    let expected = if pos < vec.len() { Some(vec.remove(pos)) } else { None };
+                    cur.remove_current() == expected
+                }
+                _ => {
+                    pos = (pos + 1).min(vec.len());
+                    cur.move_next();
+                    true
+                }
+            };
+
+            agrees && cur.peek() == vec.get(pos)
+        })
+    }};
+}
+
+pub(crate) use {cursor_agrees, cursor_mut_agrees, in_place_agrees, lazy_ops_agree};
@@ -0,0 +1,1968 @@
+//! Sets, represented as sorted, unrolled linked lists.
+//!
+//! An `UnrolledSet` offers the same operations as a `list_set::Set`, but
+//! each node holds a short sorted vector of up to `NODE_CAPACITY` elements
+//! rather than a single one. That divides the number of allocations and
+//! pointer hops by up to the capacity, and lets a lookup skip a whole node
+//! by comparing against its last element.
+//!
+//! Cursors work as they do for `Set`, but a position is a node and an
+//! index within it, and edits through `CursorMut` split and merge nodes as
+//! `insert` and `remove` do.
+
+#[cfg(feature = "std")]
+use crate::codec::{self, Decode, Encode};
+use crate::list_set::{above, below};
+use crate::merge::{self, Ascending, Tie};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use alloc::vec;
+use core::cmp::Ordering::{self, Less, Equal, Greater};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator};
+use core::mem;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+use core::ops::RangeBounds;
+use core::slice;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+/// The most elements a node holds. A node that grows past this splits in
+/// two.
+pub const NODE_CAPACITY: usize = 32;
+
+/// A node whose removals leave it with fewer elements than this merges
+/// with its successor, if they fit together.
+const MIN_FILL: usize = NODE_CAPACITY / 4;
+
+/// A set of elements of type `T`.
+///
+/// # Example
+///
+/// ```
+/// use ownership::unrolled_set::UnrolledSet;
+///
+/// let mut set = UnrolledSet::new();
+///
+/// set.insert("a");
+/// set.insert("b");
+///
+/// if set.contains(&"a") {
+///     set.insert("c");
+/// }
+/// ```
+pub struct UnrolledSet<T> {
+    head: Link<T>,
+    len:  usize,
+}
+// Invariants:
+//  - Every node holds between 1 and `NODE_CAPACITY` elements.
+//  - Read node by node, the elements are sorted according to <T as Ord>.
+
+type Link<T> = Option<Box<Node<T>>>;
+
+#[derive(Debug)]
+struct Node<T> {
+    elems: Vec<T>,
+    link:  Link<T>,
+}
+
+impl<T> Node<T> {
+    fn new(elems: Vec<T>, link: Link<T>) -> Option<Box<Self>> {
+        Some(Box::new(Node { elems, link }))
+    }
+
+    // Inserts `element` at `index`, splitting the node in two if that
+    // overfills it.
+    fn insert(&mut self, index: usize, element: T) {
+        self.elems.insert(index, element);
+
+        if self.elems.len() > NODE_CAPACITY {
+            let upper = self.elems.split_off(self.elems.len() / 2);
+            self.link = Node::new(upper, self.link.take());
+        }
+    }
+}
+
+// Removes the element at `index` of the node at `link`. A node left empty is
+// unlinked, and one left with fewer than `MIN_FILL` elements merges with its
+// successor, if they fit together.
+fn remove_at<T>(link: &mut Link<T>, index: usize) -> T {
+    let node = link.as_mut().expect("remove_at: no node");
+    let result = node.elems.remove(index);
+
+    if node.elems.is_empty() {
+        *link = node.link.take();
+    } else if node.elems.len() < MIN_FILL {
+        let fits = node.link.as_ref()
+            .is_some_and(|next| node.elems.len() + next.elems.len() <= NODE_CAPACITY);
+
+        if fits {
+            let mut next = node.link.take().unwrap();
+            node.elems.append(&mut next.elems);
+            node.link = next.link.take();
+        }
+    }
+
+    result
+}
+
+impl<T> Drop for UnrolledSet<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+
+        while let Some(mut next) = head.take() {
+            head = next.link.take();
+        }
+    }
+}
+
+impl<T> UnrolledSet<T> {
+    /// Creates a new, empty set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// let mut set = UnrolledSet::new();
+    /// set.insert("hello");
+    /// ```
+    pub fn new() -> Self {
+        UnrolledSet {
+            head: None,
+            len:  0,
+        }
+    }
+
+    /// Returns whether a set is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// let mut set = UnrolledSet::new();
+    /// assert!(set.is_empty());
+    ///
+    /// set.insert(5);
+    /// assert!(!set.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements in the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// let mut set = UnrolledSet::new();
+    /// assert_eq!(0, set.len());
+    ///
+    /// set.insert(5);
+    /// set.insert(6);
+    /// set.insert(5);
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns a borrowing iterator over the elements of the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = UnrolledSet::from_iter(vec![5, 1, 3]);
+    /// let result: Vec<_> = set.iter().collect();
+    ///
+    /// assert_eq!( result, &[&1, &3, &5] );
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+
+    /// Returns the least element of the set, if any.
+    pub fn first(&self) -> Option<&T> {
+        self.head.as_ref().and_then(|node| node.elems.first())
+    }
+
+    /// Returns the greatest element of the set, if any.
+    pub fn last(&self) -> Option<&T> {
+        let mut link = &self.head;
+        let mut last = None;
+
+        while let Some(ref node) = *link {
+            last = node.elems.last();
+            link = &node.link;
+        }
+
+        last
+    }
+
+    /// Removes and returns the least element of the set, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = UnrolledSet::from_iter(vec![2, 1]);
+    ///
+    /// assert_eq!( Some(1), set.pop_first() );
+    /// assert_eq!( Some(2), set.pop_first() );
+    /// assert_eq!( None, set.pop_first() );
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.cursor_front_mut().remove_current()
+    }
+
+    /// Returns the `n`th least element of the set, counting from zero,
+    /// skipping whole nodes on the way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = UnrolledSet::from_iter(0 .. 100);
+    ///
+    /// assert_eq!( Some(&70), set.nth(70) );
+    /// assert_eq!( None, set.nth(100) );
+    /// ```
+    pub fn nth(&self, mut n: usize) -> Option<&T> {
+        for node in Nodes(&self.head) {
+            match node.elems.get(n) {
+                Some(elem) => return Some(elem),
+                None       => n -= node.elems.len(),
+            }
+        }
+
+        None
+    }
+
+    // Returns the number of leading elements that satisfy `pred`, which
+    // must hold of some prefix of the set, and an iterator over the rest.
+    fn split_where<P: FnMut(&T) -> bool>(&self, mut pred: P) -> (usize, Iter<'_, T>) {
+        let mut count = 0;
+        let mut link = &self.head;
+
+        while let Some(ref node) = *link {
+            if node.elems.last().is_some_and(&mut pred) {
+                count += node.elems.len();
+                link = &node.link;
+                continue;
+            }
+
+            let index = node.elems.partition_point(&mut pred);
+            count += index;
+
+            let rest = Iter {
+                elems: node.elems[index ..].iter(),
+                link:  &node.link,
+                len:   self.len - count,
+            };
+            return (count, rest);
+        }
+
+        (count, Iter { elems: [].iter(), link, len: 0 })
+    }
+
+    /// Builds a set from elements that are already strictly ascending,
+    /// filling each node.
+    ///
+    /// The order is not checked. If the elements are out of order or
+    /// repeated, the resulting set will misbehave (though never unsafely).
+    pub fn from_sorted_unchecked<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut result = UnrolledSet::new();
+        let mut iter = iter.into_iter();
+
+        {
+            let mut cur = &mut result.head;
+
+            loop {
+                let mut elems = Vec::with_capacity(NODE_CAPACITY);
+                elems.extend(iter.by_ref().take(NODE_CAPACITY));
+
+                if elems.is_empty() {
+                    break;
+                }
+
+                result.len += elems.len();
+                *cur = Node::new(elems, None);
+                cur  = &mut {cur}.as_mut().unwrap().link;
+            }
+        }
+
+        result
+    }
+
+    /// Returns a cursor at the first element of the set.
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor::new(self)
+    }
+
+    /// Returns a cursor at the first element of the set that can edit the
+    /// set in place.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut::new(self)
+    }
+
+    /// Keeps only the elements that satisfy a predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = UnrolledSet::from_iter(1 .. 10);
+    ///
+    /// set.retain(|elem| elem % 3 == 0);
+    /// assert_eq!(set, UnrolledSet::from_iter(vec![3, 6, 9]));
+    /// ```
+    pub fn retain<P: FnMut(&T) -> bool>(&mut self, mut pred: P) {
+        self.drain_filter(|elem| !pred(elem)).for_each(drop);
+    }
+
+    /// Returns an iterator that removes and returns elements satisfying a
+    /// predicate, leaving the rest in the set.
+    ///
+    /// The set is rebuilt, with full nodes, when the iterator is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set = UnrolledSet::from_iter(1 .. 8);
+    /// let evens: Vec<_> = set.drain_filter(|elem| elem % 2 == 0).collect();
+    ///
+    /// assert_eq!( evens, &[2, 4, 6] );
+    /// assert_eq!( set, UnrolledSet::from_iter(vec![1, 3, 5, 7]) );
+    /// ```
+    pub fn drain_filter<P: FnMut(&T) -> bool>(&mut self, pred: P) -> DrainFilter<'_, T, P> {
+        let rest = mem::take(self).into_iter();
+
+        DrainFilter {
+            kept: Vec::with_capacity(rest.len()),
+            set:  self,
+            rest,
+            pred,
+        }
+    }
+}
+
+impl<T> Default for UnrolledSet<T> {
+    fn default() -> Self {
+        UnrolledSet::new()
+    }
+}
+
+impl<T: Ord> UnrolledSet<T> {
+    /// Checks whether the given set contains the given element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = UnrolledSet::from_iter(vec![3, 5, 4]);
+    ///
+    /// assert!(!set.contains(&2));
+    /// assert!( set.contains(&3));
+    /// assert!( set.contains(&4));
+    /// assert!( set.contains(&5));
+    /// assert!(!set.contains(&6));
+    /// ```
+    pub fn contains(&self, element: &T) -> bool {
+        self.get(element).is_some()
+    }
+
+    /// Returns the element of the set equal to the given one, if any.
+    pub fn get(&self, element: &T) -> Option<&T> {
+        let mut link = &self.head;
+
+        while let Some(ref node) = *link {
+            // Only the first node that doesn't end before `element` can
+            // hold it.
+            if node.elems.last().is_some_and(|last| last >= element) {
+                return node.elems.binary_search(element).ok()
+                    .map(|index| &node.elems[index]);
+            }
+
+            link = &node.link;
+        }
+
+        None
+    }
+
+    /// Builds a set from elements that should be strictly ascending.
+    ///
+    /// Returns `Err` of the first element that is not greater than its
+    /// predecessor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// assert!( UnrolledSet::try_from_sorted(vec![1, 2, 4]).is_ok() );
+    /// assert_eq!( Err(2), UnrolledSet::try_from_sorted(vec![1, 3, 2]) );
+    /// ```
+    pub fn try_from_sorted<I: IntoIterator<Item=T>>(iter: I) -> Result<Self, T> {
+        let mut elems: Vec<T> = Vec::new();
+
+        for elem in iter {
+            if elems.last().is_some_and(|last| *last >= elem) {
+                return Err(elem);
+            }
+
+            elems.push(elem);
+        }
+
+        Ok(UnrolledSet::from_sorted_unchecked(elems))
+    }
+
+    // Returns the link to the node where `element` belongs: the last node
+    // whose first element is not greater than it, or else the first node.
+    fn seek_node(&mut self, element: &T) -> &mut Link<T> {
+        let mut link = &mut self.head;
+
+        while link.as_ref()
+            .and_then(|node| node.link.as_ref())
+            .is_some_and(|next| next.elems[0] <= *element)
+        {
+            link = &mut {link}.as_mut().unwrap().link;
+        }
+
+        link
+    }
+
+    /// Adds the element to the set.
+    ///
+    /// Returns `true` if the set did not previously contain the
+    /// element, and `false` if it did.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// let mut set = UnrolledSet::new();
+    ///
+    /// assert!( set.insert(3));
+    /// assert!( set.insert(5));
+    /// assert!(!set.insert(3));
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn insert(&mut self, element: T) -> bool {
+        self.replace_or_insert(element, false).is_ok()
+    }
+
+    /// Adds the element to the set if absent, or replaces it if
+    /// present.
+    ///
+    /// Returns `Some` of the old element if it was present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// let mut set = UnrolledSet::new();
+    ///
+    /// assert_eq!(None, set.replace(5));
+    /// assert_eq!(Some(5), set.replace(5));
+    /// ```
+    pub fn replace(&mut self, element: T) -> Option<T> {
+        self.replace_or_insert(element, true).err()
+    }
+
+    // Inserts `element` if absent, returning `Ok`. If present, returns
+    // `Err` of whichever of the old and new elements is not kept.
+    fn replace_or_insert(&mut self, element: T, replace: bool) -> Result<(), T> {
+        let link = self.seek_node(&element);
+
+        let node = match link {
+            Some(node) => node,
+            None => {
+                *link = Node::new(vec![element], None);
+                self.len += 1;
+                return Ok(());
+            }
+        };
+
+        match node.elems.binary_search(&element) {
+            Ok(index) if replace => Err(mem::replace(&mut node.elems[index], element)),
+            Ok(_) => Err(element),
+            Err(index) => {
+                node.insert(index, element);
+                self.len += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the given element from the set.
+    ///
+    /// Returns `Some(data)` where `data` was the element, if removed,
+    /// or `None` if the element didn’t exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// let mut set = UnrolledSet::new();
+    ///
+    /// assert_eq!(true,    set.insert(5));
+    /// assert_eq!(Some(5), set.remove(&5));
+    /// assert_eq!(None,    set.remove(&5));
+    /// ```
+    pub fn remove(&mut self, element: &T) -> Option<T> {
+        let link = self.seek_node(element);
+        let index = link.as_ref()?.elems.binary_search(element).ok()?;
+        let result = remove_at(link, index);
+
+        self.len -= 1;
+        Some(result)
+    }
+
+    /// Removes and returns the element equal to the given one, if any.
+    ///
+    /// This is the same as `remove`, under the name that `BTreeSet` uses.
+    pub fn take(&mut self, element: &T) -> Option<T> {
+        self.remove(element)
+    }
+
+    /// Finds the position of the given element in the set.
+    ///
+    /// As with `slice::binary_search`, returns `Ok` of its index if it is
+    /// present, or else `Err` of the index where it would be inserted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = UnrolledSet::from_iter(vec![10, 20, 30]);
+    ///
+    /// assert_eq!( Ok(1), set.position(&20) );
+    /// assert_eq!( Err(1), set.position(&15) );
+    /// assert_eq!( Err(3), set.position(&35) );
+    /// ```
+    pub fn position(&self, element: &T) -> Result<usize, usize> {
+        let (index, mut rest) = self.split_where(|elem| elem < element);
+
+        match rest.next() {
+            Some(elem) if elem == element => Ok(index),
+            _                             => Err(index),
+        }
+    }
+
+    /// Returns an iterator over the elements of the set that lie within
+    /// the given range, in ascending order.
+    ///
+    /// Finding the ends of the range skips whole nodes, so the iterator
+    /// knows its exact length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = UnrolledSet::from_iter(1 .. 10);
+    ///
+    /// assert_eq!( set.range(3 .. 6).collect::<Vec<_>>(), &[&3, &4, &5] );
+    /// assert_eq!( set.range(7 ..).len(), 3 );
+    /// assert_eq!( set.range(..= 2).collect::<Vec<_>>(), &[&1, &2] );
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        let (skipped, iter) = self.split_where(|elem| below(range.start_bound(), elem));
+        let (end, _) = self.split_where(|elem| !above(range.end_bound(), elem));
+
+        Range {
+            iter,
+            len: end.saturating_sub(skipped),
+        }
+    }
+
+    /// Returns whether two sets are disjoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 2]);
+    /// let set2 = UnrolledSet::from_iter(vec![3, 4]);
+    /// let set3 = UnrolledSet::from_iter(vec![1, 3]);
+    ///
+    /// assert!( set1.is_disjoint(&set2));
+    /// assert!(!set1.is_disjoint(&set3));
+    /// ```
+    pub fn is_disjoint(&self, other: &UnrolledSet<T>) -> bool {
+        let mut i = self.iter().peekable();
+        let mut j = other.iter().peekable();
+
+        while let (Some(&a), Some(&b)) = (i.peek(), j.peek()) {
+            match a.cmp(b) {
+                Less    => { i.next(); }
+                Greater => { j.next(); }
+                Equal   => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether `self` is a subset of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![2]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3]);
+    ///
+    /// assert!( set1.is_subset(&set2));
+    /// assert!(!set2.is_subset(&set1));
+    /// ```
+    pub fn is_subset(&self, other: &UnrolledSet<T>) -> bool {
+        let mut j = other.iter().peekable();
+
+        self.len <= other.len && self.iter().all(|a| {
+            while j.next_if(|b| *b < a).is_some() {}
+            j.next_if(|b| *b == a).is_some()
+        })
+    }
+
+    /// Returns whether `self` is a superset of `other`.
+    pub fn is_superset(&self, other: &UnrolledSet<T>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns a lazy iterator over the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_intersection(&set2).collect();
+    /// assert_eq!( result, &[&1, &3] );
+    /// ```
+    pub fn iter_intersection<'a>(&'a self, other: &'a UnrolledSet<T>) -> Intersection<'a, T> {
+        merge::Intersection::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_union(&set2).collect();
+    /// assert_eq!( result, &[&1, &2, &3, &4, &5, &7] );
+    /// ```
+    pub fn iter_union<'a>(&'a self, other: &'a UnrolledSet<T>) -> Union<'a, T> {
+        merge::Union::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_difference(&set2).collect();
+    /// assert_eq!( result, &[&5, &7] );
+    /// ```
+    pub fn iter_difference<'a>(&'a self, other: &'a UnrolledSet<T>) -> Difference<'a, T> {
+        merge::Difference::new(self.iter(), other.iter())
+    }
+
+    /// Returns a lazy iterator over the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// let result: Vec<_> = set1.iter_symmetric_difference(&set2).collect();
+    /// assert_eq!( result, &[&2, &4, &5, &7] );
+    /// ```
+    pub fn iter_symmetric_difference<'a>(&'a self, other: &'a UnrolledSet<T>)
+        -> SymmetricDifference<'a, T>
+    {
+        merge::SymmetricDifference::new(self.iter(), other.iter())
+    }
+
+    /// Adds every element of `other` to `self`, rebuilding the set with
+    /// full nodes.
+    ///
+    /// Where both sets hold equal elements, the one from `self` is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.union_with(set2);
+    /// assert_eq!(set1, UnrolledSet::from_iter(vec![1, 2, 3, 4, 5, 7]));
+    /// ```
+    pub fn union_with(&mut self, other: UnrolledSet<T>) {
+        self.merge_owned(other, Tie::Mine);
+    }
+
+    /// Moves every element of `other` into `self`, leaving `other` empty.
+    ///
+    /// Unlike `union_with`, where both sets hold equal elements, the one
+    /// from `other` replaces the one in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = UnrolledSet::from_iter(vec![1, 3]);
+    /// let mut set2 = UnrolledSet::from_iter(vec![2, 3, 4]);
+    ///
+    /// set1.append(&mut set2);
+    /// assert_eq!(set1, UnrolledSet::from_iter(vec![1, 2, 3, 4]));
+    /// assert!(set2.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut UnrolledSet<T>) {
+        self.merge_owned(mem::take(other), Tie::Theirs);
+    }
+
+    /// Replaces `self` with the symmetric difference of `self` and `other`,
+    /// rebuilding the set with full nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.symmetric_difference_with(set2);
+    /// assert_eq!(set1, UnrolledSet::from_iter(vec![2, 4, 5, 7]));
+    /// ```
+    pub fn symmetric_difference_with(&mut self, other: UnrolledSet<T>) {
+        self.merge_owned(other, Tie::Neither);
+    }
+
+    // Merges the elements of `other` into `self` in a single pass over
+    // both, keeping one, the other or neither of each pair of equal
+    // elements.
+    fn merge_owned(&mut self, other: UnrolledSet<T>, tie: Tie) {
+        let mine = mem::take(self);
+        *self = UnrolledSet::from_sorted_unchecked(merge::merge_owned(mine, other, tie));
+    }
+
+    /// Removes every element of `self` that is not in `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.intersect_with(&set2);
+    /// assert_eq!(set1, UnrolledSet::from_iter(vec![1, 3]));
+    /// ```
+    pub fn intersect_with(&mut self, other: &UnrolledSet<T>) {
+        let mut other = other.iter().peekable();
+
+        self.retain(|elem| {
+            while other.next_if(|b| *b < elem).is_some() {}
+            other.peek() == Some(&elem)
+        });
+    }
+
+    /// Removes every element of `other` from `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// set1.subtract(&set2);
+    /// assert_eq!(set1, UnrolledSet::from_iter(vec![5, 7]));
+    /// ```
+    pub fn subtract(&mut self, other: &UnrolledSet<T>) {
+        let mut other = other.iter().peekable();
+
+        self.retain(|elem| {
+            while other.next_if(|b| *b < elem).is_some() {}
+            other.peek() != Some(&elem)
+        });
+    }
+
+    /// Splits the set in two at `element`, returning everything not less
+    /// than `element`.
+    ///
+    /// At most one node is split; the rest move over whole.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let mut set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    ///
+    /// let set2 = set1.split_off(&4);
+    /// assert_eq!(set1, UnrolledSet::from_iter(vec![1, 3]));
+    /// assert_eq!(set2, UnrolledSet::from_iter(vec![5, 7]));
+    /// ```
+    pub fn split_off(&mut self, element: &T) -> UnrolledSet<T> {
+        let mut kept = 0;
+        let mut link = &mut self.head;
+
+        while link.as_ref().is_some_and(|node| node.elems.last().is_some_and(|last| last < element)) {
+            let node = link.as_mut().unwrap();
+            kept += node.elems.len();
+            link = &mut node.link;
+        }
+
+        let head = match link.as_mut() {
+            None => None,
+            Some(node) => {
+                let index = node.elems.partition_point(|elem| elem < element);
+                kept += index;
+
+                if index == 0 {
+                    link.take()
+                } else {
+                    let upper = node.elems.split_off(index);
+                    Node::new(upper, node.link.take())
+                }
+            }
+        };
+
+        let len = self.len - kept;
+        self.len = kept;
+
+        UnrolledSet { head, len }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Encode> UnrolledSet<T> {
+    /// Writes the set to `writer` as a length followed by the elements in
+    /// ascending order, the same format as `Set::write_to`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set = UnrolledSet::from_iter(vec![3u32, 1, 2]);
+    /// let mut buf = Vec::new();
+    /// set.write_to(&mut buf).unwrap();
+    ///
+    /// assert_eq!( set, UnrolledSet::read_from(buf.as_slice()).unwrap() );
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        codec::encode_len(self.len, &mut writer)?;
+
+        for elem in self {
+            elem.encode(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord + Decode> UnrolledSet<T> {
+    /// Reads a set written by `write_to`.
+    ///
+    /// Fails with `codec::Error::Unsorted` unless the elements are strictly
+    /// ascending.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, codec::Error> {
+        let len = codec::decode_len(&mut reader)?;
+        let mut elems: Vec<T> = Vec::new();
+
+        for _ in 0 .. len {
+            let elem = T::decode(&mut reader)?;
+
+            if elems.last().is_some_and(|last| *last >= elem) {
+                return Err(codec::Error::Unsorted);
+            }
+
+            elems.push(elem);
+        }
+
+        Ok(UnrolledSet::from_sorted_unchecked(elems))
+    }
+}
+
+/// A cursor over the elements of an `UnrolledSet`.
+///
+/// A cursor points either at an element or at the end of the set, and can
+/// only move forward.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::unrolled_set::UnrolledSet;
+/// use std::iter::FromIterator;
+///
+/// let set = UnrolledSet::from_iter(vec![2, 4, 6]);
+/// let mut cur = set.cursor_front();
+///
+/// assert_eq!( Some(&2), cur.peek() );
+/// assert!(!cur.seek(&3) );
+/// assert_eq!( Some(&4), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( Some(&6), cur.peek() );
+/// cur.move_next();
+/// assert_eq!( None, cur.peek() );
+/// ```
+#[derive(Debug)]
+pub struct Cursor<'a, T: 'a> {
+    elems: &'a [T],
+    link:  &'a Link<T>,
+}
+// Invariant: `elems` is the rest of the current node, and is empty only at
+// the end of the set.
+
+impl<'a, T: 'a> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor { ..*self }
+    }
+}
+
+impl<'a, T: 'a> Cursor<'a, T> {
+    fn new(set: &'a UnrolledSet<T>) -> Self {
+        let mut result = Cursor {
+            elems: &[],
+            link:  &set.head,
+        };
+
+        result.next_node();
+        result
+    }
+
+    // Moves to the start of the next node, if any.
+    fn next_node(&mut self) {
+        if let Some(ref node) = *self.link {
+            self.elems = &node.elems;
+            self.link = &node.link;
+        }
+    }
+
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&'a T> {
+        self.elems.first()
+    }
+
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        if let Some((_, rest)) = self.elems.split_first() {
+            self.elems = rest;
+
+            if rest.is_empty() {
+                self.next_node();
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord + 'a> Cursor<'a, T> {
+    /// Moves forward to the first element not less than `element`, and
+    /// returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is. Nodes that end before `element`
+    /// are skipped whole.
+    pub fn seek(&mut self, element: &T) -> bool {
+        while self.link.is_some() && self.elems.last().is_some_and(|last| last < element) {
+            self.elems = &[];
+            self.next_node();
+        }
+
+        let index = self.elems.partition_point(|elem| elem < element);
+        self.elems = &self.elems[index ..];
+        self.peek() == Some(element)
+    }
+}
+
+/// A cursor that can edit an `UnrolledSet` in place.
+///
+/// Like `Cursor`, it points either at an element or at the end of the set,
+/// and can only move forward. Its edits cannot break the ordering of the
+/// set.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::unrolled_set::UnrolledSet;
+/// use std::iter::FromIterator;
+///
+/// let mut set = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+///
+/// {
+///     let mut cur = set.cursor_front_mut();
+///
+///     assert_eq!( Ok(()), cur.insert(0) );
+///     cur.move_next();
+///     assert_eq!( Some(1), cur.remove_current() );
+///     assert_eq!( Err(4), cur.insert(4) );
+///
+///     assert!( cur.seek(&5) );
+///     assert_eq!( Ok(()), cur.insert(4) );
+///     assert_eq!( Some(&4), cur.peek() );
+/// }
+///
+/// assert_eq!( set, UnrolledSet::from_iter(vec![0, 3, 4, 5, 7]) );
+/// ```
+pub struct CursorMut<'a, T: 'a> {
+    prev:  Option<&'a T>,
+    link:  Option<&'a mut Link<T>>,
+    index: usize,
+    len:   &'a mut usize,
+}
+// Invariants:
+//  - `link` is only `None` transiently, inside `next_node`.
+//  - `prev` is the last element of the nodes before the current one.
+//  - `index` is the position of the cursor in the current node. It equals
+//    the length of the node only in the last node, at the end of the set.
+
+impl<'a, T: 'a> CursorMut<'a, T> {
+    fn new(set: &'a mut UnrolledSet<T>) -> Self {
+        CursorMut {
+            prev:  None,
+            link:  Some(&mut set.head),
+            index: 0,
+            len:   &mut set.len,
+        }
+    }
+
+    fn node(&self) -> Option<&Node<T>> {
+        self.link.as_ref()?.as_deref()
+    }
+
+    // Moves to the start of the next node.
+    fn next_node(&mut self) {
+        let link_ptr = self.link.take()
+            .expect("CursorMut::next_node: empty cursor");
+        let Node { ref elems, ref mut link } = **link_ptr.as_mut()
+            .expect("CursorMut::next_node: no node");
+
+        self.prev = elems.last();
+        self.link = Some(link);
+        self.index = 0;
+    }
+
+    // Restores the invariant on `index` after it reaches the end of a node.
+    fn normalize(&mut self) {
+        let at_end_of_node = self.node()
+            .is_some_and(|node| self.index == node.elems.len() && node.link.is_some());
+
+        if at_end_of_node {
+            self.next_node();
+        }
+    }
+
+    /// Returns the element under the cursor, or `None` at the end.
+    pub fn peek(&self) -> Option<&T> {
+        self.node()?.elems.get(self.index)
+    }
+
+    /// Moves to the next element. Does nothing at the end.
+    pub fn move_next(&mut self) {
+        if self.peek().is_some() {
+            self.index += 1;
+            self.normalize();
+        }
+    }
+
+    /// Removes and returns the element under the cursor, leaving the cursor
+    /// at the following element.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.peek()?;
+
+        let link_ptr = self.link.as_mut()
+            .expect("CursorMut::remove_current: empty cursor");
+        let result = remove_at(link_ptr, self.index);
+        *self.len -= 1;
+
+        self.normalize();
+        Some(result)
+    }
+}
+
+// Shows only where the cursor is, not the links it borrows.
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for CursorMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CursorMut")
+            .field("current", &self.peek())
+            .finish()
+    }
+}
+
+impl<'a, T: Ord + 'a> CursorMut<'a, T> {
+    /// Moves forward to the first element not less than `element`, and
+    /// returns whether that element equals it.
+    ///
+    /// The cursor never moves backward, so seeking to an element before
+    /// the cursor leaves it where it is. Nodes that end before `element`
+    /// are skipped whole.
+    pub fn seek(&mut self, element: &T) -> bool {
+        while self.node().is_some_and(|node| {
+            node.link.is_some() && node.elems.last().is_some_and(|last| last < element)
+        }) {
+            self.next_node();
+        }
+
+        if let Some(node) = self.node() {
+            let rest = &node.elems[self.index ..];
+            self.index += rest.partition_point(|elem| elem < element);
+        }
+
+        self.peek() == Some(element)
+    }
+
+    /// Inserts `element` just before the cursor and moves the cursor onto
+    /// it.
+    ///
+    /// Returns `Err(element)`, leaving the set unchanged, unless `element`
+    /// is greater than the previous element and less than the current one.
+    pub fn insert(&mut self, element: T) -> Result<(), T> {
+        let prev = match self.node() {
+            Some(node) if self.index > 0 => Some(&node.elems[self.index - 1]),
+            _                            => self.prev,
+        };
+        let after_prev = prev.is_none_or(|prev| *prev < element);
+        let before_cur = self.peek().is_none_or(|cur| element < *cur);
+
+        if !(after_prev && before_cur) {
+            return Err(element);
+        }
+
+        let link_ptr = self.link.as_mut()
+            .expect("CursorMut::insert: empty cursor");
+
+        match link_ptr.as_mut() {
+            Some(node) => node.insert(self.index, element),
+            None       => **link_ptr = Node::new(vec![element], None),
+        }
+
+        *self.len += 1;
+
+        // If the node split, the new element may have gone to the upper
+        // half.
+        let lower_len = self.node().map_or(0, |node| node.elems.len());
+
+        if self.index >= lower_len {
+            self.index -= lower_len;
+            let index = self.index;
+            self.next_node();
+            self.index = index;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Ord + Clone> UnrolledSet<T> {
+    /// Returns the intersection of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(UnrolledSet::from_iter(vec![1, 3]), set1.intersection(&set2));
+    /// ```
+    pub fn intersection(&self, other: &UnrolledSet<T>) -> Self {
+        self.merge(other, false, true, false)
+    }
+
+    /// Returns the union of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(UnrolledSet::from_iter(vec![1, 2, 3, 4, 5, 7]), set1.union(&set2));
+    /// ```
+    pub fn union(&self, other: &UnrolledSet<T>) -> Self {
+        self.merge(other, true, true, true)
+    }
+
+    /// Returns the difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(UnrolledSet::from_iter(vec![5, 7]), set1.difference(&set2));
+    /// ```
+    pub fn difference(&self, other: &UnrolledSet<T>) -> Self {
+        self.merge(other, true, false, false)
+    }
+
+    /// Returns the symmetric difference of two sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ownership::unrolled_set::UnrolledSet;
+    /// use std::iter::FromIterator;
+    ///
+    /// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+    /// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(UnrolledSet::from_iter(vec![2, 4, 5, 7]),
+    ///            set1.symmetric_difference(&set2));
+    /// ```
+    pub fn symmetric_difference(&self, other: &UnrolledSet<T>) -> Self {
+        self.merge(other, true, false, true)
+    }
+
+    // Merges two sets in one pass, keeping the elements found only on the
+    // left, in both, or only on the right, as requested.
+    fn merge(&self, other: &UnrolledSet<T>, left: bool, both: bool, right: bool) -> Self {
+        UnrolledSet::from_sorted_unchecked(
+            merge::merge_cloned(self.iter(), other.iter(), left, both, right))
+    }
+}
+
+/// Returns the union of two sets, as `UnrolledSet::union`.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::unrolled_set::UnrolledSet;
+/// use std::iter::FromIterator;
+///
+/// let set1 = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+/// let set2 = UnrolledSet::from_iter(vec![1, 2, 3, 4]);
+///
+/// assert_eq!( UnrolledSet::from_iter(vec![1, 2, 3, 4, 5, 7]), &set1 | &set2 );
+/// assert_eq!( UnrolledSet::from_iter(vec![1, 3]),             &set1 & &set2 );
+/// assert_eq!( UnrolledSet::from_iter(vec![5, 7]),             &set1 - &set2 );
+/// assert_eq!( UnrolledSet::from_iter(vec![2, 4, 5, 7]),       &set1 ^ &set2 );
+/// ```
+impl<T: Ord + Clone> BitOr<&UnrolledSet<T>> for &UnrolledSet<T> {
+    type Output = UnrolledSet<T>;
+
+    fn bitor(self, other: &UnrolledSet<T>) -> UnrolledSet<T> {
+        self.union(other)
+    }
+}
+
+/// Returns the intersection of two sets, as `UnrolledSet::intersection`.
+impl<T: Ord + Clone> BitAnd<&UnrolledSet<T>> for &UnrolledSet<T> {
+    type Output = UnrolledSet<T>;
+
+    fn bitand(self, other: &UnrolledSet<T>) -> UnrolledSet<T> {
+        self.intersection(other)
+    }
+}
+
+/// Returns the difference of two sets, as `UnrolledSet::difference`.
+impl<T: Ord + Clone> Sub<&UnrolledSet<T>> for &UnrolledSet<T> {
+    type Output = UnrolledSet<T>;
+
+    fn sub(self, other: &UnrolledSet<T>) -> UnrolledSet<T> {
+        self.difference(other)
+    }
+}
+
+/// Returns the symmetric difference of two sets, as
+/// `UnrolledSet::symmetric_difference`.
+impl<T: Ord + Clone> BitXor<&UnrolledSet<T>> for &UnrolledSet<T> {
+    type Output = UnrolledSet<T>;
+
+    fn bitxor(self, other: &UnrolledSet<T>) -> UnrolledSet<T> {
+        self.symmetric_difference(other)
+    }
+}
+
+/// Adds the elements of `other` in place, as `UnrolledSet::union_with`.
+///
+/// # Example
+///
+/// ```
+/// # use ownership::unrolled_set::UnrolledSet;
+/// use std::iter::FromIterator;
+///
+/// let mut set = UnrolledSet::from_iter(vec![1, 3, 5, 7]);
+///
+/// set |= UnrolledSet::from_iter(vec![2, 4]);
+/// set |= &UnrolledSet::from_iter(vec![8]);
+/// set &= &UnrolledSet::from_iter(vec![1, 2, 3, 4, 5, 8]);
+/// set -= &UnrolledSet::from_iter(vec![1]);
+/// set ^= UnrolledSet::from_iter(vec![4, 6]);
+/// set ^= &UnrolledSet::from_iter(vec![8, 9]);
+///
+/// assert_eq!( UnrolledSet::from_iter(vec![2, 3, 5, 6, 9]), set );
+/// ```
+impl<T: Ord> BitOrAssign<UnrolledSet<T>> for UnrolledSet<T> {
+    fn bitor_assign(&mut self, other: UnrolledSet<T>) {
+        self.union_with(other);
+    }
+}
+
+/// Adds clones of the elements of `other` that are missing from `self`.
+impl<T: Ord + Clone> BitOrAssign<&UnrolledSet<T>> for UnrolledSet<T> {
+    fn bitor_assign(&mut self, other: &UnrolledSet<T>) {
+        let missing = UnrolledSet::from_sorted_unchecked(other.iter_difference(self).cloned());
+        self.union_with(missing);
+    }
+}
+
+/// Keeps only the elements also in `other`, as
+/// `UnrolledSet::intersect_with`.
+impl<T: Ord> BitAndAssign<&UnrolledSet<T>> for UnrolledSet<T> {
+    fn bitand_assign(&mut self, other: &UnrolledSet<T>) {
+        self.intersect_with(other);
+    }
+}
+
+/// Removes the elements of `other` in place, as `UnrolledSet::subtract`.
+impl<T: Ord> SubAssign<&UnrolledSet<T>> for UnrolledSet<T> {
+    fn sub_assign(&mut self, other: &UnrolledSet<T>) {
+        self.subtract(other);
+    }
+}
+
+/// Replaces the set with its symmetric difference with `other`, as
+/// `UnrolledSet::symmetric_difference_with`.
+impl<T: Ord> BitXorAssign<UnrolledSet<T>> for UnrolledSet<T> {
+    fn bitxor_assign(&mut self, other: UnrolledSet<T>) {
+        self.symmetric_difference_with(other);
+    }
+}
+
+/// Replaces the set with its symmetric difference with `other`, cloning the
+/// elements only `other` holds.
+impl<T: Ord + Clone> BitXorAssign<&UnrolledSet<T>> for UnrolledSet<T> {
+    fn bitxor_assign(&mut self, other: &UnrolledSet<T>) {
+        let missing = UnrolledSet::from_sorted_unchecked(other.iter_difference(self).cloned());
+        self.subtract(other);
+        self.union_with(missing);
+    }
+}
+
+/// A lazy iterator over the intersection of two `UnrolledSet`s.
+///
+/// Created by [`UnrolledSet::iter_intersection`].
+pub type Intersection<'a, T> = merge::Intersection<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the union of two `UnrolledSet`s.
+///
+/// Created by [`UnrolledSet::iter_union`].
+pub type Union<'a, T> = merge::Union<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the difference of two `UnrolledSet`s.
+///
+/// Created by [`UnrolledSet::iter_difference`].
+pub type Difference<'a, T> = merge::Difference<'a, T, Iter<'a, T>>;
+
+/// A lazy iterator over the symmetric difference of two `UnrolledSet`s.
+///
+/// Created by [`UnrolledSet::iter_symmetric_difference`].
+pub type SymmetricDifference<'a, T> = merge::SymmetricDifference<'a, T, Iter<'a, T>>;
+
+impl<T: Clone> Clone for UnrolledSet<T> {
+    fn clone(&self) -> Self {
+        let mut result = UnrolledSet::new();
+
+        {
+            let mut cur = &mut result.head;
+
+            for node in Nodes(&self.head) {
+                *cur = Node::new(node.elems.clone(), None);
+                cur  = &mut {cur}.as_mut().unwrap().link;
+            }
+        }
+
+        result.len = self.len;
+        result
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for UnrolledSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the set as `{a, b, c}`, or with `{:#}`, one element per line.
+impl<T: fmt::Display> fmt::Display for UnrolledSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        merge::fmt_set(self, f)
+    }
+}
+
+impl<T: Ord> Ord for UnrolledSet<T> {
+    fn cmp(&self, other: &UnrolledSet<T>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Ord> PartialOrd for UnrolledSet<T> {
+    fn partial_cmp(&self, other: &UnrolledSet<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for UnrolledSet<T> {
+    fn eq(&self, other: &UnrolledSet<T>) -> bool {
+        self.len == other.len && self.cmp(other) == Equal
+    }
+}
+
+impl<T: Ord> Eq for UnrolledSet<T> {}
+
+impl<T: Hash> Hash for UnrolledSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+// Walks the nodes of a list.
+struct Nodes<'a, T: 'a>(&'a Link<T>);
+
+impl<'a, T> Iterator for Nodes<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<&'a Node<T>> {
+        let node = self.0.as_ref()?;
+        self.0 = &node.link;
+        Some(node)
+    }
+}
+
+/// An immutable iterator over the elements of an `UnrolledSet`, in
+/// ascending order.
+#[derive(Debug)]
+pub struct Iter<'a, T: 'a> {
+    elems: slice::Iter<'a, T>,
+    link:  &'a Link<T>,
+    len:   usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(elem) = self.elems.next() {
+                self.len -= 1;
+                return Some(elem);
+            }
+
+            let node = self.link.as_ref()?;
+            self.elems = node.elems.iter();
+            self.link = &node.link;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> Ascending<'a, T> for Iter<'a, T> {}
+
+/// An iterator over the elements of an `UnrolledSet` within a range.
+///
+/// Created by [`UnrolledSet::range`].
+#[derive(Debug)]
+pub struct Range<'a, T: 'a> {
+    iter: Iter<'a, T>,
+    len:  usize,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Range<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> FusedIterator for Range<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a UnrolledSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        Iter {
+            elems: [].iter(),
+            link:  &self.head,
+            len:   self.len,
+        }
+    }
+}
+
+/// An iterator that consumes an `UnrolledSet` as it iterates, in ascending
+/// order.
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    elems: vec::IntoIter<T>,
+    set:   UnrolledSet<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(elem) = self.elems.next() {
+                self.set.len -= 1;
+                return Some(elem);
+            }
+
+            let mut node = self.set.head.take()?;
+            self.set.head = node.link.take();
+            self.elems = mem::take(&mut node.elems).into_iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.set.len, Some(self.set.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.set.len
+    }
+}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for UnrolledSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            elems: Vec::new().into_iter(),
+            set:   self,
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for UnrolledSet<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+
+/// Of equal elements, the first is kept.
+impl<T: Ord> FromIterator<T> for UnrolledSet<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut elems: Vec<T> = iter.into_iter().collect();
+        elems.sort();
+        elems.dedup();
+        UnrolledSet::from_sorted_unchecked(elems)
+    }
+}
+
+/// An iterator that removes the elements of an `UnrolledSet` that satisfy
+/// a predicate. Created by `UnrolledSet::drain_filter`.
+pub struct DrainFilter<'a, T: 'a, P>
+    where P: FnMut(&T) -> bool
+{
+    // The set is empty until the iterator is dropped; the elements kept
+    // so far wait in `kept`.
+    set:  &'a mut UnrolledSet<T>,
+    kept: Vec<T>,
+    rest: IntoIter<T>,
+    pred: P,
+}
+
+impl<'a, T, P> Iterator for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for elem in &mut self.rest {
+            if (self.pred)(&elem) {
+                return Some(elem);
+            }
+
+            self.kept.push(elem);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.rest.len()))
+    }
+}
+
+impl<'a, T, P> Drop for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    fn drop(&mut self) {
+        for _ in &mut *self {}
+        *self.set = UnrolledSet::from_sorted_unchecked(mem::take(&mut self.kept));
+    }
+}
+
+impl<'a, T: fmt::Debug, P> fmt::Debug for DrainFilter<'a, T, P>
+    where P: FnMut(&T) -> bool
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DrainFilter")
+            .field("kept", &self.kept)
+            .field("rest", &self.rest)
+            .finish()
+    }
+}
+
+#[cfg(any(test, feature = "quickcheck"))]
+mod impl_arbitrary_for_unrolled_set {
+    use super::UnrolledSet;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use quickcheck::{Arbitrary, Gen};
+
+    impl<T: Arbitrary + Ord> Arbitrary for UnrolledSet<T> {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            FromIterator::from_iter(Vec::<T>::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item=Self>> {
+            Box::new(Vec::from_iter(UnrolledSet::clone(self))
+                .shrink()
+                .map(FromIterator::from_iter))
+        }
+    }
+}
+
+#[cfg(any(test, feature = "serde"))]
+mod impl_serde_for_unrolled_set {
+    use super::UnrolledSet;
+    use alloc::vec::Vec;
+    use core::iter::FromIterator;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+    impl<T: Serialize> Serialize for UnrolledSet<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.len))?;
+
+            for elem in self {
+                seq.serialize_element(elem)?;
+            }
+
+            seq.end()
+        }
+    }
+
+    // `from_iter` restores the invariant, whatever the order of the input.
+    impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for UnrolledSet<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Vec::deserialize(deserializer).map(UnrolledSet::from_iter)
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::{Nodes, UnrolledSet, NODE_CAPACITY};
+    #[cfg(feature = "std")]
+    use crate::list_set::Set;
+    use crate::testing::{agrees, cursor_agrees, cursor_mut_agrees, in_place_agrees, lazy_ops_agree};
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use std::collections::BTreeSet;
+
+    // Checks the node invariants and the length.
+    fn well_formed(set: &UnrolledSet<u16>) -> bool {
+        Nodes(&set.head).all(|node| (1 ..= NODE_CAPACITY).contains(&node.elems.len()))
+            && set.iter().zip(set.iter().skip(1)).all(|(a, b)| a < b)
+            && set.len() == Nodes(&set.head).map(|node| node.elems.len()).sum::<usize>()
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(u16),
+        Replace(u16),
+        Remove(u16),
+        Contains(u16),
+        DrainFilter(u16),
+    }
+
+    use self::Op::*;
+
+    impl Arbitrary for Op {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            // Enough distinct elements to fill several nodes.
+            let elem = u16::arbitrary(g) % 256;
+
+            match u8::arbitrary(g) % 20 {
+                0 ..= 8   => Insert(elem),
+                9         => Replace(elem),
+                10 ..= 15 => Remove(elem),
+                16 ..= 18 => Contains(elem),
+                _         => DrainFilter(elem % 8 + 2),
+            }
+        }
+    }
+
+    #[test]
+    fn len_iota() {
+        let set: UnrolledSet<usize> = (0 .. 100_000).rev().collect();
+        assert_eq!(100_000, set.len());
+        assert!(set.contains(&99_999));
+    }
+
+    #[test]
+    fn split_and_merge() {
+        let mut set = UnrolledSet::new();
+
+        for elem in (0 .. 1_000).rev() {
+            set.insert(elem);
+            assert!(well_formed(&set));
+        }
+
+        for elem in 0 .. 1_000 {
+            assert_eq!(Some(elem), set.remove(&elem));
+            assert!(well_formed(&set));
+        }
+
+        assert!(set.head.is_none());
+    }
+
+    quickcheck! {
+
+        fn prop_model(start: Vec<u16>, ops: Vec<Op>) -> bool {
+            let mut set: UnrolledSet<u16> = start.iter().map(|e| e % 256).collect();
+            let mut model: BTreeSet<u16> = set.iter().cloned().collect();
+
+            ops.into_iter().all(|op| {
+                let agrees = match op {
+                    Insert(e)   => set.insert(e) == model.insert(e),
+                    Replace(e)  => set.replace(e) == model.replace(e),
+                    Remove(e)   => set.remove(&e) == model.take(&e),
+                    Contains(e) => set.contains(&e) == model.contains(&e),
+                    DrainFilter(m) => {
+                        let drained: Vec<u16> = set.drain_filter(|e| e % m == 0).collect();
+                        let expected: Vec<u16> = model.iter().cloned().filter(|e| e % m == 0).collect();
+                        model.retain(|e| e % m != 0);
+                        drained == expected
+                    }
+                };
+
+                agrees && well_formed(&set) && set.iter().eq(model.iter())
+            })
+        }
+
+        fn prop_ops(s1: UnrolledSet<u16>, s2: UnrolledSet<u16>) -> bool {
+            let b1: BTreeSet<u16> = s1.iter().cloned().collect();
+            let b2: BTreeSet<u16> = s2.iter().cloned().collect();
+
+            s1.intersection(&s2).iter().eq(b1.intersection(&b2))
+                && s1.union(&s2).iter().eq(b1.union(&b2))
+                && s1.difference(&s2).iter().eq(b1.difference(&b2))
+                && s1.symmetric_difference(&s2).iter().eq(b1.symmetric_difference(&b2))
+                && s1.is_disjoint(&s2) == b1.is_disjoint(&b2)
+                && s1.is_subset(&s2) == b1.is_subset(&b2)
+        }
+
+        fn prop_ends(s1: UnrolledSet<u16>) -> bool {
+            s1.first() == s1.iter().next() && s1.last() == s1.iter().last()
+        }
+
+        fn prop_into_iter(s1: UnrolledSet<u16>) -> bool {
+            let expected: Vec<u16> = s1.iter().cloned().collect();
+            let clone = s1.clone();
+
+            well_formed(&clone) && clone == s1 && s1.into_iter().eq(expected)
+        }
+
+        fn prop_lazy_ops(s1: UnrolledSet<u16>, s2: UnrolledSet<u16>) -> bool {
+            lazy_ops_agree!(s1, s2)
+        }
+
+        // The in-place operations agree with the allocating ones, and so do
+        // the halves that `split_off` leaves.
+        fn prop_in_place(v1: Vec<u16>, v2: Vec<u16>, elem: u16) -> bool {
+            let s1: UnrolledSet<u16> = v1.iter().map(|e| e % 256).collect();
+            let s2: UnrolledSet<u16> = v2.iter().map(|e| e % 256).collect();
+            let elem = elem % 256;
+
+            let mut low = s1.clone();
+            let high = low.split_off(&elem);
+
+            in_place_agrees!(s1, s2, well_formed)
+                && well_formed(&low) && well_formed(&high)
+                && low.iter().all(|a| *a < elem) && high.iter().all(|b| *b >= elem)
+                && low.union(&high) == s1
+        }
+
+        fn prop_positional(v1: Vec<u16>, elems: Vec<u16>) -> bool {
+            let mut s1: UnrolledSet<u16> = v1.iter().map(|e| e % 256).collect();
+            let vec: Vec<u16> = s1.iter().cloned().collect();
+
+            let agrees = (0 ..= vec.len()).all(|n| s1.nth(n) == vec.get(n))
+                && elems.iter().map(|e| e % 256).all(|elem| {
+                    s1.position(&elem) == vec.binary_search(&elem)
+                        && s1.get(&elem) == vec.binary_search(&elem).ok().map(|i| &vec[i])
+                });
+
+            agrees && vec.into_iter().all(|elem| s1.pop_first() == Some(elem) && well_formed(&s1))
+                && s1.pop_first().is_none()
+        }
+
+        fn prop_range(v1: Vec<u16>, start: u16, end: u16) -> bool {
+            use std::ops::Bound::*;
+
+            let s1: UnrolledSet<u16> = v1.iter().map(|e| e % 256).collect();
+            let model: BTreeSet<u16> = s1.iter().cloned().collect();
+            let (start, end) = (start % 256, end % 256);
+            let (start, end) = (start.min(end), start.max(end));
+            let bounds = [Included(start), Excluded(start), Unbounded];
+
+            bounds.iter().all(|&lo| {
+                [Included(end), Excluded(end), Unbounded].iter().all(|&hi| {
+                    if lo == Excluded(start) && hi == Excluded(end) && start == end {
+                        // `BTreeSet::range` panics on this empty range.
+                        return s1.range((lo, hi)).next().is_none();
+                    }
+
+                    agrees(s1.range((lo, hi)), model.range((lo, hi)))
+                })
+            })
+        }
+
+        // Seeking with a `Cursor` finds what a binary search of the
+        // elements would, never moving backward.
+        fn prop_cursor(v1: Vec<u16>, elems: Vec<u16>) -> bool {
+            let s1: UnrolledSet<u16> = v1.iter().map(|e| e % 256).collect();
+            let vec: Vec<u16> = s1.iter().cloned().collect();
+
+            cursor_agrees!(s1.cursor_front(), &vec, elems.iter().map(|e| e % 256))
+        }
+
+        // Every edit through a `CursorMut` agrees with the same edit to a
+        // vector and a position in it.
+        fn prop_cursor_mut(v1: Vec<u16>, ops: Vec<(u8, u16)>) -> bool {
+            let mut set: UnrolledSet<u16> = v1.iter().map(|e| e % 256).collect();
+            let mut vec: Vec<u16> = set.iter().cloned().collect();
+            let ops: Vec<(u8, u16)> = ops.into_iter().map(|(op, e)| (op, e % 256)).collect();
+
+            cursor_mut_agrees!(set.cursor_front_mut(), &mut vec, ops)
+                && well_formed(&set) && set.iter().eq(vec.iter())
+        }
+
+        #[cfg(feature = "std")]
+        fn prop_write_read(s1: UnrolledSet<u16>) -> bool {
+            let mut buf = Vec::new();
+            s1.write_to(&mut buf).unwrap();
+
+            Set::<u16>::read_from(buf.as_slice()).unwrap().iter().eq(s1.iter())
+                && UnrolledSet::read_from(buf.as_slice()).unwrap() == s1
+        }
+
+        fn prop_serde(s1: UnrolledSet<u16>) -> bool {
+            let json = serde_json::to_string(&s1).unwrap();
+            let s2 = serde_json::from_str::<UnrolledSet<u16>>(&json).unwrap();
+
+            well_formed(&s2) && s2 == s1
+        }
+
+    }
+}
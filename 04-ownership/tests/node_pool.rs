@@ -0,0 +1,120 @@
+//! Counts the allocations `Set` makes, with and without a node pool.
+
+use ownership::list_set::Set;
+
+mod common;
+use common::{allocations_during, Counting};
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// Inserts and then removes a batch of short-lived elements, `rounds` times.
+fn churn(set: &mut Set<u32>, rounds: u32) {
+    for round in 0 .. rounds {
+        for elem in 0 .. 64 {
+            set.insert(1_000 + round * 64 + elem);
+        }
+
+        for elem in 0 .. 64 {
+            set.remove(&(1_000 + round * 64 + elem));
+        }
+    }
+}
+
+#[test]
+fn unpooled_allocates_every_node() {
+    let mut set = Set::from_sorted_unchecked(0 .. 100);
+
+    assert_eq!( 640, allocations_during(|| churn(&mut set, 10)) );
+    assert_eq!( 0, set.pool_stats().hits );
+    assert_eq!( 100, set.len() );
+}
+
+#[test]
+fn pooled_reuses_nodes() {
+    let mut set = Set::with_node_pool(64);
+    set.extend(0 .. 100);
+
+    // The first round fills the pool.
+    churn(&mut set, 1);
+
+    assert_eq!( 0, allocations_during(|| churn(&mut set, 10)) );
+    assert_eq!( 640, set.pool_stats().hits );
+    assert_eq!( 64, set.pool_stats().pooled );
+    assert_eq!( 100, set.len() );
+}
+
+#[test]
+fn small_pool_falls_back_to_allocator() {
+    let mut set = Set::with_node_pool(16);
+    churn(&mut set, 1);
+
+    assert_eq!( 480, allocations_during(|| churn(&mut set, 10)) );
+    assert_eq!( 160, set.pool_stats().hits );
+    assert_eq!( 16, set.pool_stats().pooled );
+}
+
+#[test]
+fn drain_filter_and_cursor_recycle() {
+    let mut set = Set::with_node_pool(64);
+    set.extend(0 .. 32);
+
+    set.drain_filter(|elem| elem % 2 == 0).for_each(drop);
+    {
+        let mut cur = set.cursor_front_mut();
+        cur.remove_current();
+        cur.remove_current();
+    }
+    assert_eq!( 18, set.pool_stats().pooled );
+
+    assert_eq!( 0, allocations_during(|| (100 .. 118).for_each(|elem| { set.insert(elem); })) );
+    assert_eq!( 1, allocations_during(|| { set.insert(200); }) );
+}
+
+#[test]
+fn shrink_pool_frees_nodes() {
+    let mut set = Set::with_node_pool(64);
+    churn(&mut set, 1);
+    assert_eq!( 64, set.pool_stats().pooled );
+
+    set.shrink_pool();
+    assert_eq!( 0, set.pool_stats().pooled );
+    assert_eq!( 64, allocations_during(|| churn(&mut set, 1)) );
+    assert_eq!( 64, set.pool_stats().capacity );
+}
+
+#[test]
+fn in_place_operations_recycle_nodes_of_other() {
+    let mut set = Set::with_node_pool(64);
+    set.extend(0 .. 8);
+
+    set.append(&mut Set::from_sorted_unchecked(0 .. 2));
+    set.union_with(Set::from_sorted_unchecked(2 .. 4));
+    set.symmetric_difference_with(Set::from_sorted_unchecked(4 .. 6));
+    assert_eq!( 8, set.pool_stats().pooled );
+
+    assert_eq!( 0, allocations_during(|| (4 .. 6).for_each(|elem| { set.insert(elem); })) );
+    assert_eq!( 8, set.len() );
+}
+
+// The rounds of `benches/node_pool.rs`: once the first round has filled a
+// pool as large as the batch, the pooled set never allocates, while the
+// plain one allocates a node per element.
+#[test]
+fn bench_rounds() {
+    for &batch in &[16, 64, 256] {
+        let mut plain = Set::from_sorted_unchecked(0 .. 100);
+        let mut pooled = Set::with_node_pool(batch);
+        pooled.extend(0 .. 100);
+
+        let round = |set: &mut Set<usize>| {
+            (0 .. batch).for_each(|elem| { set.insert(1_000 + elem); });
+            (0 .. batch).for_each(|elem| { set.remove(&(1_000 + elem)); });
+        };
+        round(&mut plain);
+        round(&mut pooled);
+
+        assert_eq!( batch, allocations_during(|| round(&mut plain)) );
+        assert_eq!( 0, allocations_during(|| round(&mut pooled)) );
+    }
+}